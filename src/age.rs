@@ -0,0 +1,68 @@
+use crate::{DurationBreakdown, TimedeltaFlags};
+
+/// The age in whole calendar years, months and days, so a month is not 30 days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Age {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    /// The next birthday, or `today` on the birthday
+    pub next_birthday: chrono::NaiveDate,
+    /// The days until `next_birthday`
+    pub days_until_birthday: i64,
+}
+
+impl Age {
+    /// The age at `today` of someone born at `birth`, none when `birth` is after `today`,
+    /// the birthday of February 29 is on February 28 when the year is not a leap year
+    pub fn new(birth: chrono::NaiveDate, today: chrono::NaiveDate) -> Option<Self> {
+        if birth > today {
+            return None;
+        }
+
+        let utc = |date: chrono::NaiveDate| {
+            date.and_time(chrono::NaiveTime::MIN)
+                .and_utc()
+                .fixed_offset()
+        };
+        let breakdown =
+            DurationBreakdown::between(utc(birth), utc(today), TimedeltaFlags::new("YMD"));
+
+        let on_birthday = breakdown.months == 0 && breakdown.days == 0;
+        let years = breakdown.years + i64::from(!on_birthday);
+        let next_birthday =
+            birth.checked_add_months(chrono::Months::new(u32::try_from(years * 12).ok()?))?;
+
+        Some(Self {
+            years: breakdown.years,
+            months: breakdown.months,
+            days: breakdown.days,
+            next_birthday,
+            days_until_birthday: (next_birthday - today).num_days(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn age() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let age = Age::new(date(1990, 5, 17), date(2024, 5, 16)).unwrap();
+    assert_eq!((age.years, age.months, age.days), (33, 11, 29));
+    assert_eq!(age.next_birthday, date(2024, 5, 17));
+    assert_eq!(age.days_until_birthday, 1);
+
+    let age = Age::new(date(1990, 5, 17), date(2024, 5, 17)).unwrap();
+    assert_eq!((age.years, age.months, age.days), (34, 0, 0));
+    assert_eq!(age.days_until_birthday, 0);
+
+    let age = Age::new(date(2000, 1, 31), date(2024, 3, 1)).unwrap();
+    assert_eq!((age.years, age.months, age.days), (24, 1, 1));
+
+    let age = Age::new(date(2000, 2, 29), date(2023, 3, 1)).unwrap();
+    assert_eq!((age.years, age.months, age.days), (23, 0, 1));
+    assert_eq!(age.next_birthday, date(2024, 2, 29));
+
+    assert_eq!(Age::new(date(2000, 2, 29), date(1999, 3, 1)), None);
+}
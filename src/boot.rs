@@ -0,0 +1,93 @@
+use crate::Error;
+
+/// When the system was booted, from `/proc/uptime` on Linux
+/// and from `sysctl kern.boottime` on macOS and the BSDs
+pub fn boot_time() -> Result<chrono::DateTime<chrono::Utc>, Error> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let uptime = std::fs::read_to_string("/proc/uptime")
+            .map_err(|err| Error::NoBootTime(format!("/proc/uptime: {err}")))?;
+        let uptime = parse_proc_uptime(&uptime)
+            .ok_or_else(|| Error::NoBootTime(format!("/proc/uptime is `{}`", uptime.trim())))?;
+        Ok(chrono::Utc::now() - uptime)
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        let sysctl = std::process::Command::new("sysctl")
+            .args(["-n", "kern.boottime"])
+            .output()
+            .map_err(|err| Error::NoBootTime(format!("sysctl: {err}")))?;
+        let boottime = String::from_utf8_lossy(&sysctl.stdout);
+        parse_boottime(&boottime)
+            .ok_or_else(|| Error::NoBootTime(format!("kern.boottime is `{}`", boottime.trim())))
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    Err(Error::NoBootTime("not supported on this system".to_owned()))
+}
+
+/// The first number of `/proc/uptime`, the seconds since the boot like `8896.54 6953.76`
+#[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+fn parse_proc_uptime(uptime: &str) -> Option<chrono::TimeDelta> {
+    let seconds: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0)
+        .then(|| chrono::TimeDelta::milliseconds((seconds * 1000.0).round() as i64))
+}
+
+/// Like `{ sec = 1700000000, usec = 250000 } Tue Nov 14 22:13:20 2023`
+#[cfg_attr(
+    not(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )),
+    allow(dead_code)
+)]
+fn parse_boottime(boottime: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let field = |name: &str| -> Option<i64> {
+        let (_, rest) = boottime.split_once(name)?;
+        let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        rest[..end].parse().ok()
+    };
+    let seconds = field("sec")?;
+    let micros = field("usec").unwrap_or(0);
+    chrono::DateTime::from_timestamp(seconds, u32::try_from(micros).ok()?.checked_mul(1000)?)
+}
+
+#[cfg(test)]
+#[test]
+fn boot() {
+    assert_eq!(
+        parse_proc_uptime("8896.54 6953.76\n"),
+        Some(chrono::TimeDelta::milliseconds(8_896_540))
+    );
+    assert_eq!(parse_proc_uptime(""), None);
+    assert_eq!(parse_proc_uptime("-1 0"), None);
+
+    assert_eq!(
+        parse_boottime("{ sec = 1700000000, usec = 250000 } Tue Nov 14 22:13:20 2023\n")
+            .map(|boot| boot.to_rfc3339()),
+        Some("2023-11-14T22:13:20.250+00:00".to_owned())
+    );
+    assert_eq!(parse_boottime("kern.boottime: unknown oid"), None);
+}
@@ -0,0 +1,193 @@
+use chrono::Datelike;
+
+use crate::{Error, Holiday};
+
+/// The working days, every day that is not in the weekend and is not a holiday
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusinessCalendar {
+    pub weekend: Vec<chrono::Weekday>,
+    pub holidays: std::collections::BTreeSet<chrono::NaiveDate>,
+    /// The holidays that are in every year, like the ones of a country
+    pub rules: Vec<Holiday>,
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        Self {
+            weekend: vec![chrono::Weekday::Sat, chrono::Weekday::Sun],
+            holidays: Default::default(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl BusinessCalendar {
+    pub fn is_business_day(&self, date: chrono::NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.is_holiday(date)
+    }
+
+    pub fn is_holiday(&self, date: chrono::NaiveDate) -> bool {
+        self.holidays.contains(&date)
+            || self
+                .rules
+                .iter()
+                .any(|holiday| holiday.date(date.year()) == Some(date))
+    }
+
+    /// The days of a week that are not in the weekend
+    fn week(&self) -> i64 {
+        let mut weekday = chrono::Weekday::Mon;
+        let mut days = 0;
+        for _ in 0..7 {
+            days += i64::from(!self.weekend.contains(&weekday));
+            weekday = weekday.succ();
+        }
+        days
+    }
+
+    /// The business days from `from` to the day before `to`,
+    /// so Monday to Friday is 4, it is negative when `to` is before `from`
+    pub fn count(&self, from: chrono::NaiveDate, to: chrono::NaiveDate) -> i64 {
+        if to < from {
+            return -self.count(to, from);
+        }
+
+        let days = (to - from).num_days();
+        let mut count = days / 7 * self.week();
+        let rest = from + chrono::Days::new((days / 7 * 7) as u64);
+        count += rest
+            .iter_days()
+            .take_while(|date| *date < to)
+            .filter(|date| !self.weekend.contains(&date.weekday()))
+            .count() as i64;
+
+        let rules = (from.year()..=to.year()).flat_map(|year| {
+            self.rules
+                .iter()
+                .filter_map(move |holiday| holiday.date(year))
+        });
+        let holidays = self
+            .holidays
+            .iter()
+            .copied()
+            .chain(rules)
+            .filter(|date| (from..to).contains(date) && !self.weekend.contains(&date.weekday()))
+            .collect::<std::collections::BTreeSet<_>>();
+        count - holidays.len() as i64
+    }
+
+    /// The day that is `days` business days after `date`, or before it when `days` is negative,
+    /// none when every day is in the weekend
+    pub fn add(&self, date: chrono::NaiveDate, days: i64) -> Option<chrono::NaiveDate> {
+        if days != 0 && self.week() == 0 {
+            return None;
+        }
+
+        let mut date = date;
+        for _ in 0..days.unsigned_abs() {
+            date = if days > 0 {
+                date.succ_opt()?
+            } else {
+                date.pred_opt()?
+            };
+            while !self.is_business_day(date) {
+                date = if days > 0 {
+                    date.succ_opt()?
+                } else {
+                    date.pred_opt()?
+                };
+            }
+        }
+        Some(date)
+    }
+}
+
+/// Parses the holidays from a file with a `YYYY-MM-DD` date on every line, `#` starts a comment,
+/// or from an iCal file where the `DTSTART` of every event is a holiday
+pub fn parse_holidays(
+    holidays: &str,
+) -> Result<std::collections::BTreeSet<chrono::NaiveDate>, Error> {
+    let is_ical = holidays
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"));
+
+    let mut dates = std::collections::BTreeSet::new();
+    for (i, line) in holidays.lines().enumerate() {
+        let line = line.trim();
+        let date = if is_ical {
+            // `DTSTART;VALUE=DATE:20251225` or `DTSTART:20251225T000000Z`
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            if name.split(';').next() != Some("DTSTART") {
+                continue;
+            }
+            value
+                .get(..8)
+                .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        } else {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            chrono::NaiveDate::parse_from_str(line, "%Y-%m-%d").ok()
+        };
+
+        let Some(date) = date else {
+            return Err(Error::InvalidHolidays(format!("line {}: {line}", i + 1)));
+        };
+        dates.insert(date);
+    }
+    Ok(dates)
+}
+
+#[cfg(test)]
+#[test]
+fn business_calendar() {
+    let date = |m, d| chrono::NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut calendar = BusinessCalendar::default();
+
+    // Monday to Friday
+    assert_eq!(calendar.count(date(12, 1), date(12, 5)), 4);
+    assert_eq!(calendar.count(date(12, 1), date(12, 29)), 20);
+    assert_eq!(calendar.count(date(12, 6), date(12, 8)), 0);
+    assert_eq!(calendar.count(date(12, 29), date(12, 1)), -20);
+    assert_eq!(calendar.add(date(12, 5), 1), Some(date(12, 8)));
+
+    calendar.holidays =
+        parse_holidays("# Christmas\n2025-12-25\n\n2025-12-26 # Boxing Day\n2025-12-27\n").unwrap();
+    assert_eq!(calendar.count(date(12, 22), date(12, 29)), 3);
+    assert_eq!(calendar.add(date(12, 24), 1), Some(date(12, 29)));
+    assert_eq!(calendar.add(date(12, 29), -2), Some(date(12, 23)));
+    assert_eq!(calendar.add(date(12, 27), 0), Some(date(12, 27)));
+
+    calendar.holidays.clear();
+    calendar.rules = vec![Holiday {
+        name: "Christmas Day",
+        rule: crate::HolidayRule::Fixed { month: 12, day: 25 },
+        since: 2000,
+    }];
+    assert_eq!(calendar.count(date(12, 22), date(12, 29)), 4);
+    assert_eq!(
+        calendar.count(date(1, 1), date(1, 1) + chrono::Days::new(365 * 3)),
+        3 * 261 - 2
+    );
+
+    let ical = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251225\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20260101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    assert_eq!(
+        parse_holidays(ical)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [
+            date(12, 25),
+            chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+        ]
+    );
+    assert_eq!(
+        parse_holidays("2025-12-25\n25.12.2025"),
+        Err(Error::InvalidHolidays("line 2: 25.12.2025".to_owned()))
+    );
+}
@@ -0,0 +1,256 @@
+use chrono::Datelike;
+
+use crate::{Lang, WeekStart};
+
+/// The weekdays of a week from `week_start`
+pub fn weekdays(week_start: WeekStart) -> [chrono::Weekday; 7] {
+    let mut weekday = week_start.weekday();
+    std::array::from_fn(|_| {
+        let day = weekday;
+        weekday = weekday.succ();
+        day
+    })
+}
+
+/// The weeks of a month, from the week of the first day to the week of the last day,
+/// so the first and last weeks have days of the months around it
+pub fn month_weeks(
+    year: i32,
+    month: u32,
+    week_start: WeekStart,
+) -> Option<Vec<[chrono::NaiveDate; 7]>> {
+    let first = chrono::NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last = first
+        .checked_add_months(chrono::Months::new(1))?
+        .pred_opt()?;
+
+    let mut weeks = Vec::with_capacity(6);
+    let mut start = week_start.start_of_week(first);
+    while start <= last {
+        let mut day = start;
+        weeks.push(std::array::from_fn(|_| {
+            let res = day;
+            day = day.succ_opt().unwrap_or(day);
+            res
+        }));
+        start = start.checked_add_days(chrono::Days::new(7))?;
+    }
+    Some(weeks)
+}
+
+/// How `render_month` draws a month
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarStyle {
+    pub week_start: WeekStart,
+    pub lang: Lang,
+    /// The ISO week number is on the left of every week
+    pub week_numbers: bool,
+    /// Around a highlighted day, like the escape codes of reverse video
+    pub highlight: (&'static str, &'static str),
+}
+
+impl CalendarStyle {
+    /// The width of every line of `render_month`
+    pub fn width(&self) -> usize {
+        if self.week_numbers {
+            23
+        } else {
+            20
+        }
+    }
+
+    /// The weekdays in two letters, like `Mo Tu We Th Fr Sa Su`
+    pub fn header(&self) -> String {
+        weekdays(self.week_start)
+            .map(|weekday| {
+                let name: String = self.lang.weekday(weekday, true).chars().take(2).collect();
+                format!("{name:>2}")
+            })
+            .join(" ")
+    }
+}
+
+/// A month like `cal` with the title, the weekdays and a line for every week,
+/// the lines are padded to `CalendarStyle::width` so months can be put side by side
+pub fn render_month(
+    year: i32,
+    month: u32,
+    style: &CalendarStyle,
+    highlighted: &[chrono::NaiveDate],
+) -> Option<Vec<String>> {
+    let title = format!("{} {year}", style.lang.month(month));
+    month_lines(year, month, &title, style, highlighted)
+}
+
+fn month_lines(
+    year: i32,
+    month: u32,
+    title: &str,
+    style: &CalendarStyle,
+    highlighted: &[chrono::NaiveDate],
+) -> Option<Vec<String>> {
+    let weeks = month_weeks(year, month, style.week_start)?;
+    let width = style.width();
+    let gutter = if style.week_numbers { "   " } else { "" };
+
+    let mut lines = vec![
+        format!("{gutter}{title:^20}"),
+        format!("{gutter}{}", style.header()),
+    ];
+    for week in weeks {
+        let mut line = String::with_capacity(width);
+        if style.week_numbers {
+            let monday = week
+                .iter()
+                .find(|day| day.weekday() == chrono::Weekday::Mon)?;
+            line.push_str(&format!("{:>2} ", monday.iso_week().week()));
+        }
+        for (i, day) in week.iter().enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            if day.month() != month {
+                line.push_str("  ");
+            } else if highlighted.contains(day) {
+                let (start, end) = style.highlight;
+                line.push_str(&format!("{start}{:>2}{end}", day.day()));
+            } else {
+                line.push_str(&format!("{:>2}", day.day()));
+            }
+        }
+        lines.push(line);
+    }
+    Some(lines)
+}
+
+/// The months of `year` in rows of `columns` months like `cal -y`, with the year on top,
+/// the titles are only the names of the months
+pub fn render_year(
+    year: i32,
+    columns: usize,
+    style: &CalendarStyle,
+    highlighted: &[chrono::NaiveDate],
+) -> Option<Vec<String>> {
+    let columns = columns.clamp(1, 12);
+    let width = style.width();
+    // the title, the weekdays and at most 6 weeks
+    let height = 8;
+
+    let months = (1..=12)
+        .map(|month| {
+            let mut lines = month_lines(year, month, style.lang.month(month), style, highlighted)?;
+            lines.resize(height, " ".repeat(width));
+            Some(lines)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let title = year.to_string();
+    let mut lines = vec![
+        format!("{title:^0$}", columns * width + (columns - 1) * 2),
+        String::new(),
+    ];
+    for (i, row) in months.chunks(columns).enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+        for line in 0..height {
+            let line = row
+                .iter()
+                .map(|month| month[line].as_str())
+                .collect::<Vec<_>>()
+                .join("  ");
+            lines.push(line);
+        }
+    }
+    Some(lines)
+}
+
+#[cfg(test)]
+#[test]
+fn month() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let weeks = month_weeks(2025, 2, WeekStart::Mon).unwrap();
+    assert_eq!(weeks.len(), 5);
+    assert_eq!(weeks[0][0], date(2025, 1, 27));
+    assert_eq!(weeks[4][6], date(2025, 3, 2));
+    assert!(
+        weeks
+            .iter()
+            .flatten()
+            .filter(|day| day.month() == 2)
+            .count()
+            == 28
+    );
+
+    // starts on a Sunday so it is 4 whole weeks in the US
+    let weeks = month_weeks(2026, 2, WeekStart::Sun).unwrap();
+    assert_eq!(weeks.len(), 4);
+    assert_eq!(weeks[0][0], date(2026, 2, 1));
+    assert_eq!(month_weeks(2026, 2, WeekStart::Mon).unwrap().len(), 5);
+    assert_eq!(month_weeks(2025, 13, WeekStart::Mon), None);
+
+    assert_eq!(weekdays(WeekStart::Sun)[1], chrono::Weekday::Mon);
+}
+
+#[cfg(test)]
+#[test]
+fn render() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let style = CalendarStyle {
+        highlight: ("[", "]"),
+        ..Default::default()
+    };
+    assert_eq!(
+        render_month(2025, 2, &style, &[date(2025, 2, 14)]).unwrap(),
+        [
+            "   February 2025    ",
+            "Mo Tu We Th Fr Sa Su",
+            "                1  2",
+            " 3  4  5  6  7  8  9",
+            "10 11 12 13 [14] 15 16",
+            "17 18 19 20 21 22 23",
+            "24 25 26 27 28      ",
+        ]
+    );
+
+    let style = CalendarStyle {
+        week_start: WeekStart::Sun,
+        lang: Lang::De,
+        week_numbers: true,
+        ..Default::default()
+    };
+    let lines = render_month(2026, 1, &style, &[]).unwrap();
+    assert_eq!(lines[1], "   So Mo Di Mi Do Fr Sa");
+    assert_eq!(lines[2], " 1              1  2  3");
+    assert_eq!(lines[3], " 2  4  5  6  7  8  9 10");
+    assert!(lines
+        .iter()
+        .all(|line| line.chars().count() == style.width()));
+}
+
+#[cfg(test)]
+#[test]
+fn year() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let style = CalendarStyle {
+        week_numbers: true,
+        highlight: ("<", ">"),
+        ..Default::default()
+    };
+    let lines = render_year(2025, 3, &style, &[date(2025, 12, 25)]).unwrap();
+    // the year, a blank line and 4 rows of 8 lines with blank lines between them
+    assert_eq!(lines.len(), 2 + 4 * 8 + 3);
+    assert_eq!(lines[0].trim(), "2025");
+    assert_eq!(
+        lines[2].trim_end(),
+        "         January                  February                  March"
+    );
+    assert_eq!(&lines[4][..23], " 1        1  2  3  4  5");
+    assert!(lines[4 + 3 * 9..].iter().any(|line| line.contains("<25>")));
+    assert!(lines[2..10]
+        .iter()
+        .all(|line| line.chars().count() == 3 * 23 + 2 * 2));
+}
@@ -0,0 +1,32 @@
+use crate::Error;
+
+/// Sets the clock of the system to `date` with `settimeofday`, this needs root
+/// or the `CAP_SYS_TIME` capability
+pub fn set_system_time(date: chrono::DateTime<chrono::Utc>) -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        let time = libc::timeval {
+            tv_sec: date.timestamp() as _,
+            tv_usec: date.timestamp_subsec_micros() as _,
+        };
+        // SAFETY: `time` is a valid timeval and the time zone is null
+        if unsafe { libc::settimeofday(&time, std::ptr::null()) } != 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(Error::SetClockFailed(match err.kind() {
+                std::io::ErrorKind::PermissionDenied => {
+                    format!("{err}, it should run as root")
+                }
+                _ => err.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = date;
+        Err(Error::SetClockFailed(
+            "not supported on this system".to_owned(),
+        ))
+    }
+}
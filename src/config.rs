@@ -0,0 +1,155 @@
+use crate::Error;
+
+/// The defaults from `~/.config/timeman/config.toml`, the cli flags override them
+///
+/// ```toml
+/// format = "%Y-%m-%d %H:%M"
+/// offset = "Europe/Bucharest"
+/// duration_flags = "Dhms"
+/// preety = true
+/// zones = ["Europe/London", "Asia/Tokyo"]
+/// week_start = "sun"
+/// fiscal_start = 10
+/// working_hours = "9-17"
+///
+/// [profiles]
+/// log = "%d/%b/%Y:%T %z"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The default of `-f`, a format or a preset
+    pub format: Option<String>,
+    /// The default of `-o`, an offset or a time zone name
+    pub offset: Option<String>,
+    /// The default duration flags of `since`, `until` and `sub`
+    pub duration_flags: Option<String>,
+    /// Always print the durations like `-p`
+    pub preety: bool,
+    /// The default of `--week-start`
+    pub week_start: Option<crate::WeekStart>,
+    /// Always count the leap seconds like `--leap-seconds`
+    pub leap_seconds: bool,
+    /// The default of `--start-month` of `fiscal`, the month that starts the fiscal year
+    pub fiscal_start: Option<u32>,
+    /// The zones of `now` when there is no `--zone`, like a world clock, and of `meet`
+    pub zones: Vec<String>,
+    /// The default of `--hours` of `ics free` and `meet`, like "9-17" or "09:00-17:30"
+    pub working_hours: Option<String>,
+    /// Formats that can be used like `-f @log`
+    pub profiles: std::collections::BTreeMap<String, String>,
+}
+
+impl Config {
+    /// `$TIMEMAN_CONFIG`, or `timeman/config.toml` in `$XDG_CONFIG_HOME` or `~/.config`
+    pub fn path() -> Option<std::path::PathBuf> {
+        if let Some(path) = std::env::var_os("TIMEMAN_CONFIG") {
+            return Some(path.into());
+        }
+
+        let dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config"))
+            })?;
+        Some(dir.join("timeman").join("config.toml"))
+    }
+
+    /// `timeman/history` in `$XDG_STATE_HOME` or `~/.local/state`, the lines of `repl`
+    pub fn history_path() -> Option<std::path::PathBuf> {
+        let dir = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .map(|home| std::path::Path::new(&home).join(".local").join("state"))
+            })?;
+        Some(dir.join("timeman").join("history"))
+    }
+
+    /// `timeman/stopwatches.json` in `$XDG_DATA_HOME` or `~/.local/share`, the state of `stopwatch`
+    pub fn stopwatches_path() -> Option<std::path::PathBuf> {
+        Some(data_dir()?.join("timeman").join("stopwatches.json"))
+    }
+
+    /// `timeman/track.json` in `$XDG_DATA_HOME` or `~/.local/share`, the entries of `track`
+    pub fn track_path() -> Option<std::path::PathBuf> {
+        Some(data_dir()?.join("timeman").join("track.json"))
+    }
+
+    /// `timeman/leap-seconds.list` in `$XDG_DATA_HOME` or `~/.local/share`, a newer
+    /// `leap-seconds.list` of the IERS than the bundled one
+    pub fn leap_seconds_path() -> Option<std::path::PathBuf> {
+        Some(data_dir()?.join("timeman").join("leap-seconds.list"))
+    }
+
+    /// Reads the config from `path()`, when there is no file it is the default
+    pub fn load() -> Result<Self, Error> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(config) => config.parse(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::InvalidConfig(format!("{}: {err}", path.display()))),
+        }
+    }
+}
+
+impl Config {
+    /// When `format` is `@name` it is the format of the profile, else it is `format`
+    pub fn resolve_format<'a>(&'a self, format: &'a str) -> Result<&'a str, Error> {
+        let Some(name) = format.strip_prefix('@') else {
+            return Ok(format);
+        };
+
+        self.profiles
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| Error::NoProfile(name.to_owned()))
+    }
+}
+
+/// `$XDG_DATA_HOME` or `~/.local/share`
+fn data_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| std::path::Path::new(&home).join(".local").join("share"))
+        })
+}
+
+impl std::str::FromStr for Config {
+    type Err = Error;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        toml::from_str(config).map_err(|err| Error::InvalidConfig(err.message().to_owned()))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn config() {
+    let config = "format = \"%Y-%m-%d %H:%M\"\noffset = \"+03:00\"\npreety = true"
+        .parse::<Config>()
+        .unwrap();
+    assert_eq!(config.format.as_deref(), Some("%Y-%m-%d %H:%M"));
+    assert_eq!(config.offset.as_deref(), Some("+03:00"));
+    assert_eq!(config.duration_flags, None);
+    assert!(config.preety);
+
+    assert_eq!("".parse::<Config>(), Ok(Config::default()));
+    assert!("formta = \"%s\"".parse::<Config>().is_err());
+
+    let config = "[profiles]\nshort = \"%d %b\"".parse::<Config>().unwrap();
+    assert_eq!(config.resolve_format("@short"), Ok("%d %b"));
+    assert_eq!(config.resolve_format("%+"), Ok("%+"));
+    assert_eq!(
+        config.resolve_format("@log"),
+        Err(Error::NoProfile("log".to_owned()))
+    );
+}
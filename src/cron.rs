@@ -0,0 +1,485 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, Timelike};
+
+use crate::{Error, Lang, Zone};
+
+/// A value, a range or a step of a cron field, `*/15` is `0-59/15`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    start: u32,
+    end: u32,
+    step: u32,
+    /// It was `*` or `*/n`
+    all: bool,
+}
+
+/// A field of the cron expression with the values that it matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Field {
+    items: Vec<Item>,
+    /// Bit `n` is set when `n` matches
+    values: u64,
+}
+
+impl Field {
+    fn matches(&self, value: u32) -> bool {
+        self.values & (1 << value) != 0
+    }
+
+    fn is_all(&self) -> bool {
+        self.items.iter().all(|item| item.all && item.step == 1)
+    }
+
+    /// It starts with `*` like `*/2`, so it doesn't restrict the days like cron
+    fn is_star(&self) -> bool {
+        self.items.first().is_some_and(|item| item.all)
+    }
+
+    /// The only value, like the `2` of `0 2 * * *`
+    fn single(&self) -> Option<u32> {
+        match self.items.as_slice() {
+            [item] if item.start == item.end && !item.all => Some(item.start),
+            _ => None,
+        }
+    }
+}
+
+/// The name, the range and the names of the values of a field
+struct FieldKind {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE: FieldKind = FieldKind {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: FieldKind = FieldKind {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY: FieldKind = FieldKind {
+    name: "day-of-month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: FieldKind = FieldKind {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+/// `7` is Sunday too
+const WEEKDAY: FieldKind = FieldKind {
+    name: "day-of-week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+impl FieldKind {
+    fn value(&self, str: &str) -> Result<u32, String> {
+        let lower = str.to_lowercase();
+        if let Some(i) = self.names.iter().position(|name| *name == lower) {
+            return Ok(i as u32 + if self.names.len() == 12 { 1 } else { 0 });
+        }
+        match str.parse::<u32>() {
+            Ok(value) if (self.min..=self.max).contains(&value) => Ok(value),
+            _ => Err(format!(
+                "`{str}` is not a value of the {} from {} to {}",
+                self.name, self.min, self.max
+            )),
+        }
+    }
+
+    fn parse(&self, field: &str) -> Result<Field, String> {
+        let mut items = Vec::new();
+        let mut values = 0;
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step = step
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| format!("`{step}` is not a step of the {}", self.name))?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+            let (start, end, all) = if range == "*" {
+                (self.min, self.max, true)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (self.value(start)?, self.value(end)?, false)
+            } else {
+                let value = self.value(range)?;
+                // `5/15` is from 5 to the end every 15
+                (value, if step > 1 { self.max } else { value }, false)
+            };
+            if start > end {
+                return Err(format!(
+                    "`{range}` is a reversed range of the {}",
+                    self.name
+                ));
+            }
+
+            let mut value = start;
+            while value <= end {
+                values |= 1 << value;
+                value += step;
+            }
+            items.push(Item {
+                start,
+                end,
+                step,
+                all,
+            });
+        }
+        if self.max == 7 && values & (1 << 7) != 0 {
+            values |= 1;
+        }
+        Ok(Field { items, values })
+    }
+}
+
+/// A cron schedule like `*/15 2 * * 1-5` with the minutes, hours, days of the month,
+/// months and weekdays, or a macro like `@daily`
+///
+/// Like cron, when both the day of the month and the weekday are not `*`
+/// a day matches when either matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    minutes: Field,
+    hours: Field,
+    days: Field,
+    months: Field,
+    weekdays: Field,
+}
+
+impl FromStr for Cron {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expanded = match s.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            s => s,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return Err(Error::InvalidCron(format!(
+                "it has {} fields and not 5, minute hour day-of-month month day-of-week",
+                fields.len()
+            )));
+        };
+        let parse = |kind: &FieldKind, field| kind.parse(field).map_err(Error::InvalidCron);
+        Ok(Self {
+            minutes: parse(&MINUTE, minutes)?,
+            hours: parse(&HOUR, hours)?,
+            days: parse(&DAY, days)?,
+            months: parse(&MONTH, months)?,
+            weekdays: parse(&WEEKDAY, weekdays)?,
+        })
+    }
+}
+
+impl Cron {
+    fn matches_day(&self, date: chrono::NaiveDate) -> bool {
+        let day = self.days.matches(date.day());
+        let weekday = self.weekdays.matches(date.weekday().num_days_from_sunday());
+        match (self.days.is_star(), self.weekdays.is_star()) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// The wall clock time after `local` that matches, or before it when not `forward`
+    fn step(&self, local: chrono::NaiveDateTime, forward: bool) -> Option<chrono::NaiveDateTime> {
+        let minute = chrono::TimeDelta::minutes(1);
+        let mut time = if forward {
+            local.checked_add_signed(minute)?
+        } else {
+            local.checked_sub_signed(minute)?
+        };
+        // the days repeat at least every 400 years, a schedule like `0 0 30 2 *` never matches
+        let limit = time.year() + if forward { 400 } else { -400 };
+
+        while time.year() != limit {
+            let date = time.date();
+            if !self.months.matches(date.month()) {
+                let first = date.with_day(1)?;
+                time = if forward {
+                    first
+                        .checked_add_months(chrono::Months::new(1))?
+                        .and_time(Default::default())
+                } else {
+                    first
+                        .and_time(Default::default())
+                        .checked_sub_signed(minute)?
+                };
+            } else if !self.matches_day(date) {
+                time = if forward {
+                    date.succ_opt()?.and_time(Default::default())
+                } else {
+                    date.and_time(Default::default())
+                        .checked_sub_signed(minute)?
+                };
+            } else if !self.hours.matches(time.hour()) {
+                let hour = time.with_minute(0)?;
+                time = if forward {
+                    hour.checked_add_signed(chrono::TimeDelta::hours(1))?
+                } else {
+                    hour.checked_sub_signed(minute)?
+                };
+            } else if !self.minutes.matches(time.minute()) {
+                time = if forward {
+                    time.checked_add_signed(minute)?
+                } else {
+                    time.checked_sub_signed(minute)?
+                };
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    /// The times after `from` in the wall clock of `zone`, or before it and going back
+    /// when not `forward`, a wall clock time skipped by DST doesn't fire
+    /// and one that is repeated fires once
+    pub fn times(
+        &self,
+        from: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        forward: bool,
+    ) -> CronTimes<'_> {
+        let local = zone.from_utc_datetime(&from.naive_utc()).naive_local();
+        let mut local = local
+            .with_second(0)
+            .and_then(|local| local.with_nanosecond(0));
+        // the minute of `from` can be before it when it has seconds
+        if !forward {
+            local = local.and_then(|local| local.checked_add_signed(chrono::TimeDelta::minutes(1)));
+        }
+        CronTimes {
+            cron: self,
+            zone,
+            from,
+            local,
+            forward,
+        }
+    }
+
+    /// The schedule in words, like "At every 15th minute past hour 2 on Monday through Friday"
+    pub fn explain(&self) -> String {
+        let mut res = String::from("At ");
+        match (self.minutes.single(), self.hours.single()) {
+            (Some(minute), Some(hour)) => res.push_str(&format!("{hour:02}:{minute:02}")),
+            (_, hour) => {
+                res.push_str(&describe(&self.minutes, &MINUTE));
+                if hour.is_some() || !self.hours.is_all() {
+                    res.push_str(" past ");
+                    res.push_str(&describe(&self.hours, &HOUR));
+                }
+            }
+        }
+
+        match (self.days.is_all(), self.weekdays.is_all()) {
+            (true, true) => {}
+            (false, true) => res.push_str(&format!(" on {}", describe(&self.days, &DAY))),
+            (true, false) => {
+                res.push_str(&format!(" on {}", describe(&self.weekdays, &WEEKDAY)));
+            }
+            (false, false) => res.push_str(&format!(
+                " on {} or on {}",
+                describe(&self.days, &DAY),
+                describe(&self.weekdays, &WEEKDAY)
+            )),
+        }
+        if !self.months.is_all() {
+            res.push_str(&format!(" in {}", describe(&self.months, &MONTH)));
+        }
+        res
+    }
+}
+
+/// `1st`, `2nd`, `3rd`, `4th`, `11th`, `21st`
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// A field like "minute 5", "every 15th minute" or "Monday through Friday"
+fn describe(field: &Field, kind: &FieldKind) -> String {
+    let name = |value: u32| match kind.names.len() {
+        12 => Lang::En.month(value).to_owned(),
+        7 => {
+            let weekday = chrono::Weekday::try_from((value as u8 + 6) % 7).unwrap();
+            Lang::En.weekday(weekday, false).to_owned()
+        }
+        _ => format!("{} {value}", kind.name),
+    };
+    let unit = kind.name;
+    let items: Vec<String> = field
+        .items
+        .iter()
+        .map(|item| match (item.all, item.start == item.end, item.step) {
+            (true, _, 1) => format!("every {unit}"),
+            (true, _, step) => format!("every {} {unit}", ordinal(step)),
+            (false, true, _) => name(item.start),
+            (false, false, 1) if kind.names.is_empty() => {
+                format!("every {unit} from {} through {}", item.start, item.end)
+            }
+            (false, false, 1) => format!("{} through {}", name(item.start), name(item.end)),
+            (false, false, step) => format!(
+                "every {} {unit} from {} through {}",
+                ordinal(step),
+                name(item.start),
+                name(item.end)
+            ),
+        })
+        .collect();
+    match items.as_slice() {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// The times of `Cron::times`
+#[derive(Debug, Clone)]
+pub struct CronTimes<'a> {
+    cron: &'a Cron,
+    zone: Zone,
+    from: chrono::DateTime<chrono::FixedOffset>,
+    local: Option<chrono::NaiveDateTime>,
+    forward: bool,
+}
+
+impl Iterator for CronTimes<'_> {
+    type Item = chrono::DateTime<chrono::FixedOffset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let local = self.cron.step(self.local?, self.forward);
+            self.local = local;
+            let Some(date) = self.zone.from_local_datetime(&local?).earliest() else {
+                continue;
+            };
+            let after = if self.forward {
+                date > self.from
+            } else {
+                date < self.from
+            };
+            if after {
+                self.from = date;
+                return Some(date);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn cron() {
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+    let times = |cron: &str, from, forward, n| {
+        Cron::from_str(cron)
+            .unwrap()
+            .times(date(from), zone, forward)
+            .take(n)
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+
+    // 2025-01-03 is a Friday
+    assert_eq!(
+        times("*/15 2 * * 1-5", "2025-01-03T02:40:00+00:00", true, 3),
+        [
+            "2025-01-03T02:45:00+00:00",
+            "2025-01-06T02:00:00+00:00",
+            "2025-01-06T02:15:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("*/15 2 * * 1-5", "2025-01-06T02:00:30+00:00", false, 2),
+        ["2025-01-06T02:00:00+00:00", "2025-01-03T02:45:00+00:00"]
+    );
+    assert_eq!(
+        times("0 0 29 2 *", "2025-01-01T00:00:00+00:00", true, 1),
+        ["2028-02-29T00:00:00+00:00"]
+    );
+    // the day of the month or friday
+    assert_eq!(
+        times("0 12 13 * fri", "2025-06-01T00:00:00+00:00", true, 3),
+        [
+            "2025-06-06T12:00:00+00:00",
+            "2025-06-13T12:00:00+00:00",
+            "2025-06-20T12:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("0 0 30 2 *", "2025-01-01T00:00:00+00:00", true, 1).len(),
+        0
+    );
+
+    // 03:30 is skipped on 2025-03-30 in Bucharest and is there twice on 2025-10-26
+    let bucharest = Zone::Named(chrono_tz::Europe::Bucharest);
+    let dst = |cron: &str, from| {
+        Cron::from_str(cron)
+            .unwrap()
+            .times(date(from), bucharest, true)
+            .take(2)
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        dst("30 3 * * *", "2025-03-29T12:00:00+00:00"),
+        ["2025-03-31T03:30:00+03:00", "2025-04-01T03:30:00+03:00"]
+    );
+    assert_eq!(
+        dst("30 2 * * *", "2025-03-29T12:00:00+00:00"),
+        ["2025-03-30T02:30:00+02:00", "2025-03-31T02:30:00+03:00"]
+    );
+    assert_eq!(
+        dst("30 3 * * *", "2025-10-26T00:00:00+00:00"),
+        ["2025-10-26T03:30:00+03:00", "2025-10-27T03:30:00+02:00"]
+    );
+
+    let explain = |cron: &str| Cron::from_str(cron).unwrap().explain();
+    assert_eq!(
+        explain("*/15 2 * * 1-5"),
+        "At every 15th minute past hour 2 on Monday through Friday"
+    );
+    assert_eq!(explain("@daily"), "At 00:00");
+    assert_eq!(
+        explain("0,30 9-17 1 jan,jul *"),
+        "At minute 0 and minute 30 past every hour from 9 through 17 on day-of-month 1 in January and July"
+    );
+    assert_eq!(explain("5 * * * 0"), "At minute 5 on Sunday");
+
+    assert!(Cron::from_str("60 * * * *").is_err());
+    assert!(Cron::from_str("* * * *").is_err());
+    assert!(Cron::from_str("5-1 * * * *").is_err());
+    assert!(Cron::from_str("*/0 * * * *").is_err());
+}
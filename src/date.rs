@@ -0,0 +1,213 @@
+use chrono::TimeZone;
+
+use crate::{round_timedelta, Error, Rounding, Zone};
+
+/// The formats that are tried by `parse_date_auto` in order, the name is reported when one matched
+pub const AUTO_FORMATS: &[(&str, &str)] = &[
+    ("rfc3339", "%+"),
+    ("rfc2822", "%a, %d %b %Y %T %z"),
+    ("rfc2822", "%d %b %Y %T %z"),
+    ("unix", "%s"),
+    ("iso8601", "%Y-%m-%dT%H:%M:%S%.f"),
+    ("iso8601", "%Y-%m-%dT%H:%M"),
+    ("datetime", "%Y-%m-%d %H:%M:%S%.f %z"),
+    ("datetime", "%Y-%m-%d %H:%M:%S%.f"),
+    ("datetime", "%Y-%m-%d %H:%M"),
+    ("date", "%Y-%m-%d"),
+    ("weekdate", "%G-W%V-%u"),
+    ("weekdate", "%G-W%V"),
+    ("clf", "%d/%b/%Y:%H:%M:%S %z"),
+    ("ctime", "%a %b %e %H:%M:%S %Y"),
+    ("dotted", "%d.%m.%Y %H:%M:%S"),
+    ("dotted", "%d.%m.%Y"),
+];
+
+/// Parses `date` with `format`, the format needs to have an offset in it like `%z`
+pub fn parse_date(
+    format: &[chrono::format::Item],
+    date: &str,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let mut parsed = chrono::format::Parsed::new();
+    chrono::format::parse(&mut parsed, date, format.iter()).map_err(Error::Format)?;
+    let offset = parsed.to_fixed_offset().map_err(|_| Error::NoOffset)?;
+
+    let time = parsed
+        .to_naive_datetime_with_offset(0)
+        .map_err(|_| Error::InvalidDate)?;
+
+    let chrono::LocalResult::Single(time) = offset.from_local_datetime(&time) else {
+        return Err(Error::AmbiguousDate);
+    };
+
+    Ok(time)
+}
+
+/// Like `parse_date` but a missing offset is taken from `zone`,
+/// a missing time is the midnight and a week without a day (`2024-W17`) is the Monday
+pub fn parse_date_lenient(
+    format: &[chrono::format::Item],
+    date: &str,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let mut parsed = chrono::format::Parsed::new();
+    chrono::format::parse(&mut parsed, date, format.iter()).map_err(Error::Format)?;
+    resolve_lenient(parsed, zone)
+}
+
+/// Like `parse_date_lenient` but `date` only has to start with the date,
+/// returns the date and the text after it
+pub fn parse_date_prefix<'a>(
+    format: &[chrono::format::Item],
+    date: &'a str,
+    zone: Zone,
+) -> Result<(chrono::DateTime<chrono::FixedOffset>, &'a str), Error> {
+    let mut parsed = chrono::format::Parsed::new();
+    let rest = chrono::format::parse_and_remainder(&mut parsed, date, format.iter())
+        .map_err(Error::Format)?;
+    Ok((resolve_lenient(parsed, zone)?, rest))
+}
+
+fn resolve_lenient(
+    mut parsed: chrono::format::Parsed,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    if parsed.isoweek().is_some() && parsed.weekday().is_none() {
+        parsed
+            .set_weekday(chrono::Weekday::Mon)
+            .map_err(Error::Format)?;
+    }
+
+    if parsed.timestamp().is_none() {
+        if parsed.hour_div_12().is_none() && parsed.hour_mod_12().is_none() {
+            parsed.set_hour(0).map_err(Error::Format)?;
+        }
+        if parsed.minute().is_none() {
+            parsed.set_minute(0).map_err(Error::Format)?;
+        }
+    }
+
+    let time = parsed
+        .to_naive_datetime_with_offset(0)
+        .map_err(|_| Error::InvalidDate)?;
+
+    if let Ok(offset) = parsed.to_fixed_offset() {
+        offset.from_local_datetime(&time).single()
+    } else if parsed.timestamp().is_some() {
+        Some(zone.from_utc_datetime(&time))
+    } else {
+        zone.from_local_datetime(&time).single()
+    }
+    .ok_or(Error::AmbiguousDate)
+}
+
+/// Tries `format` and then every one of the `AUTO_FORMATS` with `parse_date_lenient`
+///
+/// Returns the name and the format of the auto format that matched,
+/// or `None` if `format` matched
+#[allow(clippy::type_complexity)]
+pub fn parse_date_auto(
+    format: &[chrono::format::Item],
+    date: &str,
+    zone: Zone,
+) -> Result<
+    (
+        chrono::DateTime<chrono::FixedOffset>,
+        Option<(&'static str, &'static str)>,
+    ),
+    Error,
+> {
+    if let Ok(date) = parse_date_lenient(format, date, zone) {
+        return Ok((date, None));
+    }
+
+    for (name, auto_format) in AUTO_FORMATS {
+        let Ok(items) = chrono::format::strftime::StrftimeItems::new(auto_format).parse() else {
+            continue;
+        };
+        if let Ok(date) = parse_date_lenient(&items, date, zone) {
+            return Ok((date, Some((name, auto_format))));
+        }
+    }
+
+    Err(Error::NoAutoFormat)
+}
+
+/// Snaps `date` to a multiple of `unit` in the wall clock of `zone`,
+/// so `P1D` is the midnight and `PT15M` is the quarter of the hour
+pub fn round_date(
+    date: chrono::DateTime<chrono::FixedOffset>,
+    unit: chrono::TimeDelta,
+    rounding: Rounding,
+    zone: Zone,
+) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let local = zone.from_utc_datetime(&date.naive_utc()).naive_local();
+    let since = local - chrono::NaiveDateTime::UNIX_EPOCH;
+    let local = chrono::NaiveDateTime::UNIX_EPOCH
+        .checked_add_signed(round_timedelta(since, unit, rounding)?)?;
+    zone.resolve_local(&local)
+}
+
+#[cfg(test)]
+#[test]
+fn weekdate() {
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let items = |format| {
+        chrono::format::strftime::StrftimeItems::new(format)
+            .parse()
+            .unwrap()
+    };
+    let monday = chrono::Utc
+        .with_ymd_and_hms(2024, 4, 22, 0, 0, 0)
+        .unwrap()
+        .fixed_offset();
+
+    assert_eq!(
+        parse_date_lenient(&items("%G-W%V-%u"), "2024-W17-1", utc),
+        Ok(monday)
+    );
+    assert_eq!(
+        parse_date_lenient(&items("%G-W%V"), "2024-W17", utc),
+        Ok(monday)
+    );
+    assert_eq!(
+        monday
+            .format_with_items(items("%G-W%V-%u").iter())
+            .to_string(),
+        "2024-W17-1"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn round() {
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(5 * 3600 + 1800).unwrap());
+    let at = |h, m, s| {
+        chrono::FixedOffset::east_opt(5 * 3600 + 1800)
+            .unwrap()
+            .with_ymd_and_hms(2024, 4, 22, h, m, s)
+            .unwrap()
+    };
+    let minutes = chrono::TimeDelta::minutes(5);
+
+    assert_eq!(
+        round_date(at(10, 7, 30), minutes, Rounding::Nearest, zone),
+        Some(at(10, 10, 0))
+    );
+    assert_eq!(
+        round_date(at(10, 7, 30), minutes, Rounding::Floor, zone),
+        Some(at(10, 5, 0))
+    );
+    assert_eq!(
+        round_date(at(10, 5, 0), minutes, Rounding::Ceil, zone),
+        Some(at(10, 5, 0))
+    );
+    assert_eq!(
+        round_date(
+            at(10, 5, 0),
+            chrono::TimeDelta::days(1),
+            Rounding::Floor,
+            zone
+        ),
+        Some(at(0, 0, 0))
+    );
+}
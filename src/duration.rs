@@ -0,0 +1,1825 @@
+use std::{fmt::Write, str::FromStr};
+
+use chrono::{Datelike, Offset};
+
+use crate::{Error, Lang, Zone};
+
+/// Which units are used when a duration is turned in to a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimedeltaFlags(u8);
+
+impl core::ops::BitOr<Self> for TimedeltaFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl Default for TimedeltaFlags {
+    fn default() -> Self {
+        Self::SECOND | Self::NANOS
+    }
+}
+
+impl TimedeltaFlags {
+    pub const YEAR: Self = Self(1 << 0);
+    pub const MONTH: Self = Self(1 << 1);
+    pub const WEEK: Self = Self(1 << 2);
+    pub const DAY: Self = Self(1 << 3);
+    pub const HOUR: Self = Self(1 << 4);
+    pub const MINUTE: Self = Self(1 << 5);
+    pub const SECOND: Self = Self(1 << 6);
+    pub const NANOS: Self = Self(1 << 7);
+
+    /// Every unit from the biggest to the smallest
+    pub const UNITS: [Self; 8] = [
+        Self::YEAR,
+        Self::MONTH,
+        Self::WEEK,
+        Self::DAY,
+        Self::HOUR,
+        Self::MINUTE,
+        Self::SECOND,
+        Self::NANOS,
+    ];
+
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> Self {
+        Self::YEAR
+            | Self::MONTH
+            | Self::WEEK
+            | Self::DAY
+            | Self::HOUR
+            | Self::MINUTE
+            | Self::SECOND
+            | Self::NANOS
+    }
+
+    pub fn contains(self, rhs: Self) -> bool {
+        self.0 & rhs.0 == rhs.0
+    }
+
+    pub fn remove(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+
+    /// From the flags like "YMDhmsn", see `tm help-duration`,
+    /// the letters that are not flags are left out, `from_str` fails on them
+    pub fn new(str: &str) -> Self {
+        str.chars()
+            .filter_map(Self::from_char)
+            .fold(Self::empty(), |out, flag| out | flag)
+    }
+
+    /// The flag of one of the letters of `new`
+    pub fn from_char(char: char) -> Option<Self> {
+        match char {
+            'Y' => Some(Self::YEAR),
+            'M' => Some(Self::MONTH),
+            'W' => Some(Self::WEEK),
+            'D' => Some(Self::DAY),
+            'h' => Some(Self::HOUR),
+            'm' => Some(Self::MINUTE),
+            's' => Some(Self::SECOND),
+            'n' => Some(Self::NANOS),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for TimedeltaFlags {
+    type Err = Error;
+
+    /// Parses the flags like `YMDhmsn`, `all`, a range like `D..s` that is every unit
+    /// from `D` to `s`, and after a `-` the flags that are removed, like `all-n`
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let invalid = |err: String| Error::InvalidDurationFlags(format!("{err} in \"{str}\""));
+        let terms = |str: &str| -> Result<Self, Error> {
+            let mut flags = Self::empty();
+            let mut rest = str;
+            while !rest.is_empty() {
+                if let Some(after) = rest.strip_prefix("all") {
+                    flags = flags | Self::all();
+                    rest = after;
+                    continue;
+                }
+
+                let mut chars = rest.chars();
+                let char = chars.next().unwrap_or_default();
+                let flag = Self::from_char(char)
+                    .ok_or_else(|| invalid(format!("`{char}` is not a flag")))?;
+                rest = chars.as_str();
+
+                let Some(after) = rest.strip_prefix("..") else {
+                    flags = flags | flag;
+                    continue;
+                };
+                let mut chars = after.chars();
+                let end = chars
+                    .next()
+                    .ok_or_else(|| invalid(format!("`{char}..` has no end")))?;
+                let end_flag = Self::from_char(end)
+                    .ok_or_else(|| invalid(format!("`{end}` is not a flag")))?;
+                rest = chars.as_str();
+
+                let index = |flag| Self::UNITS.iter().position(|unit| *unit == flag);
+                let (Some(from), Some(to)) = (index(flag), index(end_flag)) else {
+                    unreachable!("every flag of `from_char` is in `UNITS`");
+                };
+                if from > to {
+                    return Err(invalid(format!(
+                        "`{char}..{end}` should be from the bigger unit to the smaller one"
+                    )));
+                }
+                for unit in &Self::UNITS[from..=to] {
+                    flags = flags | *unit;
+                }
+            }
+            Ok(flags)
+        };
+
+        let (add, remove) = str.split_once('-').unwrap_or((str, ""));
+        let flags = terms(add)?;
+        let remove = terms(remove)?;
+        let flags = flags.remove(remove);
+        if flags == Self::empty() {
+            return Err(invalid("there are no flags".to_owned()));
+        }
+        Ok(flags)
+    }
+}
+
+pub const YEAR_IN_SECONDS: i64 = 31_536_000;
+pub const MONTH_IN_SECONDS: i64 = YEAR_IN_SECONDS / 12;
+pub const WEAK_IN_SECONDS: i64 = 604800;
+pub const DAY_IN_SECONDS: i64 = 86400;
+pub const HOUR_IN_SECONDS: i64 = 3600;
+pub const MINUTE_IN_SECONDS: i64 = 60;
+
+/// The units of `relative` and `approximate` from the biggest, with their seconds
+const UNITS: [(&str, TimedeltaFlags, i64); 7] = [
+    ("year", TimedeltaFlags::YEAR, YEAR_IN_SECONDS),
+    ("month", TimedeltaFlags::MONTH, MONTH_IN_SECONDS),
+    ("week", TimedeltaFlags::WEEK, WEAK_IN_SECONDS),
+    ("day", TimedeltaFlags::DAY, DAY_IN_SECONDS),
+    ("hour", TimedeltaFlags::HOUR, HOUR_IN_SECONDS),
+    ("minute", TimedeltaFlags::MINUTE, MINUTE_IN_SECONDS),
+    ("second", TimedeltaFlags::SECOND, 1),
+];
+
+/// A duration split in to the units that are in `flags`,
+/// the units that are not in `flags` are carried to the next smaller unit
+///
+/// Is built only from `TimeDelta::num_seconds` and `TimeDelta::subsec_nanos`
+/// so every unit is the absolute value and the sign is in `negative`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationBreakdown {
+    pub negative: bool,
+    pub years: i64,
+    pub months: i64,
+    pub weeks: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+    pub nanos: u32,
+    pub flags: TimedeltaFlags,
+    /// The digits after the `.` of the seconds, all of them without the zeros at the end when none
+    pub precision: Option<u8>,
+}
+
+impl DurationBreakdown {
+    pub fn new(timedelta: chrono::TimeDelta, flags: TimedeltaFlags) -> Self {
+        let mut seconds = timedelta.num_seconds().abs();
+        let mut take = |flag: TimedeltaFlags, unit: i64| {
+            if !flags.contains(flag) {
+                return 0;
+            }
+            let value = seconds / unit;
+            seconds -= value * unit;
+            value
+        };
+
+        Self {
+            negative: timedelta < chrono::TimeDelta::zero(),
+            years: take(TimedeltaFlags::YEAR, YEAR_IN_SECONDS),
+            months: take(TimedeltaFlags::MONTH, MONTH_IN_SECONDS),
+            weeks: take(TimedeltaFlags::WEEK, WEAK_IN_SECONDS),
+            days: take(TimedeltaFlags::DAY, DAY_IN_SECONDS),
+            hours: take(TimedeltaFlags::HOUR, HOUR_IN_SECONDS),
+            minutes: take(TimedeltaFlags::MINUTE, MINUTE_IN_SECONDS),
+            seconds: take(TimedeltaFlags::SECOND, 1),
+            nanos: timedelta.subsec_nanos().unsigned_abs(),
+            flags,
+            precision: None,
+        }
+    }
+
+    /// Like `new(to - from, flags)` but the years and months are walked in the calendar,
+    /// so they have the real length, the smaller units are from the rest
+    ///
+    /// Both ends are in the offset of `from` while walking
+    pub fn between(
+        from: chrono::DateTime<chrono::FixedOffset>,
+        to: chrono::DateTime<chrono::FixedOffset>,
+        flags: TimedeltaFlags,
+    ) -> Self {
+        let negative = to < from;
+        let offset = from.offset().fix();
+        let (from, to) = (from.with_timezone(&offset), to.with_timezone(&offset));
+        let (start, end) = if negative { (to, from) } else { (from, to) };
+        let (start, end) = (start.naive_local(), end.naive_local());
+
+        let add_months = |months: i64| start.checked_add_months(chrono::Months::new(months as u32));
+
+        let mut months = 0;
+        if flags.contains(TimedeltaFlags::YEAR) || flags.contains(TimedeltaFlags::MONTH) {
+            months = (end.year() as i64 - start.year() as i64) * 12 + end.month0() as i64
+                - start.month0() as i64;
+            while months > 0 && add_months(months).is_none_or(|date| date > end) {
+                months -= 1;
+            }
+        }
+
+        let years = if flags.contains(TimedeltaFlags::YEAR) {
+            months / 12
+        } else {
+            0
+        };
+        let months = if flags.contains(TimedeltaFlags::MONTH) {
+            months - years * 12
+        } else {
+            0
+        };
+
+        let anchor = add_months(years * 12 + months).unwrap_or(start);
+        let rest = flags
+            .remove(TimedeltaFlags::YEAR)
+            .remove(TimedeltaFlags::MONTH);
+
+        Self {
+            negative,
+            years,
+            months,
+            flags,
+            ..Self::new(end - anchor, rest)
+        }
+    }
+
+    /// The nanoseconds as the decimals of the seconds like `.5`, with `precision` digits
+    /// or without the zeros at the end, empty when there are no digits
+    pub fn fraction(&self) -> String {
+        let nanos = if self.flags.contains(TimedeltaFlags::NANOS) {
+            self.nanos
+        } else {
+            0
+        };
+        let fraction = format!("{nanos:09}");
+        let fraction = match self.precision {
+            Some(precision) => &fraction[..usize::from(precision.min(9))],
+            None => fraction.trim_end_matches('0'),
+        };
+        if fraction.is_empty() {
+            String::new()
+        } else {
+            format!(".{fraction}")
+        }
+    }
+
+    /// The value of the unit of `flag`
+    pub fn get(&self, flag: TimedeltaFlags) -> i64 {
+        match flag {
+            TimedeltaFlags::YEAR => self.years,
+            TimedeltaFlags::MONTH => self.months,
+            TimedeltaFlags::WEEK => self.weeks,
+            TimedeltaFlags::DAY => self.days,
+            TimedeltaFlags::HOUR => self.hours,
+            TimedeltaFlags::MINUTE => self.minutes,
+            TimedeltaFlags::SECOND => self.seconds,
+            TimedeltaFlags::NANOS => self.nanos as i64,
+            _ => 0,
+        }
+    }
+
+    /// Keeps only the `max_units` biggest units from the first one that is not zero,
+    /// the smaller units are dropped and not rounded
+    pub fn truncate(mut self, max_units: usize) -> Self {
+        let mut kept = 0;
+        for flag in TimedeltaFlags::UNITS {
+            if !self.flags.contains(flag) || (kept == 0 && self.get(flag) == 0) {
+                continue;
+            }
+            if kept < max_units {
+                kept += 1;
+                continue;
+            }
+
+            match flag {
+                TimedeltaFlags::YEAR => self.years = 0,
+                TimedeltaFlags::MONTH => self.months = 0,
+                TimedeltaFlags::WEEK => self.weeks = 0,
+                TimedeltaFlags::DAY => self.days = 0,
+                TimedeltaFlags::HOUR => self.hours = 0,
+                TimedeltaFlags::MINUTE => self.minutes = 0,
+                TimedeltaFlags::SECOND => self.seconds = 0,
+                _ => self.nanos = 0,
+            }
+            self.flags = self.flags.remove(flag);
+        }
+        self
+    }
+
+    /// Every unit that is in `flags` with its name, from the biggest to the smallest
+    pub fn components(&self) -> Vec<(&'static str, i64)> {
+        [
+            ("years", TimedeltaFlags::YEAR, self.years),
+            ("months", TimedeltaFlags::MONTH, self.months),
+            ("weeks", TimedeltaFlags::WEEK, self.weeks),
+            ("days", TimedeltaFlags::DAY, self.days),
+            ("hours", TimedeltaFlags::HOUR, self.hours),
+            ("minutes", TimedeltaFlags::MINUTE, self.minutes),
+            ("seconds", TimedeltaFlags::SECOND, self.seconds),
+            ("nanoseconds", TimedeltaFlags::NANOS, self.nanos as i64),
+        ]
+        .into_iter()
+        .filter(|(_, flag, _)| self.flags.contains(*flag))
+        .map(|(name, _, value)| (name, value))
+        .collect()
+    }
+}
+
+impl std::fmt::Display for DurationBreakdown {
+    /// Like `P1DT2H3M4.5S`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flags = self.flags;
+        if self.negative {
+            f.write_char('-')?;
+        }
+        f.write_char('P')?;
+
+        let seconds = flags.contains(TimedeltaFlags::SECOND);
+        let time = self.hours > 0 || self.minutes > 0 || seconds;
+        // ISO 8601 has the weeks only alone, with other units they are days
+        let alone = self.years == 0 && self.months == 0 && self.days == 0 && !time;
+        let (weeks, days) = match self.weeks {
+            0 => (0, self.days),
+            weeks if alone => (weeks, 0),
+            weeks => (0, weeks * 7 + self.days),
+        };
+
+        if self.years > 0 {
+            write!(f, "{}Y", self.years)?;
+        }
+        if self.months > 0 {
+            write!(f, "{}M", self.months)?;
+        }
+        if weeks > 0 {
+            write!(f, "{weeks}W")?;
+        }
+        if days > 0 {
+            write!(f, "{days}D")?;
+        }
+
+        if time {
+            f.write_char('T')?;
+        } else if self.years == 0 && self.months == 0 && weeks == 0 && days == 0 {
+            return f.write_str("T0S");
+        }
+
+        if self.hours > 0 {
+            write!(f, "{}H", self.hours)?;
+        }
+        if self.minutes > 0 {
+            write!(f, "{}M", self.minutes)?;
+        }
+
+        if seconds {
+            write!(f, "{}{}S", self.seconds, self.fraction())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How a `DurationBreakdown` is turned in to text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DurationStyle {
+    /// like `PT3H12M5S`
+    #[default]
+    Iso,
+    /// like `3h 12m 5s`
+    Compact,
+    /// like `3 Hours, 12 Minutes, 5 Seconds, `
+    Long,
+}
+
+impl DurationBreakdown {
+    pub fn render(&self, style: DurationStyle, lang: Lang) -> String {
+        match style {
+            DurationStyle::Iso => self.to_string(),
+            DurationStyle::Long => self.long(lang),
+            DurationStyle::Compact => self.compact(),
+        }
+    }
+
+    /// Like `3 days ago` or `in 2 hours` with only the biggest unit,
+    /// a positive duration is in the past like the one of `since`
+    ///
+    /// When every unit down to `granularity` is zero it is `just now`
+    pub fn relative(&self, granularity: TimedeltaFlags) -> String {
+        // with flags like `sn` the hours are in the seconds, so the granularity
+        // is compared with the whole duration and not with the first unit
+        let length = UNITS
+            .iter()
+            .find(|(_, flag, _)| *flag == granularity)
+            .map_or(1, |(_, _, length)| *length);
+        if self.total_nanos() < length as i128 * NANOS_IN_SECOND {
+            return "just now".to_owned();
+        }
+
+        for (unit, flag, _) in UNITS {
+            let value = self.get(flag);
+            if value != 0 && self.flags.contains(flag) {
+                let s = if value == 1 { "" } else { "s" };
+                return if self.negative {
+                    format!("in {value} {unit}{s}")
+                } else {
+                    format!("{value} {unit}{s} ago")
+                };
+            }
+        }
+
+        "just now".to_owned()
+    }
+
+    /// The length of the breakdown without the sign
+    fn total_nanos(&self) -> i128 {
+        UNITS
+            .iter()
+            .map(|(_, flag, length)| self.get(*flag) as i128 * *length as i128 * NANOS_IN_SECOND)
+            .sum::<i128>()
+            + self.nanos as i128
+    }
+
+    /// Like `about 3 weeks` with only the biggest unit of the flags that fits in the duration,
+    /// the rest is rounded in it with a year of 365 days and a month of a twelfth of it
+    pub fn approximate(&self) -> String {
+        let total = self.total_nanos();
+        let units = UNITS
+            .iter()
+            .filter(|(_, flag, _)| self.flags.contains(*flag))
+            .map(|(name, _, length)| (*name, *length as i128 * NANOS_IN_SECOND))
+            .collect::<Vec<_>>();
+        let Some(mut i) = units
+            .iter()
+            .position(|(_, length)| *length <= total)
+            .or(units.len().checked_sub(1))
+        else {
+            return "about 0 seconds".to_owned();
+        };
+        // 59.6 minutes are rounded to 60 minutes, that is an hour
+        if i > 0 && (total + units[i].1 / 2) / units[i].1 * units[i].1 >= units[i - 1].1 {
+            i -= 1;
+        }
+
+        let (name, length) = units[i];
+        let value = (total + length / 2) / length;
+        let s = if value == 1 { "" } else { "s" };
+        let sign = if self.negative && value != 0 { "-" } else { "" };
+        format!("about {sign}{value} {name}{s}")
+    }
+
+    /// Like `1y 2mo 3d 4h 5m 6.5s`, the units that are zero are left out
+    fn compact(&self) -> String {
+        let mut parts = Vec::new();
+        for (unit, flag) in [
+            ("y", TimedeltaFlags::YEAR),
+            ("mo", TimedeltaFlags::MONTH),
+            ("w", TimedeltaFlags::WEEK),
+            ("d", TimedeltaFlags::DAY),
+            ("h", TimedeltaFlags::HOUR),
+            ("m", TimedeltaFlags::MINUTE),
+        ] {
+            let value = self.get(flag);
+            if value != 0 {
+                parts.push(format!("{value}{unit}"));
+            }
+        }
+
+        let fraction = self.fraction();
+        if self.seconds != 0 || !fraction.is_empty() || parts.is_empty() {
+            parts.push(format!("{}{fraction}s", self.seconds));
+        }
+
+        let sign = if self.negative { "-" } else { "" };
+        format!("{sign}{}", parts.join(" "))
+    }
+}
+
+/// A duration template like `{h:02}:{m:02}:{s:02}` or `{D}d {h}h`
+///
+/// The units are the letters of the duration flags, `{h:2}` is padded with spaces
+/// and `{h:02}` with zeros, `{{` and `}}` are `{` and `}`,
+/// a negative duration starts with `-`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Unit {
+        flag: TimedeltaFlags,
+        width: usize,
+        zero: bool,
+    },
+}
+
+impl DurationTemplate {
+    /// The units that are in the template, the duration should be split only in to them
+    pub fn flags(&self) -> TimedeltaFlags {
+        self.parts
+            .iter()
+            .fold(TimedeltaFlags::empty(), |out, part| match part {
+                TemplatePart::Unit { flag, .. } => out | *flag,
+                TemplatePart::Text(_) => out,
+            })
+    }
+
+    pub fn render(&self, breakdown: &DurationBreakdown) -> String {
+        let mut out = String::new();
+        if breakdown.negative {
+            out.push('-');
+        }
+
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => out.push_str(text),
+                TemplatePart::Unit { flag, width, zero } => {
+                    let value = breakdown.get(*flag);
+                    if *zero {
+                        _ = write!(out, "{value:0width$}");
+                    } else {
+                        _ = write!(out, "{value:width$}");
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl FromStr for DurationTemplate {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = str.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match char {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut unit = String::new();
+                    loop {
+                        match chars.next().ok_or(Error::InvalidTemplate)? {
+                            '}' => break,
+                            char => unit.push(char),
+                        }
+                    }
+
+                    let (letter, width) = unit.split_once(':').unwrap_or((&unit, ""));
+                    let mut letters = letter.chars();
+                    let flag = letters
+                        .next()
+                        .and_then(TimedeltaFlags::from_char)
+                        .ok_or(Error::InvalidTemplate)?;
+                    if letters.next().is_some() {
+                        return Err(Error::InvalidTemplate);
+                    }
+                    let zero = width.starts_with('0');
+                    let width = if width.is_empty() {
+                        0
+                    } else {
+                        width.parse().map_err(|_| Error::InvalidTemplate)?
+                    };
+
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Unit { flag, width, zero });
+                }
+                '}' => return Err(Error::InvalidTemplate),
+                char => text.push(char),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+}
+
+/// Formats the duration like `P1DT2H3M4.5S` only with the units that are in `flags`,
+/// see `DurationBreakdown`
+pub fn timedelta_to_str(timedelta: chrono::TimeDelta, flags: TimedeltaFlags) -> String {
+    DurationBreakdown::new(timedelta, flags).to_string()
+}
+
+/// Turns the output of `timedelta_to_str` in to something like `1 Day, 2 Hours, `
+pub fn timedelta_str_to_preety(str: &str) -> String {
+    let mut out = String::default();
+
+    let mut num1 = 0u64;
+    let mut num2 = String::new();
+    let mut dec = false;
+
+    let mut time = false;
+
+    for char in str.chars() {
+        let s = if num1 > 1 { "s" } else { "" };
+        match char {
+            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
+                let num = char as u32 - b'0' as u32;
+                if dec {
+                    num2.push(char);
+                } else {
+                    num1 = (num1 * 10) + num as u64;
+                }
+            }
+            '.' => {
+                dec = true;
+            }
+            '-' => {
+                out.push_str("minus ");
+            }
+            'P' => {}
+            'T' => time = true,
+            'Y' => {
+                out.push_str(&format!("{num1} Year{s}, "));
+                num1 = 0;
+            }
+            'M' => {
+                if !time {
+                    out.push_str(&format!("{num1} Month{s}, "));
+                } else {
+                    out.push_str(&format!("{num1} Minute{s}, "));
+                }
+                num1 = 0;
+            }
+            'W' => {
+                out.push_str(&format!("{num1} Weak{s}, "));
+                num1 = 0;
+            }
+            'D' => {
+                out.push_str(&format!("{num1} Day{s}, "));
+                num1 = 0;
+            }
+            'H' => {
+                out.push_str(&format!("{num1} Hour{s}, "));
+                num1 = 0;
+            }
+            'S' => {
+                // the digits after the `.` are the decimals of the seconds, like `1.50 Seconds`
+                if num2.is_empty() {
+                    out.push_str(&format!("{num1} Second{s}, "));
+                } else {
+                    out.push_str(&format!("{num1}.{num2} Seconds, "));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Which multiple a value is rounded to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// The nearest one, the halves are rounded away from zero
+    #[default]
+    Nearest,
+    /// The one that is not after the value
+    Floor,
+    /// The one that is not before the value
+    Ceil,
+}
+
+const NANOS_IN_SECOND: i128 = 1_000_000_000;
+
+pub(crate) fn to_nanos(timedelta: chrono::TimeDelta) -> i128 {
+    timedelta.num_seconds() as i128 * NANOS_IN_SECOND + timedelta.subsec_nanos() as i128
+}
+
+pub(crate) fn from_nanos(nanos: i128) -> Option<chrono::TimeDelta> {
+    let seconds = i64::try_from(nanos.div_euclid(NANOS_IN_SECOND)).ok()?;
+    chrono::TimeDelta::new(seconds, nanos.rem_euclid(NANOS_IN_SECOND) as u32)
+}
+
+/// Rounds `timedelta` to a multiple of `unit`
+pub fn round_timedelta(
+    timedelta: chrono::TimeDelta,
+    unit: chrono::TimeDelta,
+    rounding: Rounding,
+) -> Option<chrono::TimeDelta> {
+    let unit = to_nanos(unit).abs();
+    if unit == 0 {
+        return Some(timedelta);
+    }
+    let value = to_nanos(timedelta);
+    let rounded = match rounding {
+        Rounding::Nearest => (value.abs() + unit / 2) / unit * unit * value.signum(),
+        Rounding::Floor => value.div_euclid(unit) * unit,
+        Rounding::Ceil => -(-value).div_euclid(unit) * unit,
+    };
+    from_nanos(rounded)
+}
+
+/// `timedelta` in `unit` like `1.25` for `P1DT6H` in days, with at most 9 decimals
+/// that are rounded, none when `unit` is 0 or the value is out of range
+pub fn timedelta_in(timedelta: chrono::TimeDelta, unit: chrono::TimeDelta) -> Option<String> {
+    decimal_to_str(to_nanos(timedelta), to_nanos(unit).abs())
+}
+
+/// `numerator / denominator` as a decimal with at most 9 digits after the point,
+/// none when the denominator is not positive or it is out of range
+pub fn decimal_to_str(numerator: i128, denominator: i128) -> Option<String> {
+    if denominator <= 0 {
+        return None;
+    }
+    let value = numerator.checked_mul(NANOS_IN_SECOND)?;
+    let value = (value.abs() + denominator / 2) / denominator * value.signum();
+
+    let sign = if value < 0 { "-" } else { "" };
+    let (int, frac) = (value.abs() / NANOS_IN_SECOND, value.abs() % NANOS_IN_SECOND);
+    if frac == 0 {
+        return Some(format!("{sign}{int}"));
+    }
+    let frac = format!("{frac:09}");
+    Some(format!("{sign}{int}.{}", frac.trim_end_matches('0')))
+}
+
+/// Parses a factor like `3`, `-0.25` or `1.5` to a numerator and a denominator,
+/// the denominator is a power of 10 so `1.5` is `(15, 10)`
+pub fn parse_factor(str: &str) -> Option<(i128, i128)> {
+    let (negative, str) = match str.strip_prefix('-') {
+        Some(str) => (true, str),
+        None => (false, str.strip_prefix('+').unwrap_or(str)),
+    };
+    let (int, frac) = str.split_once('.').unwrap_or((str, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+
+    let mut numerator: i128 = 0;
+    let mut denominator: i128 = 1;
+    for (i, c) in int.chars().chain(frac.chars()).enumerate() {
+        let digit = c.to_digit(10)?;
+        numerator = numerator.checked_mul(10)?.checked_add(digit as i128)?;
+        if i >= int.len() {
+            denominator = denominator.checked_mul(10)?;
+        }
+    }
+    Some((if negative { -numerator } else { numerator }, denominator))
+}
+
+/// `timedelta` times `numerator / denominator` rounded to the nearest nanosecond,
+/// none when `denominator` is 0 or the result is out of range
+pub fn scale_timedelta(
+    timedelta: chrono::TimeDelta,
+    numerator: i128,
+    denominator: i128,
+) -> Option<chrono::TimeDelta> {
+    if denominator == 0 {
+        return None;
+    }
+    let value = to_nanos(timedelta).checked_mul(numerator)?;
+    let (value, denominator) = if denominator < 0 {
+        (value.checked_neg()?, -denominator)
+    } else {
+        (value, denominator)
+    };
+    from_nanos((value.abs() + denominator / 2) / denominator * value.signum())
+}
+
+/// Parses a duration like `P1DT2H3M4.5S` or `-PT5S`,
+/// a year is 365 days and a month is a twelfth of it
+pub fn timedelta_from_str(str: &str) -> Result<chrono::TimeDelta, Error> {
+    CalendarDuration::from_str(str)?
+        .to_timedelta()
+        .ok_or(Error::InvalidDuration)
+}
+
+/// A duration where the years, months and days are kept apart from the rest,
+/// so they can be added in the calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarDuration {
+    /// With the years as 12 months
+    pub months: i64,
+    /// With the weeks as 7 days
+    pub days: i64,
+    pub time: chrono::TimeDelta,
+}
+
+impl CalendarDuration {
+    /// The years and months are fixed like in `timedelta_from_str` and a day is 24 hours
+    pub fn to_timedelta(self) -> Option<chrono::TimeDelta> {
+        chrono::TimeDelta::try_seconds(self.months.checked_mul(MONTH_IN_SECONDS)?)?
+            .checked_add(&chrono::TimeDelta::try_days(self.days)?)?
+            .checked_add(&self.time)
+    }
+
+    /// Every part times `n`
+    pub fn checked_mul(self, n: i64) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_mul(n)?,
+            days: self.days.checked_mul(n)?,
+            time: self.time.checked_mul(i32::try_from(n).ok()?)?,
+        })
+    }
+
+    /// Adds the months and days in the wall clock of the offset of `date` and then the rest,
+    /// when the day doesn't exist in the new month it is the last day of the month
+    /// so `2024-01-31` + `P1M` is `2024-02-29`
+    pub fn add_to(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.add_to_zoned(date, Zone::Fixed(date.offset().fix()))
+    }
+
+    /// Like `add_to` but in the wall clock of `zone`, so a day can be 23 or 25 hours with DST
+    /// and `09:00` + `P1D` is `09:00` of the next day, see `Zone::resolve_local`
+    pub fn add_to_zoned(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let local = zone.from_utc_datetime(&date.naive_utc()).naive_local();
+
+        let months = chrono::Months::new(u32::try_from(self.months.unsigned_abs()).ok()?);
+        let local = if self.months < 0 {
+            local.checked_sub_months(months)?
+        } else {
+            local.checked_add_months(months)?
+        };
+        let local = local.checked_add_signed(chrono::TimeDelta::try_days(self.days)?)?;
+
+        let date = zone.resolve_local(&local)?;
+
+        date.checked_add_signed(self.time)
+            .map(|date| zone.from_utc_datetime(&date.naive_utc()))
+    }
+}
+
+impl std::ops::Neg for CalendarDuration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            months: -self.months,
+            days: -self.days,
+            time: -self.time,
+        }
+    }
+}
+
+impl FromStr for CalendarDuration {
+    type Err = Error;
+
+    /// Parses a duration like `P1Y2M3DT4H5M6.7S` or `-PT5S`,
+    /// or like `1h30m`, `90 minutes` or `2 weeks 3 days` when it doesn't start with `P`
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        if !str.trim_start_matches(['-', '+']).starts_with('P') {
+            return from_human(str);
+        }
+        from_iso(str)
+    }
+}
+
+/// Parses an ISO 8601 duration like `P1Y2M3DT4H5M6S`, `P2W` or `-PT1.5H`
+///
+/// The units are in order and at most once, the weeks can't be with other units
+/// and only the last unit can have a fraction, the `.` or `,` of a year or a month
+/// is in the time like `timedelta_from_str` so `P0.5Y` is 6 months
+fn from_iso(str: &str) -> Result<CalendarDuration, Error> {
+    let malformed = |at: usize, err: String| {
+        Error::MalformedDuration(format!("at {}: {err}", str[..at].chars().count() + 1))
+    };
+
+    let (sign, rest) = match str.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, str.strip_prefix('+').unwrap_or(str)),
+    };
+    let Some(rest) = rest.strip_prefix('P') else {
+        return Err(malformed(0, "it should start with `P`".to_owned()));
+    };
+    let start = str.len() - rest.len();
+
+    let mut months = 0i64;
+    let mut days = 0i64;
+    let mut time = chrono::TimeDelta::zero();
+
+    let mut in_time = false;
+    // the units that are allowed next, from `Y` = 0 to `S` = 6
+    let mut next = 0;
+    let mut weeks = false;
+    let mut others = false;
+    let mut fraction = false;
+    let mut empty = true;
+
+    let mut i = start;
+    while i < str.len() {
+        if str[i..].starts_with('T') {
+            if in_time {
+                return Err(malformed(i, "`T` is twice".to_owned()));
+            }
+            if empty && i > start {
+                return Err(malformed(i, "there is no unit after `P`".to_owned()));
+            }
+            in_time = true;
+            empty = true;
+            next = next.max(4);
+            i += 1;
+            continue;
+        }
+        if fraction {
+            return Err(malformed(
+                i,
+                "only the last unit can have a fraction, like `PT1H1.5M`".to_owned(),
+            ));
+        }
+
+        let end = str[i..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')
+            .map_or(str.len(), |end| i + end);
+        let number = &str[i..end];
+        let Some(unit) = str[end..].chars().next() else {
+            return Err(malformed(i, format!("`{number}` has no unit")));
+        };
+        if number.is_empty() {
+            return Err(match unit {
+                'Y' | 'M' | 'W' | 'D' | 'H' | 'S' => {
+                    malformed(i, format!("there is no number before `{unit}`"))
+                }
+                _ => malformed(i, format!("`{unit}` is not a number or a unit")),
+            });
+        }
+        let Some((numerator, denominator)) = parse_factor(&number.replace(',', ".")) else {
+            return Err(malformed(i, format!("`{number}` is not a number")));
+        };
+
+        let index = match (in_time, unit) {
+            (false, 'Y') => 0,
+            (false, 'M') => 1,
+            (false, 'W') => 2,
+            (false, 'D') => 3,
+            (true, 'H') => 4,
+            (true, 'M') => 5,
+            (true, 'S') => 6,
+            (false, 'H' | 'S') => {
+                return Err(malformed(end, format!("`{unit}` should be after `T`")))
+            }
+            (true, 'Y' | 'W' | 'D') => {
+                return Err(malformed(end, format!("`{unit}` should be before `T`")))
+            }
+            _ => return Err(malformed(end, format!("`{unit}` is not a unit"))),
+        };
+        if index < next {
+            return Err(malformed(
+                end,
+                format!("`{unit}` is twice or after a smaller unit"),
+            ));
+        }
+        if index == 2 {
+            weeks = true;
+        } else {
+            others = true;
+        }
+        if weeks && others {
+            return Err(malformed(
+                end,
+                "the weeks can't be with other units, use days like `P10D`".to_owned(),
+            ));
+        }
+
+        let out_of_range = || malformed(i, format!("`{number}{unit}` is out of range"));
+        // the whole part of `numerator / denominator` in `unit`s and the rest in the time
+        let mut whole = |factor: i128, unit: chrono::TimeDelta| {
+            let value = numerator.checked_mul(factor).ok_or_else(out_of_range)?;
+            let rest = scale_timedelta(unit, value % denominator, denominator)
+                .and_then(|rest| time.checked_add(&rest))
+                .ok_or_else(out_of_range)?;
+            time = rest;
+            i64::try_from(value / denominator).map_err(|_| out_of_range())
+        };
+        let month = chrono::TimeDelta::seconds(MONTH_IN_SECONDS);
+        let day = chrono::TimeDelta::days(1);
+        match index {
+            0 => {
+                months = months
+                    .checked_add(whole(12, month)?)
+                    .ok_or_else(out_of_range)?
+            }
+            1 => {
+                months = months
+                    .checked_add(whole(1, month)?)
+                    .ok_or_else(out_of_range)?
+            }
+            2 => days = days.checked_add(whole(7, day)?).ok_or_else(out_of_range)?,
+            3 => days = days.checked_add(whole(1, day)?).ok_or_else(out_of_range)?,
+            _ => {
+                let unit = chrono::TimeDelta::seconds([3600, 60, 1][index - 4]);
+                time = scale_timedelta(unit, numerator, denominator)
+                    .and_then(|value| time.checked_add(&value))
+                    .ok_or_else(out_of_range)?;
+            }
+        }
+
+        next = index + 1;
+        fraction = denominator != 1;
+        empty = false;
+        i = end + 1;
+    }
+    if empty {
+        let unit = if in_time { 'T' } else { 'P' };
+        return Err(malformed(
+            str.len(),
+            format!("there is no unit after `{unit}`"),
+        ));
+    }
+
+    Ok(CalendarDuration {
+        months: months * sign,
+        days: days * sign,
+        time: time * sign as i32,
+    })
+}
+
+/// Parses a duration like `1h30m`, `90 minutes`, `2 weeks, 3 days and 4h`, `-1.5h`
+/// or a number of seconds like `90`, the years and months can't have decimals
+fn from_human(str: &str) -> Result<CalendarDuration, Error> {
+    let str = str.trim();
+    let (sign, str) = match str.strip_prefix('-') {
+        Some(str) => (-1, str.trim_start()),
+        None => (1, str),
+    };
+    if let Some((numerator, denominator)) = parse_factor(str) {
+        let time = scale_timedelta(chrono::TimeDelta::seconds(sign), numerator, denominator)
+            .ok_or(Error::InvalidDuration)?;
+        return Ok(CalendarDuration {
+            months: 0,
+            days: 0,
+            time,
+        });
+    }
+
+    let mut months = 0i64;
+    let mut days = 0i64;
+    let mut time = chrono::TimeDelta::zero();
+    let mut rest = str;
+    let mut empty = true;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        rest = rest.strip_prefix("and ").unwrap_or(rest);
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (numerator, denominator) = parse_factor(&rest[..end]).ok_or(Error::InvalidDuration)?;
+        rest = rest[end..].trim_start();
+        let end = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let (unit, after) = rest.split_at(end);
+        rest = after;
+
+        let seconds = match unit.to_lowercase().as_str() {
+            "y" | "yr" | "yrs" | "year" | "years" | "mo" | "mon" | "month" | "months" => {
+                if denominator != 1 {
+                    return Err(Error::InvalidDuration);
+                }
+                let n = i64::try_from(numerator).map_err(|_| Error::InvalidDuration)?;
+                let n = if unit.starts_with(['y', 'Y']) {
+                    n.checked_mul(12).ok_or(Error::InvalidDuration)?
+                } else {
+                    n
+                };
+                months = months.checked_add(n).ok_or(Error::InvalidDuration)?;
+                empty = false;
+                continue;
+            }
+            "w" | "wk" | "wks" | "week" | "weeks" | "d" | "day" | "days" => {
+                let week = unit.starts_with(['w', 'W']);
+                let unit = if week { 7 } else { 1 };
+                // the whole days are in the calendar like the ones of `P1D`
+                if denominator == 1 {
+                    let n = i64::try_from(numerator).map_err(|_| Error::InvalidDuration)?;
+                    days = n
+                        .checked_mul(unit)
+                        .and_then(|n| days.checked_add(n))
+                        .ok_or(Error::InvalidDuration)?;
+                    empty = false;
+                    continue;
+                }
+                chrono::TimeDelta::days(unit)
+            }
+            "h" | "hr" | "hrs" | "hour" | "hours" => chrono::TimeDelta::hours(1),
+            "m" | "min" | "mins" | "minute" | "minutes" => chrono::TimeDelta::minutes(1),
+            "s" | "sec" | "secs" | "second" | "seconds" => chrono::TimeDelta::seconds(1),
+            "ms" | "millisecond" | "milliseconds" => chrono::TimeDelta::milliseconds(1),
+            "us" | "µs" | "microsecond" | "microseconds" => chrono::TimeDelta::microseconds(1),
+            "ns" | "nanosecond" | "nanoseconds" => chrono::TimeDelta::nanoseconds(1),
+            _ => return Err(Error::InvalidDuration),
+        };
+        let value =
+            scale_timedelta(seconds, numerator, denominator).ok_or(Error::InvalidDuration)?;
+        time = time.checked_add(&value).ok_or(Error::InvalidDuration)?;
+        empty = false;
+    }
+    if empty {
+        return Err(Error::InvalidDuration);
+    }
+
+    Ok(CalendarDuration {
+        months: months * sign,
+        days: days * sign,
+        time: time * sign as i32,
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn human_duration() {
+    use chrono::TimeDelta;
+
+    let time = |time| CalendarDuration {
+        months: 0,
+        days: 0,
+        time,
+    };
+    assert_eq!(
+        CalendarDuration::from_str("1h30m"),
+        Ok(time(TimeDelta::minutes(90)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("90 minutes"),
+        Ok(time(TimeDelta::minutes(90)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("2 weeks, 3 days and 1.5h"),
+        Ok(CalendarDuration {
+            months: 0,
+            days: 17,
+            time: TimeDelta::minutes(90),
+        })
+    );
+    assert_eq!(
+        CalendarDuration::from_str("0.5d"),
+        Ok(time(TimeDelta::hours(12)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("90"),
+        Ok(time(TimeDelta::seconds(90)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("-2.5"),
+        Ok(time(-TimeDelta::milliseconds(2500)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("1 year 2mo"),
+        Ok(CalendarDuration {
+            months: 14,
+            days: 0,
+            time: TimeDelta::zero(),
+        })
+    );
+    assert_eq!(
+        timedelta_from_str("- 1d 250ms"),
+        Ok(-TimeDelta::days(1) - TimeDelta::milliseconds(250))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("1.5 months"),
+        Err(Error::InvalidDuration)
+    );
+    assert_eq!(
+        CalendarDuration::from_str("3 fortnights"),
+        Err(Error::InvalidDuration)
+    );
+    assert_eq!(CalendarDuration::from_str("h"), Err(Error::InvalidDuration));
+    assert_eq!(CalendarDuration::from_str(""), Err(Error::InvalidDuration));
+}
+
+#[cfg(test)]
+#[test]
+fn approximate() {
+    use chrono::TimeDelta;
+
+    let approximate = |timedelta, flags| DurationBreakdown::new(timedelta, flags).approximate();
+    assert_eq!(
+        approximate(TimeDelta::days(18), TimedeltaFlags::all()),
+        "about 3 weeks"
+    );
+    assert_eq!(
+        approximate(TimeDelta::days(17), TimedeltaFlags::all()),
+        "about 2 weeks"
+    );
+    assert_eq!(
+        approximate(TimeDelta::days(18), TimedeltaFlags::new("Dhms")),
+        "about 18 days"
+    );
+    assert_eq!(
+        approximate(TimeDelta::seconds(3575), TimedeltaFlags::all()),
+        "about 1 hour"
+    );
+    assert_eq!(
+        approximate(-TimeDelta::minutes(90), TimedeltaFlags::all()),
+        "about -2 hours"
+    );
+    assert_eq!(
+        approximate(TimeDelta::milliseconds(300), TimedeltaFlags::all()),
+        "about 0 seconds"
+    );
+    assert_eq!(
+        approximate(TimeDelta::days(400), TimedeltaFlags::all()),
+        "about 1 year"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn duration_flags() {
+    use TimedeltaFlags as F;
+
+    assert_eq!(F::from_str("YMWDhmsn"), Ok(F::all()));
+    assert_eq!(F::from_str("W"), Ok(F::WEEK));
+    assert_eq!(F::from_str("all-n"), Ok(F::all().remove(F::NANOS)));
+    assert_eq!(
+        F::from_str("D..s"),
+        Ok(F::DAY | F::HOUR | F::MINUTE | F::SECOND)
+    );
+    assert_eq!(F::from_str("Y..D-W"), Ok(F::YEAR | F::MONTH | F::DAY));
+    assert_eq!(F::from_str("hn"), Ok(F::HOUR | F::NANOS));
+    assert_eq!(
+        F::from_str("Dhx"),
+        Err(Error::InvalidDurationFlags(
+            "`x` is not a flag in \"Dhx\"".to_owned()
+        ))
+    );
+    assert_eq!(
+        F::from_str("s..D"),
+        Err(Error::InvalidDurationFlags(
+            "`s..D` should be from the bigger unit to the smaller one in \"s..D\"".to_owned()
+        ))
+    );
+    assert_eq!(
+        F::from_str("D.."),
+        Err(Error::InvalidDurationFlags(
+            "`D..` has no end in \"D..\"".to_owned()
+        ))
+    );
+    assert_eq!(
+        F::from_str("all-all"),
+        Err(Error::InvalidDurationFlags(
+            "there are no flags in \"all-all\"".to_owned()
+        ))
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn negative_duration() {
+    use chrono::{TimeDelta, TimeZone};
+
+    let duration = CalendarDuration::from_str("-P1M1DT1H").unwrap();
+    assert_eq!(
+        duration,
+        CalendarDuration {
+            months: -1,
+            days: -1,
+            time: -TimeDelta::hours(1),
+        }
+    );
+    assert_eq!(-duration, CalendarDuration::from_str("P1M1DT1H").unwrap());
+    assert_eq!(
+        CalendarDuration::from_str("-1h30m"),
+        CalendarDuration::from_str("-PT1H30M")
+    );
+
+    let date = chrono::FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 3, 31, 12, 0, 0)
+        .unwrap();
+    assert_eq!(
+        duration.add_to(date),
+        Some(date.with_day(28).unwrap().with_month(2).unwrap() - TimeDelta::hours(1))
+    );
+
+    let timedelta = timedelta_from_str("-P1DT2H").unwrap();
+    assert_eq!(timedelta, -TimeDelta::hours(26));
+    assert_eq!(
+        timedelta_to_str(timedelta, TimedeltaFlags::all()),
+        "-P1DT2H0S"
+    );
+    assert_eq!(
+        timedelta_str_to_preety(&timedelta_to_str(timedelta, TimedeltaFlags::all())),
+        "minus 1 Day, 2 Hours, 0 Second, "
+    );
+    assert_eq!(
+        DurationBreakdown::new(timedelta, TimedeltaFlags::all())
+            .render(DurationStyle::Compact, Lang::En),
+        "-1d 2h"
+    );
+    assert_eq!(
+        timedelta_from_str(&timedelta_to_str(
+            -TimeDelta::milliseconds(500),
+            TimedeltaFlags::all()
+        )),
+        Ok(-TimeDelta::milliseconds(500))
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn iso_duration() {
+    use chrono::TimeDelta;
+
+    let duration = |months, days, time| CalendarDuration { months, days, time };
+    let malformed = |err: &str| Err(Error::MalformedDuration(err.to_owned()));
+
+    assert_eq!(
+        CalendarDuration::from_str("P1Y2M3DT4H5M6S"),
+        Ok(duration(
+            14,
+            3,
+            TimeDelta::hours(4) + TimeDelta::minutes(5) + TimeDelta::seconds(6)
+        ))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P0.5Y"),
+        Ok(duration(6, 0, TimeDelta::zero()))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1.5M"),
+        Ok(duration(1, 0, TimeDelta::seconds(MONTH_IN_SECONDS / 2)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("-PT1,5H"),
+        Ok(duration(0, 0, -TimeDelta::minutes(90)))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P2W"),
+        Ok(duration(0, 14, TimeDelta::zero()))
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1.5W"),
+        Ok(duration(0, 10, TimeDelta::hours(12)))
+    );
+
+    assert_eq!(
+        from_iso("1DT2H"),
+        malformed("at 1: it should start with `P`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P"),
+        malformed("at 2: there is no unit after `P`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1DT"),
+        malformed("at 5: there is no unit after `T`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1W2D"),
+        malformed("at 5: the weeks can't be with other units, use days like `P10D`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1H"),
+        malformed("at 3: `H` should be after `T`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("PT1S2M"),
+        malformed("at 6: `M` is twice or after a smaller unit")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("PT1.5H2M"),
+        malformed("at 7: only the last unit can have a fraction, like `PT1H1.5M`")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("PT5"),
+        malformed("at 3: `5` has no unit")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("P1.2.3D"),
+        malformed("at 2: `1.2.3` is not a number")
+    );
+    assert_eq!(
+        CalendarDuration::from_str("PTS"),
+        malformed("at 3: there is no number before `S`")
+    );
+
+    let breakdown = |timedelta| DurationBreakdown::new(timedelta, TimedeltaFlags::all());
+    assert_eq!(breakdown(TimeDelta::weeks(2)).to_string(), "P14DT0S");
+    assert_eq!(
+        DurationBreakdown::new(
+            TimeDelta::weeks(2),
+            TimedeltaFlags::WEEK | TimedeltaFlags::DAY
+        )
+        .to_string(),
+        "P2W"
+    );
+    assert_eq!(
+        breakdown(TimeDelta::days(9) + TimeDelta::hours(1)).to_string(),
+        "P9DT1H0S"
+    );
+    assert_eq!(
+        DurationBreakdown::new(TimeDelta::zero(), TimedeltaFlags::HOUR).to_string(),
+        "PT0S"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn timedelta() {
+    use chrono::NaiveDateTime;
+    use chrono::TimeDelta;
+    use chrono::Utc;
+
+    let time_delta = TimeDelta::new(1, 320_000_000).unwrap();
+    assert_eq!(
+        timedelta_to_str(time_delta, TimedeltaFlags::all()),
+        "PT1.32S".to_owned()
+    );
+    assert_eq!(
+        time_delta,
+        timedelta_from_str(&timedelta_to_str(time_delta, TimedeltaFlags::all())).unwrap()
+    );
+
+    let time_delta = TimeDelta::new(1, 32).unwrap();
+    assert_eq!(
+        timedelta_to_str(time_delta, TimedeltaFlags::all()),
+        "PT1.000000032S".to_owned()
+    );
+    assert_eq!(
+        time_delta,
+        timedelta_from_str(&timedelta_to_str(time_delta, TimedeltaFlags::all())).unwrap()
+    );
+    assert_eq!(
+        timedelta_from_str("PT0.5S"),
+        Ok(TimeDelta::milliseconds(500))
+    );
+
+    let mut breakdown =
+        DurationBreakdown::new(TimeDelta::new(1, 5_000_000).unwrap(), TimedeltaFlags::all());
+    breakdown.precision = Some(3);
+    assert_eq!(breakdown.to_string(), "PT1.005S");
+    breakdown.precision = Some(0);
+    assert_eq!(breakdown.to_string(), "PT1S");
+    assert_eq!(timedelta_str_to_preety("PT1.5S"), "1.5 Seconds, ");
+    // the long style has the digits of `precision` too
+    let mut breakdown =
+        DurationBreakdown::new(TimeDelta::milliseconds(1500), TimedeltaFlags::all());
+    breakdown.precision = Some(2);
+    assert_eq!(
+        breakdown.render(DurationStyle::Long, Lang::En),
+        "1.50 Seconds, "
+    );
+    assert_eq!(
+        breakdown.render(DurationStyle::Long, Lang::De),
+        "1.50 Sekunden, "
+    );
+    breakdown.precision = Some(0);
+    assert_eq!(
+        breakdown.render(DurationStyle::Long, Lang::En),
+        "1 Second, "
+    );
+
+    let since = Utc::now().naive_utc() - NaiveDateTime::UNIX_EPOCH;
+    assert_eq!(
+        since,
+        timedelta_from_str(&timedelta_to_str(since, TimedeltaFlags::default())).unwrap()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn duration_breakdown() {
+    use chrono::TimeDelta;
+
+    let breakdown =
+        DurationBreakdown::new(-TimeDelta::new(90061, 500).unwrap(), TimedeltaFlags::all());
+    assert!(breakdown.negative);
+    assert_eq!(
+        (
+            breakdown.days,
+            breakdown.hours,
+            breakdown.minutes,
+            breakdown.seconds
+        ),
+        (1, 1, 1, 1)
+    );
+    assert_eq!(breakdown.nanos, 500);
+    assert_eq!(breakdown.to_string(), "-P1DT1H1M1.0000005S");
+}
+
+#[cfg(test)]
+#[test]
+fn duration_truncate() {
+    use chrono::TimeDelta;
+
+    let breakdown =
+        DurationBreakdown::new(TimeDelta::new(90061, 500).unwrap(), TimedeltaFlags::all());
+    assert_eq!(breakdown.truncate(2).to_string(), "P1DT1H");
+    assert_eq!(breakdown.truncate(4).to_string(), "P1DT1H1M1S");
+    assert_eq!(
+        timedelta_str_to_preety(&breakdown.truncate(1).to_string()),
+        "1 Day, "
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn round() {
+    use chrono::TimeDelta;
+
+    let timedelta = TimeDelta::new(3 * 3600 - 1, 100_000_000).unwrap();
+    assert_eq!(
+        round_timedelta(timedelta, TimeDelta::minutes(1), Rounding::Nearest),
+        Some(TimeDelta::hours(3))
+    );
+    assert_eq!(
+        round_timedelta(-timedelta, TimeDelta::seconds(1), Rounding::Nearest),
+        Some(-TimeDelta::hours(3) + TimeDelta::seconds(1))
+    );
+    assert_eq!(
+        round_timedelta(
+            TimeDelta::minutes(90),
+            TimeDelta::hours(1),
+            Rounding::Nearest
+        ),
+        Some(TimeDelta::hours(2))
+    );
+    assert_eq!(
+        round_timedelta(
+            TimeDelta::minutes(89),
+            TimeDelta::hours(1),
+            Rounding::Nearest
+        ),
+        Some(TimeDelta::hours(1))
+    );
+    assert_eq!(
+        round_timedelta(
+            -TimeDelta::minutes(89),
+            TimeDelta::hours(1),
+            Rounding::Floor
+        ),
+        Some(-TimeDelta::hours(2))
+    );
+    assert_eq!(
+        round_timedelta(-TimeDelta::minutes(89), TimeDelta::hours(1), Rounding::Ceil),
+        Some(-TimeDelta::hours(1))
+    );
+    assert_eq!(
+        round_timedelta(TimeDelta::minutes(61), TimeDelta::hours(1), Rounding::Ceil),
+        Some(TimeDelta::hours(2))
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn scale() {
+    use chrono::TimeDelta;
+
+    assert_eq!(parse_factor("3"), Some((3, 1)));
+    assert_eq!(parse_factor("-0.25"), Some((-25, 100)));
+    assert_eq!(parse_factor(".5"), Some((5, 10)));
+    assert_eq!(parse_factor("1.5x"), None);
+    assert_eq!(parse_factor("-"), None);
+
+    assert_eq!(
+        scale_timedelta(TimeDelta::minutes(90), 3, 1),
+        Some(TimeDelta::minutes(270))
+    );
+    assert_eq!(
+        scale_timedelta(TimeDelta::days(1), 1, 4),
+        Some(TimeDelta::hours(6))
+    );
+    assert_eq!(
+        scale_timedelta(TimeDelta::hours(1), 15, -10),
+        Some(-TimeDelta::minutes(90))
+    );
+    // a third of a second is rounded to the nanosecond
+    assert_eq!(
+        scale_timedelta(TimeDelta::seconds(1), 1, 3),
+        Some(TimeDelta::nanoseconds(333_333_333))
+    );
+    assert_eq!(
+        scale_timedelta(-TimeDelta::seconds(2), 1, 3),
+        Some(-TimeDelta::nanoseconds(666_666_667))
+    );
+    assert_eq!(scale_timedelta(TimeDelta::seconds(1), 1, 0), None);
+
+    let duration = TimeDelta::hours(30);
+    assert_eq!(
+        timedelta_in(duration, TimeDelta::hours(1)),
+        Some("30".to_owned())
+    );
+    assert_eq!(
+        timedelta_in(duration, TimeDelta::days(1)),
+        Some("1.25".to_owned())
+    );
+    assert_eq!(
+        timedelta_in(-TimeDelta::seconds(1), TimeDelta::minutes(1)),
+        Some("-0.016666667".to_owned())
+    );
+    assert_eq!(timedelta_in(duration, TimeDelta::zero()), None);
+    assert_eq!(scale_timedelta(TimeDelta::max_value(), 2, 1), None);
+}
+
+#[cfg(test)]
+#[test]
+fn duration_style() {
+    use chrono::TimeDelta;
+
+    let breakdown = DurationBreakdown::new(
+        TimeDelta::new(3 * 3600 + 12 * 60 + 5, 500_000_000).unwrap(),
+        TimedeltaFlags::all(),
+    );
+    assert_eq!(
+        breakdown.render(DurationStyle::Compact, Lang::En),
+        "3h 12m 5.5s"
+    );
+    assert_eq!(
+        breakdown.render(DurationStyle::Long, Lang::En),
+        "3 Hours, 12 Minutes, 5.5 Seconds, "
+    );
+    assert_eq!(
+        DurationBreakdown::new(-TimeDelta::days(2), TimedeltaFlags::all())
+            .render(DurationStyle::Compact, Lang::En),
+        "-2d"
+    );
+    assert_eq!(
+        DurationBreakdown::new(TimeDelta::zero(), TimedeltaFlags::all())
+            .render(DurationStyle::Compact, Lang::En),
+        "0s"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn duration_relative() {
+    use chrono::TimeDelta;
+
+    let relative = |timedelta, granularity| {
+        DurationBreakdown::new(timedelta, TimedeltaFlags::all()).relative(granularity)
+    };
+    assert_eq!(
+        relative(TimeDelta::hours(80), TimedeltaFlags::SECOND),
+        "3 days ago"
+    );
+    assert_eq!(
+        relative(-TimeDelta::minutes(125), TimedeltaFlags::SECOND),
+        "in 2 hours"
+    );
+    assert_eq!(
+        relative(TimeDelta::seconds(1), TimedeltaFlags::SECOND),
+        "1 second ago"
+    );
+    assert_eq!(
+        relative(TimeDelta::seconds(59), TimedeltaFlags::MINUTE),
+        "just now"
+    );
+
+    // only seconds, the hours are in them
+    let seconds = TimedeltaFlags::SECOND | TimedeltaFlags::NANOS;
+    let relative =
+        |timedelta, granularity| DurationBreakdown::new(timedelta, seconds).relative(granularity);
+    assert_eq!(
+        relative(TimeDelta::hours(3), TimedeltaFlags::MINUTE),
+        "10800 seconds ago"
+    );
+    assert_eq!(
+        relative(TimeDelta::seconds(59), TimedeltaFlags::MINUTE),
+        "just now"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn duration_template() {
+    use chrono::TimeDelta;
+
+    let template = DurationTemplate::from_str("{h:02}:{m:02}:{s:02}").unwrap();
+    assert_eq!(
+        template.flags(),
+        TimedeltaFlags::HOUR | TimedeltaFlags::MINUTE | TimedeltaFlags::SECOND
+    );
+    let breakdown = DurationBreakdown::new(TimeDelta::seconds(90061), template.flags());
+    assert_eq!(template.render(&breakdown), "25:01:01");
+
+    let template = DurationTemplate::from_str("{{{D}}}d {h:3}h").unwrap();
+    let breakdown = DurationBreakdown::new(-TimeDelta::seconds(90061), template.flags());
+    assert_eq!(template.render(&breakdown), "-{1}d   1h");
+
+    assert!(DurationTemplate::from_str("{x}").is_err());
+    assert!(DurationTemplate::from_str("{h").is_err());
+    assert!(DurationTemplate::from_str("h}").is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn duration_between() {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+    let from = offset.with_ymd_and_hms(2000, 1, 31, 10, 0, 0).unwrap();
+    let to = offset.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+
+    let breakdown = DurationBreakdown::between(from, to, TimedeltaFlags::all());
+    assert_eq!(breakdown.to_string(), "P24Y1M1DT2H30M0S");
+    assert_eq!(
+        DurationBreakdown::between(to, from, TimedeltaFlags::all()).to_string(),
+        "-P24Y1M1DT2H30M0S"
+    );
+    assert_eq!(
+        DurationBreakdown::between(from, to, TimedeltaFlags::new("Mhms")).to_string(),
+        "P289MT26H30M0S"
+    );
+
+    // 12:00+02:00 is an hour after 09:00+00:00
+    let utc = chrono::FixedOffset::east_opt(0).unwrap();
+    let from = offset.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    let to = utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    assert_eq!(
+        DurationBreakdown::between(from, to, TimedeltaFlags::all()).to_string(),
+        "-PT1H0S"
+    );
+    assert_eq!(
+        DurationBreakdown::between(to, from, TimedeltaFlags::all()).to_string(),
+        "PT1H0S"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn calendar_duration() {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(0).unwrap();
+    let date = offset.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap();
+
+    let duration = CalendarDuration::from_str("P1MT1H").unwrap();
+    assert_eq!(
+        duration.add_to(date),
+        Some(offset.with_ymd_and_hms(2024, 2, 29, 11, 0, 0).unwrap())
+    );
+    assert_eq!(
+        (-duration).add_to(date),
+        Some(offset.with_ymd_and_hms(2023, 12, 31, 9, 0, 0).unwrap())
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn calendar_duration_zoned() {
+    use chrono::TimeZone;
+
+    let zone = Zone::Named(chrono_tz::Europe::Bucharest);
+    let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+    // the day before DST starts
+    let date = offset.with_ymd_and_hms(2024, 3, 30, 9, 0, 0).unwrap();
+    let duration = CalendarDuration::from_str("P1D").unwrap();
+
+    assert_eq!(
+        duration.add_to_zoned(date, zone),
+        Some(
+            chrono::FixedOffset::east_opt(3 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2024, 3, 31, 9, 0, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        duration.add_to(date),
+        Some(offset.with_ymd_and_hms(2024, 3, 31, 9, 0, 0).unwrap())
+    );
+}
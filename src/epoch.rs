@@ -0,0 +1,168 @@
+type Utc = chrono::DateTime<chrono::Utc>;
+
+const NANOS_IN_SECOND: i128 = 1_000_000_000;
+
+/// The timestamps that are not from 1970, like the ones of Windows, .NET and macOS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LegacyEpoch {
+    /// Windows FILETIME, the 100 nanoseconds from 1601-01-01 UTC
+    Filetime,
+    /// .NET `DateTime.Ticks`, the 100 nanoseconds from 0001-01-01 UTC
+    Dotnet,
+    /// Apple Cocoa `NSDate`, the seconds from 2001-01-01 UTC, they can have a fraction
+    Cocoa,
+    /// HFS+ dates, the seconds from 1904-01-01 UTC in 32 bits
+    Hfs,
+}
+
+impl LegacyEpoch {
+    /// The unix timestamp of the epoch
+    pub fn start(self) -> i64 {
+        match self {
+            LegacyEpoch::Filetime => -11_644_473_600,
+            LegacyEpoch::Dotnet => -62_135_596_800,
+            LegacyEpoch::Cocoa => 978_307_200,
+            LegacyEpoch::Hfs => -2_082_844_800,
+        }
+    }
+
+    /// The nanoseconds of a tick
+    fn unit(self) -> i128 {
+        match self {
+            LegacyEpoch::Filetime | LegacyEpoch::Dotnet => 100,
+            LegacyEpoch::Cocoa | LegacyEpoch::Hfs => NANOS_IN_SECOND,
+        }
+    }
+
+    fn in_range(self, ticks: i128) -> bool {
+        match self {
+            LegacyEpoch::Hfs => (0..=u32::MAX as i128).contains(&ticks),
+            LegacyEpoch::Filetime => ticks >= 0,
+            _ => true,
+        }
+    }
+
+    /// The date of the timestamp, only `Cocoa` can have a fraction like `735830400.5`
+    pub fn to_date(self, timestamp: &str) -> Option<Utc> {
+        let timestamp = timestamp.trim();
+        let (whole, fraction) = match timestamp.split_once('.') {
+            Some(_) if self != LegacyEpoch::Cocoa => return None,
+            Some((whole, fraction)) => (whole, fraction),
+            None => (timestamp, ""),
+        };
+        if fraction.len() > 9 || !fraction.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+        let ticks = whole.parse::<i128>().ok()?;
+        if !self.in_range(ticks) {
+            return None;
+        }
+        let fraction = format!("{fraction:0<9}").parse::<i128>().ok()?;
+        let fraction = if whole.starts_with('-') {
+            -fraction
+        } else {
+            fraction
+        };
+
+        let nanos = ticks
+            .checked_mul(self.unit())?
+            .checked_add(fraction)?
+            .checked_add(self.start() as i128 * NANOS_IN_SECOND)?;
+        let seconds = i64::try_from(nanos.div_euclid(NANOS_IN_SECOND)).ok()?;
+        chrono::DateTime::from_timestamp(seconds, nanos.rem_euclid(NANOS_IN_SECOND) as u32)
+    }
+
+    /// The timestamp of `date`, the ticks are rounded down,
+    /// `None` when it is before a FILETIME or out of the 32 bits of HFS+
+    pub fn from_date(self, date: Utc) -> Option<String> {
+        // a leap second is the end of the second before it
+        let subsec = date.timestamp_subsec_nanos().min(999_999_999) as i128;
+        let nanos = (date.timestamp() - self.start()) as i128 * NANOS_IN_SECOND + subsec;
+        let ticks = nanos.div_euclid(self.unit());
+        if !self.in_range(ticks) {
+            return None;
+        }
+
+        let fraction = nanos.rem_euclid(self.unit());
+        if self != LegacyEpoch::Cocoa || fraction == 0 {
+            return Some(ticks.to_string());
+        }
+        // like `-1.5` and not `-2.5` for 1.5 seconds before 2001
+        let sign = if nanos < 0 { "-" } else { "" };
+        let (whole, fraction) = (nanos.abs() / NANOS_IN_SECOND, nanos.abs() % NANOS_IN_SECOND);
+        let fraction = format!("{fraction:09}");
+        Some(format!("{sign}{whole}.{}", fraction.trim_end_matches('0')))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn legacy_epoch() {
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap().to_utc();
+
+    // the epochs of each other
+    let unix = date("1970-01-01T00:00:00Z");
+    assert_eq!(
+        LegacyEpoch::Filetime.from_date(unix).unwrap(),
+        "116444736000000000"
+    );
+    assert_eq!(
+        LegacyEpoch::Dotnet.from_date(unix).unwrap(),
+        "621355968000000000"
+    );
+    assert_eq!(LegacyEpoch::Hfs.from_date(unix).unwrap(), "2082844800");
+    assert_eq!(
+        LegacyEpoch::Cocoa.to_date("0"),
+        Some(date("2001-01-01T00:00:00Z"))
+    );
+    assert_eq!(
+        LegacyEpoch::Filetime.to_date("0"),
+        Some(date("1601-01-01T00:00:00Z"))
+    );
+    assert_eq!(
+        LegacyEpoch::Dotnet.to_date("0"),
+        Some(date("0001-01-01T00:00:00Z"))
+    );
+
+    let now = date("2024-04-26T12:00:00.1234567Z");
+    for epoch in [
+        LegacyEpoch::Filetime,
+        LegacyEpoch::Dotnet,
+        LegacyEpoch::Cocoa,
+    ] {
+        let timestamp = epoch.from_date(now).unwrap();
+        assert_eq!(epoch.to_date(&timestamp), Some(now));
+    }
+    assert_eq!(
+        LegacyEpoch::Filetime.from_date(now).unwrap(),
+        "133586064001234567"
+    );
+    assert_eq!(
+        LegacyEpoch::Cocoa.from_date(now).unwrap(),
+        "735825600.1234567"
+    );
+    assert_eq!(LegacyEpoch::Hfs.from_date(now).unwrap(), "3796977600");
+    assert_eq!(
+        LegacyEpoch::Cocoa
+            .from_date(date("2000-12-31T23:59:58.5Z"))
+            .unwrap(),
+        "-1.5"
+    );
+    assert_eq!(
+        LegacyEpoch::Cocoa.to_date("-1.5"),
+        Some(date("2000-12-31T23:59:58.5Z"))
+    );
+
+    assert_eq!(
+        LegacyEpoch::Hfs.from_date(date("1900-01-01T00:00:00Z")),
+        None
+    );
+    assert_eq!(LegacyEpoch::Hfs.to_date("4294967296"), None);
+    assert_eq!(LegacyEpoch::Filetime.to_date("-1"), None);
+    assert_eq!(LegacyEpoch::Filetime.to_date("1.5"), None);
+    assert_eq!(LegacyEpoch::Cocoa.to_date("1.x"), None);
+    assert_eq!(
+        LegacyEpoch::Dotnet.to_date("-1701411834604692317316873037158841056"),
+        None
+    );
+}
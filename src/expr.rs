@@ -0,0 +1,393 @@
+use std::str::FromStr;
+
+use crate::duration::to_nanos;
+use crate::{parse_factor, scale_timedelta, CalendarDuration, Error, Zone};
+
+/// The result of an expression of `eval`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Date(chrono::DateTime<chrono::FixedOffset>),
+    Duration(CalendarDuration),
+    /// An exact decimal as a numerator and a positive denominator, like `(3, 2)` for `1.5`
+    Number(i128, i128),
+}
+
+impl Value {
+    fn name(&self) -> &'static str {
+        match self {
+            Value::Date(_) => "a date",
+            Value::Duration(_) => "a duration",
+            Value::Number(..) => "a number",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Op(char),
+    /// A date, a duration or a number, it can have spaces like `next tuesday 14:00`
+    Literal(&'a str),
+}
+
+/// An operator is one of `+ - * /` between spaces or parentheses,
+/// so the `-` of `2025-01-01` and the `+` of `+03:00` are part of the literal
+fn tokenize(expression: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let flush = |tokens: &mut Vec<_>, start: usize, end: usize| {
+        let literal = &expression[start..end];
+        let trimmed = literal.trim_start();
+        if !trimmed.trim_end().is_empty() {
+            let at = start + literal.len() - trimmed.len();
+            tokens.push((at, Token::Literal(trimmed.trim_end())));
+        }
+    };
+
+    for (i, c) in expression.char_indices() {
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '+' | '-' | '*' | '/' => {
+                let before = expression[..i].chars().next_back();
+                let after = expression[i + 1..].chars().next();
+                let separated = before.is_none_or(|c| c.is_whitespace() || c == '(' || c == ')')
+                    && after.is_none_or(|c| c.is_whitespace() || c == '(');
+                if !separated {
+                    continue;
+                }
+                Token::Op(c)
+            }
+            _ => continue,
+        };
+        flush(&mut tokens, start, i);
+        tokens.push((i, token));
+        start = i + 1;
+    }
+    flush(&mut tokens, start, expression.len());
+    tokens
+}
+
+/// How deep `(` and `-` can be nested
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a, F> {
+    expression: &'a str,
+    tokens: Vec<(usize, Token<'a>)>,
+    next: usize,
+    zone: Zone,
+    literal: F,
+    /// How many `(` and `-` the parser is in, a limit for the stack
+    depth: usize,
+}
+
+impl<'a, F> Parser<'a, F>
+where
+    F: FnMut(&str) -> Option<Value>,
+{
+    fn error(&self, at: usize, err: impl std::fmt::Display) -> Error {
+        let at = self.expression[..at].chars().count() + 1;
+        Error::InvalidExpression(format!("at {at}: {err}"))
+    }
+
+    /// Where the next token is, the end when there are no more
+    fn at(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.expression.len(), |(at, _)| *at)
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.next).map(|(_, token)| *token)
+    }
+
+    /// sum = product (("+" | "-") product)*
+    fn sum(&mut self) -> Result<Value, Error> {
+        let mut value = self.product()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            let at = self.at();
+            self.next += 1;
+            let rhs = self.product()?;
+            value = self.apply(at, op, value, rhs)?;
+        }
+        Ok(value)
+    }
+
+    /// product = unary (("*" | "/") unary)*
+    fn product(&mut self) -> Result<Value, Error> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek() {
+            let at = self.at();
+            self.next += 1;
+            let rhs = self.unary()?;
+            value = self.apply(at, op, value, rhs)?;
+        }
+        Ok(value)
+    }
+
+    /// unary = "-" unary | "(" sum ")" | literal
+    fn unary(&mut self) -> Result<Value, Error> {
+        let at = self.at();
+        let Some(token) = self.peek() else {
+            return Err(self.error(at, "expected a date, a duration or a number"));
+        };
+        self.next += 1;
+        if matches!(token, Token::Op('-') | Token::Open) {
+            if self.depth >= MAX_DEPTH {
+                return Err(self.error(at, "expression is nested too deeply"));
+            }
+            self.depth += 1;
+        }
+        let value = self.nested(at, token);
+        if matches!(token, Token::Op('-') | Token::Open) {
+            self.depth -= 1;
+        }
+        value
+    }
+
+    /// The rest of `unary` after its first token
+    fn nested(&mut self, at: usize, token: Token<'a>) -> Result<Value, Error> {
+        match token {
+            Token::Op('-') => match self.unary()? {
+                Value::Duration(duration) => Ok(Value::Duration(-duration)),
+                Value::Number(n, d) => Ok(Value::Number(-n, d)),
+                Value::Date(_) => Err(self.error(at, "a date cannot be negative")),
+            },
+            Token::Open => {
+                let value = self.sum()?;
+                if self.peek() != Some(Token::Close) {
+                    return Err(self.error(self.at(), "expected `)`"));
+                }
+                self.next += 1;
+                Ok(value)
+            }
+            Token::Literal(literal) => self.literal(at, literal),
+            Token::Close | Token::Op(_) => {
+                Err(self.error(at, "expected a date, a duration or a number"))
+            }
+        }
+    }
+
+    /// A number is tried first, `90` is a number and `90s` is a duration
+    fn literal(&mut self, at: usize, literal: &str) -> Result<Value, Error> {
+        if let Some((n, d)) = parse_factor(literal) {
+            return Ok(Value::Number(n, d));
+        }
+        if let Ok(duration) = CalendarDuration::from_str(literal) {
+            return Ok(Value::Duration(duration));
+        }
+        if let Some(value) = (self.literal)(literal) {
+            return Ok(value);
+        }
+        Err(self.error(
+            at,
+            format_args!("`{literal}` is not a date, a duration or a number"),
+        ))
+    }
+
+    fn apply(&self, at: usize, op: char, lhs: Value, rhs: Value) -> Result<Value, Error> {
+        let out_of_range = || self.error(at, "the result is out of range");
+        let add_to = |duration: CalendarDuration, date| match self.zone {
+            Zone::Named(_) => duration.add_to_zoned(date, self.zone),
+            _ => duration.add_to(date),
+        };
+
+        let value = match (op, lhs, rhs) {
+            ('+', Value::Date(date), Value::Duration(duration))
+            | ('+', Value::Duration(duration), Value::Date(date)) => {
+                Value::Date(add_to(duration, date).ok_or_else(out_of_range)?)
+            }
+            ('-', Value::Date(date), Value::Duration(duration)) => {
+                Value::Date(add_to(-duration, date).ok_or_else(out_of_range)?)
+            }
+            ('-', Value::Date(lhs), Value::Date(rhs)) => Value::Duration(CalendarDuration {
+                months: 0,
+                days: 0,
+                time: lhs.signed_duration_since(rhs),
+            }),
+            ('+', Value::Duration(lhs), Value::Duration(rhs)) => {
+                Value::Duration(add_durations(lhs, rhs).ok_or_else(out_of_range)?)
+            }
+            ('-', Value::Duration(lhs), Value::Duration(rhs)) => {
+                Value::Duration(add_durations(lhs, -rhs).ok_or_else(out_of_range)?)
+            }
+            ('*', Value::Duration(duration), Value::Number(n, d))
+            | ('*', Value::Number(n, d), Value::Duration(duration)) => {
+                Value::Duration(scale(duration, n, d).ok_or_else(out_of_range)?)
+            }
+            ('/', Value::Duration(duration), Value::Number(n, d)) => {
+                if n == 0 {
+                    return Err(self.error(at, "division by zero"));
+                }
+                let (n, d) = if n < 0 { (-d, -n) } else { (d, n) };
+                Value::Duration(scale(duration, n, d).ok_or_else(out_of_range)?)
+            }
+            ('/', Value::Duration(lhs), Value::Duration(rhs)) => {
+                let lhs = lhs.to_timedelta().ok_or_else(out_of_range)?;
+                let rhs = rhs.to_timedelta().ok_or_else(out_of_range)?;
+                if rhs.is_zero() {
+                    return Err(self.error(at, "division by zero"));
+                }
+                number(to_nanos(lhs), to_nanos(rhs)).ok_or_else(out_of_range)?
+            }
+            (op, Value::Number(a, b), Value::Number(c, d)) => {
+                let res = match op {
+                    '+' => a
+                        .checked_mul(d)
+                        .zip(c.checked_mul(b))
+                        .and_then(|(ad, cb)| ad.checked_add(cb))
+                        .zip(b.checked_mul(d)),
+                    '-' => a
+                        .checked_mul(d)
+                        .zip(c.checked_mul(b))
+                        .and_then(|(ad, cb)| ad.checked_sub(cb))
+                        .zip(b.checked_mul(d)),
+                    '*' => a.checked_mul(c).zip(b.checked_mul(d)),
+                    _ if c == 0 => return Err(self.error(at, "division by zero")),
+                    _ => a.checked_mul(d).zip(b.checked_mul(c)),
+                };
+                res.and_then(|(n, d)| number(n, d))
+                    .ok_or_else(out_of_range)?
+            }
+            (op, lhs, rhs) => {
+                let op = match op {
+                    '+' => "add",
+                    '-' => "subtract",
+                    '*' => "multiply",
+                    _ => "divide",
+                };
+                return Err(self.error(
+                    at,
+                    format_args!("cannot {op} {} and {}", lhs.name(), rhs.name()),
+                ));
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Keeps the denominator positive and the fraction reduced, so it doesn't overflow fast
+fn number(n: i128, d: i128) -> Option<Value> {
+    fn gcd(a: i128, b: i128) -> i128 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+    let sign = d.signum();
+    let gcd = gcd(n.checked_abs()?, d.checked_abs()?).max(1);
+    Some(Value::Number(n / gcd * sign, d / gcd * sign))
+}
+
+fn add_durations(lhs: CalendarDuration, rhs: CalendarDuration) -> Option<CalendarDuration> {
+    Some(CalendarDuration {
+        months: lhs.months.checked_add(rhs.months)?,
+        days: lhs.days.checked_add(rhs.days)?,
+        time: lhs.time.checked_add(&rhs.time)?,
+    })
+}
+
+/// The months and days stay in the calendar for a whole factor,
+/// with a fraction the duration is fixed like `CalendarDuration::to_timedelta`
+fn scale(duration: CalendarDuration, n: i128, d: i128) -> Option<CalendarDuration> {
+    if n % d == 0 {
+        return duration.checked_mul(i64::try_from(n / d).ok()?);
+    }
+    let time = scale_timedelta(duration.to_timedelta()?, n, d)?;
+    Some(CalendarDuration {
+        months: 0,
+        days: 0,
+        time,
+    })
+}
+
+/// Evaluates an expression like `now + 3d - 2h30m` or `(2025-01-01 - now) / 2`
+///
+/// The operators are `+ - * /` between spaces with parentheses,
+/// a date and a duration are added in `zone` like `CalendarDuration::add_to_zoned`
+/// when it is a time zone name,
+/// two dates subtracted are a duration and two durations divided are a number
+///
+/// A literal is a number, a duration like `CalendarDuration::from_str`
+/// or what `literal` makes of it, like a date or a previous result
+pub fn eval(
+    expression: &str,
+    zone: Zone,
+    literal: impl FnMut(&str) -> Option<Value>,
+) -> Result<Value, Error> {
+    let mut parser = Parser {
+        expression,
+        tokens: tokenize(expression),
+        next: 0,
+        zone,
+        literal,
+        depth: 0,
+    };
+    let value = parser.sum()?;
+    if parser.next < parser.tokens.len() {
+        return Err(parser.error(parser.at(), "expected an operator"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+#[test]
+fn expr() {
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let date =
+        |date: &str| chrono::DateTime::parse_from_rfc3339(&format!("{date}T00:00:00+00:00")).ok();
+    let eval = |expression: &str| eval(expression, zone, |literal| date(literal).map(Value::Date));
+    let hours = |hours| {
+        Value::Duration(CalendarDuration {
+            months: 0,
+            days: 0,
+            time: chrono::TimeDelta::hours(hours),
+        })
+    };
+
+    assert_eq!(
+        eval("2025-01-01 + 3d - 2h30m"),
+        Ok(Value::Date(
+            date("2025-01-03").unwrap() + chrono::TimeDelta::minutes(21 * 60 + 30)
+        ))
+    );
+    assert_eq!(eval("(2025-01-03 - 2025-01-01) / 2"), Ok(hours(24)));
+    assert_eq!(eval("-(1h + 2h) * 2"), Ok(hours(-6)));
+    assert_eq!(eval("P1D / PT1H"), Ok(Value::Number(24, 1)));
+    assert_eq!(eval("1.5 * 4 - 1"), Ok(Value::Number(5, 1)));
+    assert_eq!(
+        eval("2025-01-31 + P1M"),
+        Ok(Value::Date(date("2025-02-28").unwrap()))
+    );
+
+    assert_eq!(
+        eval("2025-01-01 + 2025-01-02"),
+        Err(Error::InvalidExpression(
+            "at 12: cannot add a date and a date".to_owned()
+        ))
+    );
+    assert_eq!(
+        eval("(1h + 2h"),
+        Err(Error::InvalidExpression("at 9: expected `)`".to_owned()))
+    );
+    assert_eq!(
+        eval("1h / 0"),
+        Err(Error::InvalidExpression(
+            "at 4: division by zero".to_owned()
+        ))
+    );
+    assert_eq!(
+        eval(&"(".repeat(100_000)),
+        Err(Error::InvalidExpression(
+            "at 257: expression is nested too deeply".to_owned()
+        ))
+    );
+    assert_eq!(
+        eval(&format!("{}1h{}", "(".repeat(256), ")".repeat(256))),
+        Ok(hours(1))
+    );
+    assert!(eval("1h +").is_err());
+    assert!(eval("1h soon").is_err());
+}
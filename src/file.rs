@@ -0,0 +1,36 @@
+/// The times of a file, the ones that the system doesn't keep are none
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileTimes {
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+    pub accessed: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the metadata was changed, the ctime of unix
+    pub changed: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the file was created, the birth time
+    pub born: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Reads the times of the file at `path`, a symlink is followed
+pub fn file_times(path: &std::path::Path) -> std::io::Result<FileTimes> {
+    let metadata = std::fs::metadata(path)?;
+    let time = |time: std::io::Result<std::time::SystemTime>| time.ok().map(Into::into);
+
+    #[cfg(unix)]
+    let changed = {
+        use std::os::unix::fs::MetadataExt;
+        chrono::DateTime::from_timestamp(metadata.ctime(), metadata.ctime_nsec() as u32)
+    };
+    #[cfg(not(unix))]
+    let changed = None;
+
+    Ok(FileTimes {
+        modified: time(metadata.modified()),
+        accessed: time(metadata.accessed()),
+        changed,
+        born: time(metadata.created()),
+    })
+}
+
+/// The time the file at `path` was last modified, a symlink is followed
+pub fn file_mtime(path: &std::path::Path) -> std::io::Result<chrono::DateTime<chrono::Utc>> {
+    std::fs::metadata(path)?.modified().map(Into::into)
+}
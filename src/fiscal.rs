@@ -0,0 +1,93 @@
+use chrono::Datelike;
+
+/// A fiscal year that starts on the first day of the month `start`, like 4 in the UK
+/// or 10 for the US federal government, it is named by the calendar year that it ends in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiscalYear {
+    pub start: u32,
+}
+
+impl Default for FiscalYear {
+    fn default() -> Self {
+        Self { start: 1 }
+    }
+}
+
+impl FiscalYear {
+    /// The fiscal year of `date`, October 2024 is in FY2025 when it starts in October
+    pub fn year(self, date: chrono::NaiveDate) -> i32 {
+        if self.start > 1 && date.month() >= self.start {
+            date.year() + 1
+        } else {
+            date.year()
+        }
+    }
+
+    /// The quarter of `date` in its fiscal year, from 1 to 4
+    pub fn quarter(self, date: chrono::NaiveDate) -> u32 {
+        (date.month() + 12 - self.start) % 12 / 3 + 1
+    }
+
+    /// The first day of the fiscal `year`
+    pub fn start_of_year(self, year: i32) -> Option<chrono::NaiveDate> {
+        let year = if self.start > 1 { year - 1 } else { year };
+        chrono::NaiveDate::from_ymd_opt(year, self.start, 1)
+    }
+
+    /// The first and the last day of the `quarter` of the fiscal `year`
+    pub fn quarter_bounds(
+        self,
+        year: i32,
+        quarter: u32,
+    ) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+        let start = self
+            .start_of_year(year)?
+            .checked_add_months(chrono::Months::new((quarter - 1) * 3))?;
+        let end = start
+            .checked_add_months(chrono::Months::new(3))?
+            .pred_opt()?;
+        Some((start, end))
+    }
+
+    /// The first and the last day of the fiscal `year`
+    pub fn year_bounds(self, year: i32) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+        let start = self.start_of_year(year)?;
+        let end = start
+            .checked_add_months(chrono::Months::new(12))?
+            .pred_opt()?;
+        Some((start, end))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn fiscal() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let us = FiscalYear { start: 10 };
+    assert_eq!(us.year(date(2024, 10, 1)), 2025);
+    assert_eq!(us.year(date(2024, 9, 30)), 2024);
+    assert_eq!(us.quarter(date(2024, 10, 1)), 1);
+    assert_eq!(us.quarter(date(2025, 9, 30)), 4);
+    assert_eq!(
+        us.year_bounds(2025),
+        Some((date(2024, 10, 1), date(2025, 9, 30)))
+    );
+    assert_eq!(
+        us.quarter_bounds(2025, 2),
+        Some((date(2025, 1, 1), date(2025, 3, 31)))
+    );
+
+    let uk = FiscalYear { start: 4 };
+    assert_eq!(uk.year(date(2025, 3, 31)), 2025);
+    assert_eq!(uk.quarter(date(2025, 3, 31)), 4);
+    assert_eq!(uk.quarter(date(2025, 4, 1)), 1);
+
+    let calendar = FiscalYear::default();
+    assert_eq!(calendar.year(date(2025, 12, 31)), 2025);
+    assert_eq!(calendar.quarter(date(2025, 5, 1)), 2);
+    assert_eq!(
+        calendar.year_bounds(2025),
+        Some((date(2025, 1, 1), date(2025, 12, 31)))
+    );
+}
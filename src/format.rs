@@ -0,0 +1,89 @@
+/// A name that can be used with `-f` and `-F` instead of a format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatPreset {
+    pub name: &'static str,
+    pub format: &'static str,
+    /// The format is always in UTC, like the `GMT` of `http-date`
+    pub utc: bool,
+    pub description: &'static str,
+}
+
+pub const FORMAT_PRESETS: &[FormatPreset] = &[
+    FormatPreset {
+        name: "rfc3339",
+        format: "%Y-%m-%dT%H:%M:%S%:z",
+        utc: false,
+        description: "Date and time like: 2024-04-22T18:20:29+03:00",
+    },
+    FormatPreset {
+        name: "rfc3339-nanos",
+        format: "%Y-%m-%dT%H:%M:%S%.9f%:z",
+        utc: false,
+        description: "Date and time like: 2024-04-22T18:20:29.306665267+03:00",
+    },
+    FormatPreset {
+        name: "rfc2822",
+        format: "%a, %d %b %Y %T %z",
+        utc: false,
+        description: "Date and time like: Mon, 22 Apr 2024 18:20:29 +0300, same as `date -R`",
+    },
+    FormatPreset {
+        name: "iso8601",
+        format: "%Y-%m-%dT%H:%M:%S%.f%:z",
+        utc: false,
+        description: "Date and time like: 2024-04-22T18:20:29.306+03:00",
+    },
+    FormatPreset {
+        name: "iso8601-basic",
+        format: "%Y%m%dT%H%M%S%z",
+        utc: false,
+        description: "Date and time like: 20240422T182029+0300",
+    },
+    FormatPreset {
+        name: "iso8601-date",
+        format: "%Y-%m-%d",
+        utc: false,
+        description: "Date like: 2024-04-22, in the `-o` offset",
+    },
+    FormatPreset {
+        name: "weekdate",
+        format: "%G-W%V-%u",
+        utc: false,
+        description:
+            "ISO 8601 week date like: 2024-W17-1, the Monday of the week 17, in the `-o` offset",
+    },
+    FormatPreset {
+        name: "sql",
+        format: "%Y-%m-%d %H:%M:%S",
+        utc: false,
+        description: "Date and time like: 2024-04-22 18:20:29, in the `-o` offset",
+    },
+    FormatPreset {
+        name: "unix",
+        format: "%s",
+        utc: false,
+        description: "Seconds since 1970-01-01T00:00:00Z like: 1713799229",
+    },
+    FormatPreset {
+        name: "http-date",
+        format: "%a, %d %b %Y %T GMT",
+        utc: true,
+        description: "Date and time like: Mon, 22 Apr 2024 15:20:29 GMT, always in UTC",
+    },
+    FormatPreset {
+        name: "clf",
+        format: "%d/%b/%Y:%H:%M:%S %z",
+        utc: false,
+        description: "Common log format like: 22/Apr/2024:18:20:29 +0300",
+    },
+    FormatPreset {
+        name: "ctime",
+        format: "%a %b %e %T %Y",
+        utc: false,
+        description: "Date and time like: Mon Apr 22 18:20:29 2024, in the `-o` offset",
+    },
+];
+
+pub fn find_preset(name: &str) -> Option<&'static FormatPreset> {
+    FORMAT_PRESETS.iter().find(|preset| preset.name == name)
+}
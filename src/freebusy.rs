@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use crate::{BusinessCalendar, Error, Interval, Zone};
+
+/// The hours of a working day like `9-17` or `09:00-17:30`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkingHours {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self {
+            start: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: chrono::NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl FromStr for WorkingHours {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let time = |time: &str| {
+            let time = time.trim();
+            match time.parse::<u32>() {
+                Ok(hour) => chrono::NaiveTime::from_hms_opt(hour, 0, 0),
+                Err(_) => chrono::NaiveTime::parse_from_str(time, "%H:%M").ok(),
+            }
+        };
+        let hours = s
+            .split_once('-')
+            .and_then(|(start, end)| Some((time(start)?, time(end)?)));
+        match hours {
+            Some((start, end)) if start < end => Ok(Self { start, end }),
+            _ => Err(Error::InvalidWorkingHours),
+        }
+    }
+}
+
+impl std::fmt::Display for WorkingHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl WorkingHours {
+    /// The working hours of the business days from `from` to `to` in the wall clock of `zone`,
+    /// cut to `from` and `to`
+    pub fn windows(
+        &self,
+        from: chrono::DateTime<chrono::FixedOffset>,
+        to: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        calendar: &BusinessCalendar,
+    ) -> Vec<Interval> {
+        let first = zone.from_utc_datetime(&from.naive_utc()).date_naive();
+        let last = zone.from_utc_datetime(&to.naive_utc()).date_naive();
+        first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .filter(|day| calendar.is_business_day(*day))
+            .filter_map(|day| {
+                let start = zone.resolve_local(&day.and_time(self.start))?.max(from);
+                let end = zone.resolve_local(&day.and_time(self.end))?.min(to);
+                (start < end).then_some(Interval { start, end })
+            })
+            .collect()
+    }
+}
+
+/// The times that are in both `a` and `b`, they are sorted by start and don't overlap
+pub fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let (mut i, mut j) = (0, 0);
+    let mut common = Vec::new();
+    while let (Some(x), Some(y)) = (a.get(i), b.get(j)) {
+        common.extend(x.intersection(y));
+        if x.end < y.end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// The busy times sorted by start, with the ones that overlap or touch joined
+pub fn merge_busy(busy: impl IntoIterator<Item = Interval>) -> Vec<Interval> {
+    let mut busy: Vec<Interval> = busy
+        .into_iter()
+        .filter(|slot| slot.start < slot.end)
+        .collect();
+    busy.sort_unstable_by_key(|slot| slot.start);
+
+    let mut merged: Vec<Interval> = Vec::with_capacity(busy.len());
+    for slot in busy {
+        match merged.last_mut() {
+            Some(last) if slot.start <= last.end => last.end = last.end.max(slot.end),
+            _ => merged.push(slot),
+        }
+    }
+    merged
+}
+
+/// The parts of the `windows` that are not in the `busy` times of `merge_busy`
+/// and are at least `min` long
+pub fn free_slots(
+    windows: &[Interval],
+    busy: &[Interval],
+    min: chrono::TimeDelta,
+) -> Vec<Interval> {
+    let mut free = Vec::new();
+    for window in windows {
+        let mut start = window.start;
+        for slot in busy
+            .iter()
+            .filter(|slot| slot.end > window.start && slot.start < window.end)
+        {
+            if slot.start > start {
+                free.push(Interval {
+                    start,
+                    end: slot.start,
+                });
+            }
+            start = start.max(slot.end);
+        }
+        if start < window.end {
+            free.push(Interval {
+                start,
+                end: window.end,
+            });
+        }
+    }
+    free.retain(|slot| slot.duration() >= min);
+    free
+}
+
+#[cfg(test)]
+#[test]
+fn free() {
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+    let interval = |start, end| Interval {
+        start: date(start),
+        end: date(end),
+    };
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(2 * 3600).unwrap());
+
+    let hours = WorkingHours::from_str("9-17:30").unwrap();
+    assert_eq!(hours.to_string(), "09:00-17:30");
+    assert!(WorkingHours::from_str("17-9").is_err());
+    assert!(WorkingHours::from_str("9").is_err());
+
+    // 2025-01-03 is a Friday, so the weekend is left out
+    let windows = hours.windows(
+        date("2025-01-03T10:00:00+02:00"),
+        date("2025-01-06T12:00:00+02:00"),
+        zone,
+        &BusinessCalendar::default(),
+    );
+    assert_eq!(
+        windows,
+        [
+            interval("2025-01-03T10:00:00+02:00", "2025-01-03T17:30:00+02:00"),
+            interval("2025-01-06T09:00:00+02:00", "2025-01-06T12:00:00+02:00"),
+        ]
+    );
+
+    let busy = merge_busy([
+        interval("2025-01-03T11:00:00+02:00", "2025-01-03T12:00:00+02:00"),
+        interval("2025-01-03T11:30:00+02:00", "2025-01-03T13:00:00+02:00"),
+        interval("2025-01-03T13:00:00+02:00", "2025-01-03T13:15:00+02:00"),
+        interval("2025-01-03T17:00:00+02:00", "2025-01-03T17:10:00+02:00"),
+        interval("2025-01-06T08:00:00+02:00", "2025-01-06T09:30:00+02:00"),
+    ]);
+    assert_eq!(busy.len(), 3);
+    assert_eq!(
+        intersect(&windows, &busy),
+        [
+            interval("2025-01-03T11:00:00+02:00", "2025-01-03T13:15:00+02:00"),
+            interval("2025-01-03T17:00:00+02:00", "2025-01-03T17:10:00+02:00"),
+            interval("2025-01-06T09:00:00+02:00", "2025-01-06T09:30:00+02:00"),
+        ]
+    );
+    assert_eq!(
+        free_slots(&windows, &busy, chrono::TimeDelta::minutes(30)),
+        [
+            interval("2025-01-03T10:00:00+02:00", "2025-01-03T11:00:00+02:00"),
+            interval("2025-01-03T13:15:00+02:00", "2025-01-03T17:00:00+02:00"),
+            interval("2025-01-06T09:30:00+02:00", "2025-01-06T12:00:00+02:00"),
+        ]
+    );
+}
@@ -0,0 +1,293 @@
+use crate::{parse_date_lenient, Zone, AUTO_FORMATS, FORMAT_PRESETS};
+
+/// A format that can parse the sample
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuessedFormat {
+    pub format: String,
+    /// Formatting the parsed sample with `format` gives back the sample
+    pub round_trip: bool,
+}
+
+/// Finds the formats that can parse `sample`, the most likely is the first
+///
+/// The sample is split in to numbers, words and the rest,
+/// every part gets the format items that it can be, like `04` can be `%m` or `%d`,
+/// then every combination is tried on the sample.
+/// The presets and the auto formats are tried too.
+pub fn guess_format(sample: &str) -> Vec<GuessedFormat> {
+    let tokens = tokenize(sample);
+    let slots = slots(&tokens);
+
+    let mut candidates = Vec::new();
+    combine(&slots, String::new(), &mut candidates);
+    candidates.extend(FORMAT_PRESETS.iter().map(|preset| preset.format.to_owned()));
+    candidates.extend(AUTO_FORMATS.iter().map(|(_, format)| (*format).to_owned()));
+
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let mut out = Vec::<GuessedFormat>::new();
+    for format in candidates {
+        if out.iter().any(|guessed| guessed.format == format) {
+            continue;
+        }
+        let Ok(items) = chrono::format::strftime::StrftimeItems::new(&format).parse() else {
+            continue;
+        };
+        let Ok(date) = parse_date_lenient(&items, sample, utc) else {
+            continue;
+        };
+        let round_trip = date.format_with_items(items.iter()).to_string() == sample;
+        out.push(GuessedFormat { format, round_trip });
+    }
+
+    // stable, so the order of the slots is kept for the same round trip
+    out.sort_by_key(|guessed| !guessed.round_trip);
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Number(&'a str),
+    Word(&'a str),
+    Other(char),
+}
+
+fn tokenize(sample: &str) -> Vec<Token<'_>> {
+    let mut out = Vec::new();
+    let mut rest = sample;
+
+    while let Some(char) = rest.chars().next() {
+        let len = if char.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            out.push(Token::Number(&rest[..len]));
+            len
+        } else if char.is_alphabetic() {
+            let len = rest
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(rest.len());
+            out.push(Token::Word(&rest[..len]));
+            len
+        } else {
+            out.push(Token::Other(char));
+            char.len_utf8()
+        };
+        rest = &rest[len..];
+    }
+
+    out
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// What a word can be, the full name, the short name or the text itself
+fn word_slot(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    let named = |names: &[&str], short: &str, full: &str| {
+        if names.contains(&lower.as_str()) {
+            Some(vec![full.to_owned()])
+        } else if lower.len() == 3 && names.iter().any(|name| name.starts_with(&lower)) {
+            Some(vec![short.to_owned()])
+        } else {
+            None
+        }
+    };
+
+    if let Some(slot) = named(&MONTHS, "%b", "%B") {
+        return slot;
+    }
+    if let Some(slot) = named(&WEEKDAYS, "%a", "%A") {
+        return slot;
+    }
+
+    match word {
+        "AM" | "PM" => vec!["%p".to_owned()],
+        "am" | "pm" => vec!["%P".to_owned()],
+        _ => vec![word.replace('%', "%%")],
+    }
+}
+
+/// The format items for a number, the unpadded items are used for a single digit
+fn number_slot(number: &str, item: &str) -> String {
+    if number.len() == 1 {
+        item.replace('%', "%-")
+    } else {
+        item.to_owned()
+    }
+}
+
+/// Every part of the sample with the formats that it can be, from the most likely
+fn slots(tokens: &[Token]) -> Vec<Vec<String>> {
+    let twelve_hour = tokens.iter().any(|token| {
+        matches!(token, Token::Word(word) if ["am", "pm"].contains(&word.to_lowercase().as_str()))
+    });
+    let hour = if twelve_hour { "%I" } else { "%H" };
+    let is_month = |i: usize| match tokens.get(i) {
+        Some(Token::Word(word)) => ["%b", "%B"].contains(&word_slot(word)[0].as_str()),
+        _ => false,
+    };
+    let near_month = |i: usize| is_month(i.wrapping_sub(2)) || is_month(i + 2);
+
+    let mut out = Vec::new();
+    let mut seen_time = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        // H:M:S.f
+        if let [Token::Number(h), Token::Other(':'), Token::Number(m), rest @ ..] = &tokens[i..] {
+            if h.len() <= 2 && m.len() == 2 {
+                let mut slot = format!("{}:%M", number_slot(h, hour));
+                i += 3;
+                if let [Token::Other(':'), Token::Number(_), ..] = rest {
+                    slot.push_str(":%S");
+                    i += 2;
+                    if let [Token::Other('.'), Token::Number(f), ..] = &tokens[i..] {
+                        slot.push_str(&match f.len() {
+                            3 | 6 | 9 => format!("%.{}f", f.len()),
+                            _ => "%.f".to_owned(),
+                        });
+                        i += 2;
+                    }
+                }
+                out.push(vec![slot]);
+                seen_time = true;
+                continue;
+            }
+        }
+
+        // +0300, +03:00 after the time
+        if let [Token::Other('+' | '-'), rest @ ..] = &tokens[i..] {
+            if seen_time {
+                match rest {
+                    [Token::Number(hm), ..] if hm.len() == 4 => {
+                        out.push(vec!["%z".to_owned()]);
+                        i += 2;
+                        continue;
+                    }
+                    [Token::Number(h), Token::Other(':'), Token::Number(m), ..]
+                        if h.len() == 2 && m.len() == 2 =>
+                    {
+                        out.push(vec!["%:z".to_owned()]);
+                        i += 4;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Y-m-d, d/m/Y, m/d/Y, d.m.Y
+        if let [Token::Number(a), Token::Other(sep @ ('-' | '/' | '.')), Token::Number(b), Token::Other(sep2), Token::Number(c), ..] =
+            &tokens[i..]
+        {
+            if sep == sep2 && a.len() <= 4 && b.len() <= 2 && c.len() <= 4 {
+                let mut slot = Vec::new();
+                let join = |items: [(&str, &str); 3]| {
+                    items
+                        .iter()
+                        .map(|(number, item)| number_slot(number, item))
+                        .collect::<Vec<_>>()
+                        .join(&sep.to_string())
+                };
+                if a.len() == 4 {
+                    slot.push(join([(a, "%Y"), (b, "%m"), (c, "%d")]));
+                    slot.push(join([(a, "%Y"), (b, "%d"), (c, "%m")]));
+                } else {
+                    let year = if c.len() == 4 { "%Y" } else { "%y" };
+                    let day_month = join([(a, "%d"), (b, "%m"), (c, year)]);
+                    let month_day = join([(a, "%m"), (b, "%d"), (c, year)]);
+                    if *sep == '/' {
+                        slot.push(month_day);
+                        slot.push(day_month);
+                    } else {
+                        slot.push(day_month);
+                        slot.push(month_day);
+                    }
+                }
+                out.push(slot);
+                i += 5;
+                continue;
+            }
+        }
+
+        match tokens[i] {
+            Token::Number(number) => out.push(match number.len() {
+                10 => vec!["%s".to_owned()],
+                8 => vec!["%Y%m%d".to_owned()],
+                6 => vec!["%H%M%S".to_owned(), "%y%m%d".to_owned()],
+                4 => vec!["%Y".to_owned(), "%H%M".to_owned()],
+                1 | 2 if near_month(i) => vec![number_slot(number, "%d")],
+                1 | 2 => ["%d", "%m", hour, "%y"]
+                    .iter()
+                    .map(|item| number_slot(number, item))
+                    .collect(),
+                _ => vec![number.to_owned()],
+            }),
+            Token::Word(word) => out.push(word_slot(word)),
+            Token::Other('%') => out.push(vec!["%%".to_owned()]),
+            Token::Other(char) => out.push(vec![char.to_string()]),
+        }
+        i += 1;
+    }
+
+    out
+}
+
+/// Every combination of the slots, in the order of the most likely
+fn combine(slots: &[Vec<String>], prefix: String, out: &mut Vec<String>) {
+    const MAX: usize = 256;
+
+    let Some((slot, rest)) = slots.split_first() else {
+        out.push(prefix);
+        return;
+    };
+
+    for item in slot {
+        if out.len() >= MAX {
+            return;
+        }
+        combine(rest, format!("{prefix}{item}"), out);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn guess() {
+    let best = |sample| {
+        guess_format(sample)
+            .first()
+            .map(|guessed| guessed.format.clone())
+    };
+
+    assert_eq!(
+        best("22/Apr/2024:18:20:29 +0300").as_deref(),
+        Some("%d/%b/%Y:%H:%M:%S %z")
+    );
+    assert_eq!(best("2024-04-22 7:03").as_deref(), Some("%Y-%m-%d %-H:%M"));
+    assert_eq!(best("04/25/2024").as_deref(), Some("%m/%d/%Y"));
+    assert_eq!(best("25.04.2024").as_deref(), Some("%d.%m.%Y"));
+    assert_eq!(best("not a date"), None);
+}
@@ -0,0 +1,343 @@
+use crate::NthWeekday;
+
+/// When a holiday is in a year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayRule {
+    /// Every year on the same day, like December 25
+    Fixed { month: u32, day: u32 },
+    /// Like the 4th Thursday of November
+    Nth { month: u32, nth: NthWeekday },
+    /// Days from the Easter Sunday, Good Friday is `-2`
+    Easter { days: i64, orthodox: bool },
+}
+
+impl HolidayRule {
+    /// The day of the holiday in `year`
+    pub fn date(self, year: i32) -> Option<chrono::NaiveDate> {
+        match self {
+            HolidayRule::Fixed { month, day } => chrono::NaiveDate::from_ymd_opt(year, month, day),
+            HolidayRule::Nth { month, nth } => nth.in_month(year, month),
+            HolidayRule::Easter { days, orthodox } => {
+                easter(year, orthodox)?.checked_add_signed(chrono::TimeDelta::days(days))
+            }
+        }
+    }
+}
+
+/// A public holiday, it is a holiday from the year `since`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holiday {
+    pub name: &'static str,
+    pub rule: HolidayRule,
+    pub since: i32,
+}
+
+impl Holiday {
+    /// The day of the holiday in `year`, none before `since`
+    pub fn date(self, year: i32) -> Option<chrono::NaiveDate> {
+        if year < self.since {
+            return None;
+        }
+        self.rule.date(year)
+    }
+}
+
+/// The Easter Sunday of `year` in the Gregorian calendar, with `orthodox`
+/// it is the Orthodox Easter that is computed in the Julian calendar
+pub fn easter(year: i32, orthodox: bool) -> Option<chrono::NaiveDate> {
+    if orthodox {
+        let a = year.rem_euclid(4);
+        let b = year.rem_euclid(7);
+        let c = year.rem_euclid(19);
+        let d = (19 * c + 15) % 30;
+        let e = (2 * a + 4 * b - d + 34) % 7;
+        let month = (d + e + 114) / 31;
+        let day = (d + e + 114) % 31 + 1;
+
+        // the Julian calendar is behind by the leap days that the Gregorian one skips
+        let julian = chrono::NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
+        let behind = year.div_euclid(100) - year.div_euclid(400) - 2;
+        return julian.checked_add_signed(chrono::TimeDelta::days(behind as i64));
+    }
+
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    chrono::NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// The feasts that move with Easter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Feast {
+    AshWednesday,
+    PalmSunday,
+    MaundyThursday,
+    GoodFriday,
+    HolySaturday,
+    #[default]
+    Easter,
+    EasterMonday,
+    Ascension,
+    Pentecost,
+    WhitMonday,
+    TrinitySunday,
+    CorpusChristi,
+}
+
+impl Feast {
+    pub const ALL: [Self; 12] = [
+        Feast::AshWednesday,
+        Feast::PalmSunday,
+        Feast::MaundyThursday,
+        Feast::GoodFriday,
+        Feast::HolySaturday,
+        Feast::Easter,
+        Feast::EasterMonday,
+        Feast::Ascension,
+        Feast::Pentecost,
+        Feast::WhitMonday,
+        Feast::TrinitySunday,
+        Feast::CorpusChristi,
+    ];
+
+    /// The days from the Easter Sunday
+    pub fn days(self) -> i64 {
+        match self {
+            Feast::AshWednesday => -46,
+            Feast::PalmSunday => -7,
+            Feast::MaundyThursday => -3,
+            Feast::GoodFriday => -2,
+            Feast::HolySaturday => -1,
+            Feast::Easter => 0,
+            Feast::EasterMonday => 1,
+            Feast::Ascension => 39,
+            Feast::Pentecost => 49,
+            Feast::WhitMonday => 50,
+            Feast::TrinitySunday => 56,
+            Feast::CorpusChristi => 60,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feast::AshWednesday => "Ash Wednesday",
+            Feast::PalmSunday => "Palm Sunday",
+            Feast::MaundyThursday => "Maundy Thursday",
+            Feast::GoodFriday => "Good Friday",
+            Feast::HolySaturday => "Holy Saturday",
+            Feast::Easter => "Easter Sunday",
+            Feast::EasterMonday => "Easter Monday",
+            Feast::Ascension => "Ascension",
+            Feast::Pentecost => "Pentecost",
+            Feast::WhitMonday => "Whit Monday",
+            Feast::TrinitySunday => "Trinity Sunday",
+            Feast::CorpusChristi => "Corpus Christi",
+        }
+    }
+
+    /// The day of the feast in `year`, with `orthodox` from the Orthodox Easter
+    pub fn date(self, year: i32, orthodox: bool) -> Option<chrono::NaiveDate> {
+        HolidayRule::Easter {
+            days: self.days(),
+            orthodox,
+        }
+        .date(year)
+    }
+}
+
+#[cfg(feature = "holidays")]
+const fn fixed(name: &'static str, month: u32, day: u32) -> Holiday {
+    Holiday {
+        name,
+        rule: HolidayRule::Fixed { month, day },
+        since: i32::MIN,
+    }
+}
+
+#[cfg(feature = "holidays")]
+const fn nth(name: &'static str, n: i8, weekday: chrono::Weekday, month: u32) -> Holiday {
+    Holiday {
+        name,
+        rule: HolidayRule::Nth {
+            month,
+            nth: NthWeekday { n, weekday },
+        },
+        since: i32::MIN,
+    }
+}
+
+#[cfg(feature = "holidays")]
+const fn easter_day(name: &'static str, days: i64, orthodox: bool) -> Holiday {
+    Holiday {
+        name,
+        rule: HolidayRule::Easter { days, orthodox },
+        since: i32::MIN,
+    }
+}
+
+#[cfg(feature = "holidays")]
+const fn since(holiday: Holiday, year: i32) -> Holiday {
+    Holiday {
+        since: year,
+        ..holiday
+    }
+}
+
+/// The national public holidays of the countries by their ISO 3166 code,
+/// the days themselves and not the days off when they are in the weekend
+#[cfg(feature = "holidays")]
+pub const COUNTRIES: &[(&str, &[Holiday])] = {
+    use chrono::Weekday::{Mon, Thu};
+
+    &[
+        (
+            "DE",
+            &[
+                fixed("Neujahr", 1, 1),
+                easter_day("Karfreitag", -2, false),
+                easter_day("Ostermontag", 1, false),
+                fixed("Tag der Arbeit", 5, 1),
+                easter_day("Christi Himmelfahrt", 39, false),
+                easter_day("Pfingstmontag", 50, false),
+                fixed("Tag der Deutschen Einheit", 10, 3),
+                fixed("1. Weihnachtstag", 12, 25),
+                fixed("2. Weihnachtstag", 12, 26),
+            ],
+        ),
+        (
+            "ES",
+            &[
+                fixed("Año Nuevo", 1, 1),
+                fixed("Epifanía del Señor", 1, 6),
+                easter_day("Viernes Santo", -2, false),
+                fixed("Fiesta del Trabajo", 5, 1),
+                fixed("Asunción de la Virgen", 8, 15),
+                fixed("Fiesta Nacional de España", 10, 12),
+                fixed("Todos los Santos", 11, 1),
+                fixed("Día de la Constitución", 12, 6),
+                fixed("Inmaculada Concepción", 12, 8),
+                fixed("Navidad", 12, 25),
+            ],
+        ),
+        (
+            "FR",
+            &[
+                fixed("Jour de l'an", 1, 1),
+                easter_day("Lundi de Pâques", 1, false),
+                fixed("Fête du Travail", 5, 1),
+                fixed("Victoire 1945", 5, 8),
+                easter_day("Ascension", 39, false),
+                easter_day("Lundi de Pentecôte", 50, false),
+                fixed("Fête nationale", 7, 14),
+                fixed("Assomption", 8, 15),
+                fixed("Toussaint", 11, 1),
+                fixed("Armistice 1918", 11, 11),
+                fixed("Noël", 12, 25),
+            ],
+        ),
+        (
+            "GB",
+            &[
+                fixed("New Year's Day", 1, 1),
+                easter_day("Good Friday", -2, false),
+                easter_day("Easter Monday", 1, false),
+                nth("Early May Bank Holiday", 1, Mon, 5),
+                nth("Spring Bank Holiday", -1, Mon, 5),
+                nth("Summer Bank Holiday", -1, Mon, 8),
+                fixed("Christmas Day", 12, 25),
+                fixed("Boxing Day", 12, 26),
+            ],
+        ),
+        (
+            "RO",
+            &[
+                fixed("Anul Nou", 1, 1),
+                fixed("Anul Nou", 1, 2),
+                since(fixed("Boboteaza", 1, 6), 2024),
+                since(fixed("Sfântul Ioan", 1, 7), 2024),
+                fixed("Ziua Unirii Principatelor Române", 1, 24),
+                easter_day("Vinerea Mare", -2, true),
+                easter_day("Paștele", 0, true),
+                easter_day("Paștele", 1, true),
+                fixed("Ziua Muncii", 5, 1),
+                fixed("Ziua Copilului", 6, 1),
+                easter_day("Rusaliile", 49, true),
+                easter_day("Rusaliile", 50, true),
+                fixed("Adormirea Maicii Domnului", 8, 15),
+                fixed("Sfântul Andrei", 11, 30),
+                fixed("Ziua Națională a României", 12, 1),
+                fixed("Crăciunul", 12, 25),
+                fixed("Crăciunul", 12, 26),
+            ],
+        ),
+        (
+            "US",
+            &[
+                fixed("New Year's Day", 1, 1),
+                nth("Martin Luther King Jr. Day", 3, Mon, 1),
+                nth("Washington's Birthday", 3, Mon, 2),
+                nth("Memorial Day", -1, Mon, 5),
+                since(fixed("Juneteenth", 6, 19), 2021),
+                fixed("Independence Day", 7, 4),
+                nth("Labor Day", 1, Mon, 9),
+                nth("Columbus Day", 2, Mon, 10),
+                fixed("Veterans Day", 11, 11),
+                nth("Thanksgiving Day", 4, Thu, 11),
+                fixed("Christmas Day", 12, 25),
+            ],
+        ),
+    ]
+};
+
+/// The public holidays of a country like `RO` or `us`
+#[cfg(feature = "holidays")]
+pub fn country(code: &str) -> Option<&'static [Holiday]> {
+    COUNTRIES
+        .iter()
+        .find(|(country, _)| country.eq_ignore_ascii_case(code))
+        .map(|(_, holidays)| *holidays)
+}
+
+#[cfg(test)]
+#[test]
+fn holidays() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d);
+
+    assert_eq!(easter(2024, false), date(2024, 3, 31));
+    assert_eq!(easter(2025, false), date(2025, 4, 20));
+    assert_eq!(easter(2023, false), date(2023, 4, 9));
+    assert_eq!(easter(2024, true), date(2024, 5, 5));
+    assert_eq!(easter(2025, true), date(2025, 4, 20));
+    assert_eq!(easter(2023, true), date(2023, 4, 16));
+    assert_eq!(Feast::GoodFriday.date(2025, false), date(2025, 4, 18));
+    assert_eq!(Feast::Pentecost.date(2024, false), date(2024, 5, 19));
+    assert_eq!(Feast::Pentecost.date(2024, true), date(2024, 6, 23));
+    assert_eq!(Feast::AshWednesday.date(2024, false), date(2024, 2, 14));
+
+    #[cfg(feature = "holidays")]
+    {
+        let days = |code, year| {
+            country(code)
+                .unwrap()
+                .iter()
+                .filter_map(|holiday| holiday.date(year))
+                .collect::<Vec<_>>()
+        };
+        assert!(days("us", 2025).contains(&date(2025, 11, 27).unwrap()));
+        assert!(days("US", 2020).len() == 10);
+        assert!(days("RO", 2024).contains(&date(2024, 6, 24).unwrap()));
+        assert!(days("GB", 2025).contains(&date(2025, 8, 25).unwrap()));
+        assert_eq!(country("XX"), None);
+    }
+}
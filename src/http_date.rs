@@ -0,0 +1,71 @@
+use crate::Error;
+
+type Utc = chrono::DateTime<chrono::Utc>;
+
+/// The `Date` header of a web server and how much the local clock is behind it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpDate {
+    pub date: chrono::DateTime<chrono::FixedOffset>,
+    /// How much the local clock is behind the server, it is negative when it is ahead,
+    /// the `Date` has whole seconds so it is within a second
+    pub offset: chrono::TimeDelta,
+    /// The time from the request to the answer
+    pub delay: chrono::TimeDelta,
+}
+
+/// Sends a HEAD request to the `url` and reads the `Date` of the answer,
+/// the redirects are not followed and the url without a scheme is https
+pub fn query_http_date(url: &str, timeout: std::time::Duration) -> Result<HttpDate, Error> {
+    let url = if url.contains("://") {
+        url.to_owned()
+    } else {
+        format!("https://{url}")
+    };
+    let failed = |err: &dyn std::fmt::Display| Error::HttpFailed(format!("{url}: {err}"));
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .max_redirects(0)
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let sent = chrono::Utc::now();
+    let answer = agent.head(&url).call().map_err(|err| failed(&err))?;
+    let received = chrono::Utc::now();
+
+    let date = answer
+        .headers()
+        .get("date")
+        .ok_or_else(|| failed(&"the answer has no `Date` header"))?;
+    let date = date
+        .to_str()
+        .ok()
+        .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+        .ok_or_else(|| failed(&format!("the `Date` header is invalid: {date:?}")))?;
+    Ok(skew(date, sent, received))
+}
+
+/// The `date` was sent by the server in the middle of the request
+fn skew(date: chrono::DateTime<chrono::FixedOffset>, sent: Utc, received: Utc) -> HttpDate {
+    let delay = (received - sent).max(chrono::TimeDelta::zero());
+    HttpDate {
+        date,
+        offset: date.to_utc() - (sent + delay / 2),
+        delay,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn http_date() {
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+
+    let server = chrono::DateTime::parse_from_rfc2822("Wed, 01 Jan 2025 00:00:10 GMT").unwrap();
+    let http = skew(
+        server,
+        date("2025-01-01T00:00:00Z").to_utc(),
+        date("2025-01-01T00:00:01Z").to_utc(),
+    );
+    assert_eq!(http.delay, chrono::TimeDelta::seconds(1));
+    assert_eq!(http.offset, chrono::TimeDelta::milliseconds(9500));
+}
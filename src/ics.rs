@@ -0,0 +1,488 @@
+use std::str::FromStr;
+
+use crate::{CalendarDuration, Error, RRule, Zone};
+
+/// The time of `DTSTART`, `DTEND`, `RDATE`, `EXDATE` or `RECURRENCE-ID`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventTime {
+    /// A whole day like `DTSTART;VALUE=DATE:20250101`, in the active zone
+    Date(chrono::NaiveDate),
+    /// A wall clock time without a zone like `DTSTART:20250101T090000`, in the active zone
+    Floating(chrono::NaiveDateTime),
+    /// Like `DTSTART:20250101T090000Z`
+    Utc(chrono::NaiveDateTime),
+    /// Like `DTSTART;TZID=Europe/Berlin:20250101T090000`
+    Zoned(chrono::NaiveDateTime, chrono_tz::Tz),
+}
+
+impl EventTime {
+    fn local(&self) -> chrono::NaiveDateTime {
+        match self {
+            EventTime::Date(date) => date.and_time(Default::default()),
+            EventTime::Floating(local) | EventTime::Utc(local) | EventTime::Zoned(local, _) => {
+                *local
+            }
+        }
+    }
+
+    /// The zone of its wall clock, `zone` for the dates and the floating times
+    pub fn zone(&self, zone: Zone) -> Zone {
+        match self {
+            EventTime::Date(_) | EventTime::Floating(_) => zone,
+            EventTime::Utc(_) => Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap()),
+            EventTime::Zoned(_, tz) => Zone::Named(*tz),
+        }
+    }
+
+    /// The instant of it, see `Zone::resolve_local`
+    pub fn resolve(&self, zone: Zone) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.zone(zone).resolve_local(&self.local())
+    }
+}
+
+/// A `VEVENT` of an iCalendar file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub uid: Option<String>,
+    pub summary: String,
+    pub location: Option<String>,
+    pub start: EventTime,
+    pub end: Option<EventTime>,
+    /// The `DURATION` when it has no `DTEND`
+    pub duration: Option<CalendarDuration>,
+    pub rrule: Option<RRule>,
+    pub rdates: Vec<EventTime>,
+    pub exdates: Vec<EventTime>,
+    /// The occurrence of the recurring event with the same uid that this event changes
+    pub recurrence_id: Option<EventTime>,
+    /// `TRANSP:TRANSPARENT`, the time is not busy
+    pub transparent: bool,
+    /// `STATUS:CANCELLED`
+    pub cancelled: bool,
+}
+
+impl Event {
+    /// It is for whole days
+    pub fn all_day(&self) -> bool {
+        matches!(self.start, EventTime::Date(_))
+    }
+
+    /// The end of the occurrence that starts at `start`, from `DTEND` or `DURATION`,
+    /// an event without them is a day long when it is for whole days or else has no length
+    pub fn end_of(
+        &self,
+        start: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let event_zone = self.start.zone(zone);
+        match (self.duration, self.end) {
+            (Some(duration), _) => duration.add_to_zoned(start, event_zone),
+            (None, Some(end)) => {
+                start.checked_add_signed(end.resolve(zone)? - self.start.resolve(zone)?)
+            }
+            (None, None) if self.all_day() => CalendarDuration {
+                months: 0,
+                days: 1,
+                time: chrono::TimeDelta::zero(),
+            }
+            .add_to_zoned(start, event_zone),
+            (None, None) => Some(start),
+        }
+    }
+
+    /// The starts of the occurrences from `DTSTART`, `RRULE` and `RDATE`
+    /// without the ones of `EXDATE` and of `skip`
+    fn starts(
+        &self,
+        zone: Zone,
+        skip: Vec<chrono::DateTime<chrono::FixedOffset>>,
+    ) -> Box<dyn Iterator<Item = chrono::DateTime<chrono::FixedOffset>> + '_> {
+        let Some(start) = self.start.resolve(zone) else {
+            return Box::new(std::iter::empty());
+        };
+        let mut rdates: Vec<_> = self
+            .rdates
+            .iter()
+            .filter_map(|date| date.resolve(zone))
+            .collect();
+        rdates.sort_unstable();
+        let mut rdates = rdates.into_iter().peekable();
+        let rule: Box<dyn Iterator<Item = chrono::DateTime<chrono::FixedOffset>>> =
+            match &self.rrule {
+                Some(rrule) => Box::new(rrule.times(start, self.start.zone(zone))),
+                None => Box::new(std::iter::once(start)),
+            };
+        let mut rule = rule.peekable();
+
+        let exdates: Vec<_> = self
+            .exdates
+            .iter()
+            .filter_map(|date| date.resolve(zone))
+            .chain(skip)
+            .collect();
+        let mut last = None;
+        let merged = std::iter::from_fn(move || match (rule.peek(), rdates.peek()) {
+            (Some(a), Some(b)) if b < a => rdates.next(),
+            (Some(_), _) => rule.next(),
+            (None, _) => rdates.next(),
+        });
+        Box::new(merged.filter(move |date| {
+            // an `RDATE` can be an occurrence of the rule too
+            let new = last != Some(*date);
+            last = Some(*date);
+            new && !exdates.contains(date)
+        }))
+    }
+}
+
+/// An occurrence of an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occurrence<'a> {
+    pub event: &'a Event,
+    pub start: chrono::DateTime<chrono::FixedOffset>,
+    pub end: chrono::DateTime<chrono::FixedOffset>,
+}
+
+/// The occurrences of `occurrences`, by start
+pub struct Occurrences<'a> {
+    streams: Vec<std::iter::Peekable<Box<dyn Iterator<Item = Occurrence<'a>> + 'a>>>,
+}
+
+impl<'a> Iterator for Occurrences<'a> {
+    type Item = Occurrence<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.streams
+            .iter_mut()
+            .filter_map(|stream| Some((stream.peek()?.start, stream)))
+            .min_by_key(|(start, _)| *start)?
+            .1
+            .next()
+    }
+}
+
+/// The occurrences of all the events that end after `from` (or start at it) by start,
+/// the times without a zone are in `zone`, an event with a `RECURRENCE-ID`
+/// is there instead of the occurrence that it changes and the cancelled ones are left out
+pub fn occurrences(
+    events: &[Event],
+    from: chrono::DateTime<chrono::FixedOffset>,
+    zone: Zone,
+) -> Occurrences<'_> {
+    let streams = events
+        .iter()
+        .filter(|event| !event.cancelled)
+        .map(|event| {
+            let skip = if event.recurrence_id.is_some() {
+                Vec::new()
+            } else {
+                events
+                    .iter()
+                    .filter(|other| other.uid.is_some() && other.uid == event.uid)
+                    .filter_map(|other| other.recurrence_id?.resolve(zone))
+                    .collect()
+            };
+            let stream: Box<dyn Iterator<Item = Occurrence>> = Box::new(
+                event
+                    .starts(zone, skip)
+                    .filter_map(move |start| {
+                        Some(Occurrence {
+                            event,
+                            start,
+                            end: event.end_of(start, zone)?,
+                        })
+                    })
+                    .filter(move |occurrence| occurrence.end > from || occurrence.start >= from),
+            );
+            stream.peekable()
+        })
+        .collect();
+    Occurrences { streams }
+}
+
+/// Joins the lines that go on in the next lines that start with a space
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push((i + 1, line.to_owned())),
+        }
+    }
+    lines
+}
+
+/// The name, the parameters and the value of a line like `DTSTART;TZID=Europe/Berlin:20250101T090000`
+#[allow(clippy::type_complexity)]
+fn split_line(line: &str) -> Option<(String, Vec<(String, String)>, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            Some((name.to_uppercase(), value.trim_matches('"').to_owned()))
+        })
+        .collect();
+    Some((name, params, value))
+}
+
+fn unescape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => res.push('\n'),
+            Some(c) => res.push(c),
+            None => res.push('\\'),
+        }
+    }
+    res
+}
+
+/// A date or a date and time, in the zone of `TZID` when it is a time zone name
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<EventTime> {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    };
+    if param("VALUE") == Some("DATE") || value.len() == 8 {
+        return chrono::NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::Date);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        return chrono::NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(EventTime::Utc);
+    }
+    let local = chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // a `TZID` that is not a time zone name is from a `VTIMEZONE`, so it is like a floating time
+    let tz =
+        param("TZID").and_then(|tzid| chrono_tz::Tz::from_str(tzid.trim_start_matches('/')).ok());
+    Some(match tz {
+        Some(tz) => EventTime::Zoned(local, tz),
+        None => EventTime::Floating(local),
+    })
+}
+
+/// The events of an iCalendar (RFC 5545) file, the other components like `VTODO` are skipped
+pub fn parse_ics(text: &str) -> Result<Vec<Event>, Error> {
+    let mut events = Vec::new();
+    // the components that the line is in, like `VCALENDAR` and `VEVENT`
+    let mut components: Vec<String> = Vec::new();
+    let mut event: Option<(usize, Event)> = None;
+    let mut start = None;
+
+    for (line_number, line) in unfold(text) {
+        let err = |why: String| Error::InvalidIcs(format!("at line {line_number}, {why}"));
+        let Some((name, params, value)) = split_line(&line) else {
+            return Err(err(format!("`{line}` is not like NAME:VALUE")));
+        };
+        match name.as_str() {
+            "BEGIN" => {
+                let component = value.to_uppercase();
+                if component == "VEVENT" && components.iter().all(|c| c != "VEVENT") {
+                    event = Some((
+                        line_number,
+                        Event {
+                            uid: None,
+                            summary: String::new(),
+                            location: None,
+                            // set at the end, an event needs a `DTSTART`
+                            start: EventTime::Date(Default::default()),
+                            end: None,
+                            duration: None,
+                            rrule: None,
+                            rdates: Vec::new(),
+                            exdates: Vec::new(),
+                            recurrence_id: None,
+                            transparent: false,
+                            cancelled: false,
+                        },
+                    ));
+                    start = None;
+                }
+                components.push(component);
+                continue;
+            }
+            "END" => {
+                let component = value.to_uppercase();
+                if components.pop().as_ref() != Some(&component) {
+                    return Err(err(format!("END:{value} doesn't end the component")));
+                }
+                if component == "VEVENT" {
+                    if let Some((begin, mut done)) = event.take() {
+                        done.start = start.take().ok_or_else(|| {
+                            Error::InvalidIcs(format!("the event at line {begin} has no DTSTART"))
+                        })?;
+                        events.push(done);
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+        // the properties of a `VALARM` of the event are not of the event
+        let Some((_, event)) = event
+            .as_mut()
+            .filter(|_| components.last().is_some_and(|c| c == "VEVENT"))
+        else {
+            continue;
+        };
+
+        let time = |value: &str| {
+            parse_time(value, &params).ok_or_else(|| {
+                err(format!(
+                    "`{value}` is not a date like 20250101 or 20250101T090000"
+                ))
+            })
+        };
+        match name.as_str() {
+            "UID" => event.uid = Some(value.to_owned()),
+            "SUMMARY" => event.summary = unescape(value),
+            "LOCATION" => event.location = Some(unescape(value)),
+            "DTSTART" => start = Some(time(value)?),
+            "DTEND" => event.end = Some(time(value)?),
+            "DURATION" => {
+                event.duration = Some(
+                    CalendarDuration::from_str(value)
+                        .map_err(|_| err(format!("`{value}` is not a duration like PT1H")))?,
+                )
+            }
+            "RRULE" => {
+                event.rrule = Some(RRule::from_str(value).map_err(|rule| err(rule.to_string()))?)
+            }
+            "RDATE" => {
+                for date in value.split(',') {
+                    // a period like `20250101T090000Z/PT1H` starts at its start
+                    let date = date.split('/').next().unwrap_or(date);
+                    event.rdates.push(time(date)?);
+                }
+            }
+            "EXDATE" => {
+                for date in value.split(',') {
+                    event.exdates.push(time(date)?);
+                }
+            }
+            "RECURRENCE-ID" => event.recurrence_id = Some(time(value)?),
+            "TRANSP" => event.transparent = value.eq_ignore_ascii_case("TRANSPARENT"),
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    if let Some(component) = components.last() {
+        return Err(Error::InvalidIcs(format!("{component} has no END")));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+#[test]
+fn ics() {
+    let ics = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Stand\\, up\r
+DTSTART;TZID=Europe/Bucharest:20250106T093000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r
+EXDATE;TZID=Europe/Bucharest:20250108T093000\r
+BEGIN:VALARM\r
+SUMMARY:not the summary\r
+TRIGGER:-PT5M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+RECURRENCE-ID;TZID=Europe/Bucharest:20250110T093000\r
+SUMMARY:Stand up lat\r
+ e\r
+DTSTART;TZID=Europe/Bucharest:20250110T110000\r
+DTEND;TZID=Europe/Bucharest:20250110T113000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+RECURRENCE-ID;TZID=Europe/Bucharest:20250113T093000\r
+DTSTART;TZID=Europe/Bucharest:20250113T093000\r
+STATUS:CANCELLED\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20250107\r
+TRANSP:TRANSPARENT\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let events = parse_ics(ics).unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(events[3].transparent);
+    assert_eq!(events[0].summary, "Stand, up");
+    assert_eq!(events[1].summary, "Stand up late");
+
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let from = chrono::DateTime::parse_from_rfc3339("2025-01-06T07:40:00+00:00").unwrap();
+    let found: Vec<_> = occurrences(&events, from, zone)
+        .take(5)
+        .map(|occurrence| {
+            (
+                occurrence.event.summary.as_str(),
+                occurrence.start.to_rfc3339(),
+                occurrence.end.to_rfc3339(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            // it started at 07:30 in UTC and ends at 07:45
+            (
+                "Stand, up",
+                "2025-01-06T09:30:00+02:00".to_owned(),
+                "2025-01-06T09:45:00+02:00".to_owned()
+            ),
+            (
+                "Holiday",
+                "2025-01-07T00:00:00+00:00".to_owned(),
+                "2025-01-08T00:00:00+00:00".to_owned()
+            ),
+            (
+                "Stand up late",
+                "2025-01-10T11:00:00+02:00".to_owned(),
+                "2025-01-10T11:30:00+02:00".to_owned()
+            ),
+            // the one of 2025-01-13 is cancelled
+            (
+                "Stand, up",
+                "2025-01-15T09:30:00+02:00".to_owned(),
+                "2025-01-15T09:45:00+02:00".to_owned()
+            ),
+            (
+                "Stand, up",
+                "2025-01-17T09:30:00+02:00".to_owned(),
+                "2025-01-17T09:45:00+02:00".to_owned()
+            ),
+        ]
+    );
+
+    assert!(parse_ics("BEGIN:VEVENT\nSUMMARY:no start\nEND:VEVENT\n").is_err());
+    assert!(parse_ics("BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:2025\nEND:VEVENT\n").is_err());
+}
@@ -0,0 +1,173 @@
+use std::str::FromStr;
+
+use crate::{parse_date_auto, parse_date_lenient, CalendarDuration, Error, Zone};
+
+/// The ISO 8601 dates of an interval that are not in `AUTO_FORMATS`, like `2024-04-01T00:00+00:00`
+const INTERVAL_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M%#z", "%Y%m%dT%H%M%S%#z", "%Y%m%d"];
+
+/// The time from `start` to before `end`, like the ISO 8601 `2024-04-01/2024-04-03`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub start: chrono::DateTime<chrono::FixedOffset>,
+    pub end: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl Interval {
+    pub fn duration(&self) -> chrono::TimeDelta {
+        self.end - self.start
+    }
+
+    /// `date` is from the start to before the end
+    pub fn contains(&self, date: chrono::DateTime<chrono::FixedOffset>) -> bool {
+        self.start <= date && date < self.end
+    }
+
+    /// They have an instant in common, the ones that only touch like `01/02` and `02/03` don't
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// The time that is in both of them
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// The time that is in any of them, when they overlap or touch so it is one interval
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        (self.start <= other.end && other.start <= self.end).then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// Parses `start/end`, `start/duration` or `duration/end` like `2024-04-01/P2D`,
+    /// the dates are ISO 8601 or in the `AUTO_FORMATS` and the ones without an offset
+    /// are in `zone`, the days and months of the duration are in the wall clock of `zone`
+    pub fn parse(interval: &str, zone: Zone) -> Result<Self, Error> {
+        let interval = interval.trim();
+        let duration = |str: &str| {
+            str.trim_start_matches(['-', '+'])
+                .starts_with('P')
+                .then(|| CalendarDuration::from_str(str))
+        };
+
+        let (start, end) = if let Some((start, end)) = interval
+            .rsplit_once('/')
+            .filter(|(_, end)| duration(end).is_some())
+        {
+            let start = parse_interval_date(start, zone)?;
+            let duration = duration(end).unwrap_or(Err(Error::InvalidDuration))?;
+            (Some(start), duration.add_to_zoned(start, zone))
+        } else if let Some((start, end)) = interval
+            .split_once('/')
+            .filter(|(start, _)| duration(start).is_some())
+        {
+            let end = parse_interval_date(end, zone)?;
+            let duration = duration(start).unwrap_or(Err(Error::InvalidDuration))?;
+            ((-duration).add_to_zoned(end, zone), Some(end))
+        } else {
+            // a date can have a `/` in it like `22/Apr/2024:18:20:29 +0300`
+            interval
+                .match_indices('/')
+                .find_map(|(i, _)| {
+                    let start = parse_interval_date(&interval[..i], zone).ok()?;
+                    let end = parse_interval_date(&interval[i + 1..], zone).ok()?;
+                    Some((Some(start), Some(end)))
+                })
+                .ok_or(Error::InvalidInterval)?
+        };
+
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => Ok(Self { start, end }),
+            _ => Err(Error::InvalidInterval),
+        }
+    }
+}
+
+/// Like `2024-04-01T00:00:00+00:00/2024-04-03T00:00:00+00:00`
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.start.to_rfc3339(), self.end.to_rfc3339())
+    }
+}
+
+fn parse_interval_date(
+    date: &str,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    for format in INTERVAL_FORMATS {
+        let Ok(items) = chrono::format::strftime::StrftimeItems::new(format).parse() else {
+            continue;
+        };
+        if let Ok(date) = parse_date_lenient(&items, date, zone) {
+            return Ok(date);
+        }
+    }
+    parse_date_auto(&[], date, zone).map(|(date, _)| date)
+}
+
+#[cfg(test)]
+#[test]
+fn interval() {
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let parse = |interval: &str, zone| Interval::parse(interval, zone).map(|i| i.to_string());
+
+    assert_eq!(
+        parse("2024-04-01T00:00+00:00/2024-04-03T00:00+00:00", utc).unwrap(),
+        "2024-04-01T00:00:00+00:00/2024-04-03T00:00:00+00:00"
+    );
+    assert_eq!(
+        parse("2024-04-01/P2D", utc).unwrap(),
+        "2024-04-01T00:00:00+00:00/2024-04-03T00:00:00+00:00"
+    );
+    assert_eq!(
+        parse("PT36H/2024-04-03T12:00:00Z", utc).unwrap(),
+        "2024-04-02T00:00:00+00:00/2024-04-03T12:00:00+00:00"
+    );
+    assert_eq!(
+        parse("20240401T090000Z/20240401T100000Z", utc).unwrap(),
+        "2024-04-01T09:00:00+00:00/2024-04-01T10:00:00+00:00"
+    );
+    // a day is 23 hours when DST starts
+    let bucharest = Zone::Named(chrono_tz::Europe::Bucharest);
+    let interval = Interval::parse("2025-03-30/P1D", bucharest).unwrap();
+    assert_eq!(interval.duration(), chrono::TimeDelta::hours(23));
+    assert!(interval.contains(interval.start));
+    assert!(!interval.contains(interval.end));
+
+    assert_eq!(
+        parse("2024-04-03/2024-04-01", utc),
+        Err(Error::InvalidInterval)
+    );
+    assert_eq!(parse("2024-04-01", utc), Err(Error::InvalidInterval));
+    assert!(parse("2024-04-01/P2X", utc).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn overlap() {
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let interval = |interval: &str| Interval::parse(interval, utc).unwrap();
+
+    let a = interval("2024-04-01/P2D");
+    let b = interval("2024-04-02T12:00:00Z/P2D");
+    assert!(a.overlaps(&b));
+    assert_eq!(
+        a.intersection(&b).unwrap().to_string(),
+        "2024-04-02T12:00:00+00:00/2024-04-03T00:00:00+00:00"
+    );
+    assert_eq!(
+        b.union(&a).unwrap().to_string(),
+        "2024-04-01T00:00:00+00:00/2024-04-04T12:00:00+00:00"
+    );
+
+    // they only touch
+    let c = interval("2024-04-03/P1D");
+    assert!(!a.overlaps(&c));
+    assert_eq!(a.intersection(&c), None);
+    assert_eq!(a.union(&c), Some(interval("2024-04-01/2024-04-04")));
+    assert_eq!(a.union(&interval("2024-05-01/P1D")), None);
+}
@@ -0,0 +1,49 @@
+/// The Julian Day of the unix epoch, 1970-01-01 00:00 UTC
+pub const JD_UNIX_EPOCH: f64 = 2_440_587.5;
+/// The Modified Julian Date is the Julian Day minus this, so it starts at the midnight
+/// of 1858-11-17
+pub const MJD_OFFSET: f64 = 2_400_000.5;
+
+/// The Julian Day of `date`, the days since noon UTC of -4712-01-01 in the Julian calendar,
+/// the time of day is the fraction like `.25` for 18:00 UTC
+pub fn to_julian_day(date: chrono::DateTime<chrono::Utc>) -> f64 {
+    let seconds = date.timestamp() as f64 + date.timestamp_subsec_nanos() as f64 / 1e9;
+    JD_UNIX_EPOCH + seconds / 86_400.0
+}
+
+/// The date of the Julian Day, to the millisecond because a Julian Day of now in a `f64`
+/// is only exact to about 40 microseconds, `None` when it is out of range
+pub fn from_julian_day(jd: f64) -> Option<chrono::DateTime<chrono::Utc>> {
+    let millis = ((jd - JD_UNIX_EPOCH) * 86_400_000.0).round();
+    if !millis.is_finite() || millis.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    chrono::DateTime::from_timestamp_millis(millis as i64)
+}
+
+#[cfg(test)]
+#[test]
+fn julian_day() {
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap().to_utc();
+
+    assert_eq!(to_julian_day(date("2000-01-01T12:00:00Z")), 2_451_545.0);
+    assert_eq!(to_julian_day(date("1970-01-01T00:00:00Z")), JD_UNIX_EPOCH);
+    assert_eq!(to_julian_day(date("2024-04-26T18:00:00Z")), 2_460_427.25);
+    assert_eq!(
+        to_julian_day(date("1858-11-17T00:00:00Z")) - MJD_OFFSET,
+        0.0
+    );
+
+    assert_eq!(
+        from_julian_day(2_451_545.0),
+        Some(date("2000-01-01T12:00:00Z"))
+    );
+    assert_eq!(
+        from_julian_day(60_426.75 + MJD_OFFSET),
+        Some(date("2024-04-26T18:00:00Z"))
+    );
+    let now = date("2024-04-26T09:30:15.123Z");
+    assert_eq!(from_julian_day(to_julian_day(now)), Some(now));
+    assert_eq!(from_julian_day(f64::NAN), None);
+    assert_eq!(from_julian_day(1e300), None);
+}
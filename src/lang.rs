@@ -0,0 +1,292 @@
+use crate::{timedelta_str_to_preety, DurationBreakdown, TimedeltaFlags};
+
+/// The languages of the long durations like `3 Stunden, 12 Minuten, ` and of the weekdays
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+    Ro,
+}
+
+impl Lang {
+    /// From a locale like `de_DE.UTF-8` or `fr`, `C` and `POSIX` are English
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let lang = locale
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or_default();
+        match lang.to_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            "de" => Some(Self::De),
+            "fr" => Some(Self::Fr),
+            "es" => Some(Self::Es),
+            "ro" => Some(Self::Ro),
+            _ => None,
+        }
+    }
+
+    /// From `LC_ALL`, `LC_MESSAGES` or `LANG`, English when there is no translation
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// The name of `weekday` like `Friday` or with `short` like `Fri`
+    pub fn weekday(self, weekday: chrono::Weekday, short: bool) -> &'static str {
+        let names = match (self, short) {
+            (Lang::En, false) => [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ],
+            (Lang::En, true) => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            (Lang::De, false) => [
+                "Montag",
+                "Dienstag",
+                "Mittwoch",
+                "Donnerstag",
+                "Freitag",
+                "Samstag",
+                "Sonntag",
+            ],
+            (Lang::De, true) => ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            (Lang::Fr, false) => [
+                "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+            ],
+            (Lang::Fr, true) => ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+            (Lang::Es, false) => [
+                "lunes",
+                "martes",
+                "miércoles",
+                "jueves",
+                "viernes",
+                "sábado",
+                "domingo",
+            ],
+            (Lang::Es, true) => ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+            (Lang::Ro, false) => [
+                "luni",
+                "marți",
+                "miercuri",
+                "joi",
+                "vineri",
+                "sâmbătă",
+                "duminică",
+            ],
+            (Lang::Ro, true) => ["lun", "mar", "mie", "joi", "vin", "sâm", "dum"],
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The name of the month `1..=12` like `March`
+    pub fn month(self, month: u32) -> &'static str {
+        let names = match self {
+            Lang::En => [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ],
+            Lang::De => [
+                "Januar",
+                "Februar",
+                "März",
+                "April",
+                "Mai",
+                "Juni",
+                "Juli",
+                "August",
+                "September",
+                "Oktober",
+                "November",
+                "Dezember",
+            ],
+            Lang::Fr => [
+                "janvier",
+                "février",
+                "mars",
+                "avril",
+                "mai",
+                "juin",
+                "juillet",
+                "août",
+                "septembre",
+                "octobre",
+                "novembre",
+                "décembre",
+            ],
+            Lang::Es => [
+                "enero",
+                "febrero",
+                "marzo",
+                "abril",
+                "mayo",
+                "junio",
+                "julio",
+                "agosto",
+                "septiembre",
+                "octubre",
+                "noviembre",
+                "diciembre",
+            ],
+            Lang::Ro => [
+                "ianuarie",
+                "februarie",
+                "martie",
+                "aprilie",
+                "mai",
+                "iunie",
+                "iulie",
+                "august",
+                "septembrie",
+                "octombrie",
+                "noiembrie",
+                "decembrie",
+            ],
+        };
+        names[(month as usize).clamp(1, 12) - 1]
+    }
+
+    /// The word before a negative long duration, so the sign is of all of it and not of the first unit
+    pub fn minus(self) -> &'static str {
+        match self {
+            Lang::En | Lang::De | Lang::Ro => "minus",
+            Lang::Fr => "moins",
+            Lang::Es => "menos",
+        }
+    }
+
+    /// The singular and plural of every unit in the order of `TimedeltaFlags::UNITS`,
+    /// English is `timedelta_str_to_preety`
+    fn units(self) -> Option<[(&'static str, &'static str); 8]> {
+        Some(match self {
+            Lang::En => return None,
+            Lang::De => [
+                ("Jahr", "Jahre"),
+                ("Monat", "Monate"),
+                ("Woche", "Wochen"),
+                ("Tag", "Tage"),
+                ("Stunde", "Stunden"),
+                ("Minute", "Minuten"),
+                ("Sekunde", "Sekunden"),
+                ("Nanosekunde", "Nanosekunden"),
+            ],
+            Lang::Fr => [
+                ("an", "ans"),
+                ("mois", "mois"),
+                ("semaine", "semaines"),
+                ("jour", "jours"),
+                ("heure", "heures"),
+                ("minute", "minutes"),
+                ("seconde", "secondes"),
+                ("nanoseconde", "nanosecondes"),
+            ],
+            Lang::Es => [
+                ("año", "años"),
+                ("mes", "meses"),
+                ("semana", "semanas"),
+                ("día", "días"),
+                ("hora", "horas"),
+                ("minuto", "minutos"),
+                ("segundo", "segundos"),
+                ("nanosegundo", "nanosegundos"),
+            ],
+            Lang::Ro => [
+                ("an", "ani"),
+                ("lună", "luni"),
+                ("săptămână", "săptămâni"),
+                ("zi", "zile"),
+                ("oră", "ore"),
+                ("minut", "minute"),
+                ("secundă", "secunde"),
+                ("nanosecundă", "nanosecunde"),
+            ],
+        })
+    }
+}
+
+impl DurationBreakdown {
+    /// Like `timedelta_str_to_preety` but in `lang`,
+    /// English is the same as `timedelta_str_to_preety`
+    pub fn long(&self, lang: Lang) -> String {
+        let Some(units) = lang.units() else {
+            return timedelta_str_to_preety(&self.to_string());
+        };
+
+        let mut out = String::new();
+        if self.negative {
+            out.push_str(lang.minus());
+            out.push(' ');
+        }
+
+        for (flag, (singular, plural)) in TimedeltaFlags::UNITS.into_iter().zip(units) {
+            let value = self.get(flag);
+            let fraction = match flag {
+                // the seconds are always there like in `P1DT0S`, with the decimals of `fraction`
+                TimedeltaFlags::SECOND if self.flags.contains(flag) => self.fraction(),
+                TimedeltaFlags::SECOND | TimedeltaFlags::NANOS => continue,
+                _ if value == 0 => continue,
+                _ => String::new(),
+            };
+
+            let unit = if value == 1 && fraction.is_empty() {
+                singular
+            } else {
+                plural
+            };
+            out.push_str(&format!("{value}{fraction} {unit}, "));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn lang() {
+    use chrono::TimeDelta;
+
+    assert_eq!(Lang::from_locale("de_DE.UTF-8"), Some(Lang::De));
+    assert_eq!(Lang::from_locale("C"), Some(Lang::En));
+    assert_eq!(Lang::from_locale("xx_XX"), None);
+
+    assert_eq!(Lang::En.weekday(chrono::Weekday::Fri, false), "Friday");
+    assert_eq!(Lang::De.weekday(chrono::Weekday::Sun, true), "So");
+    assert_eq!(Lang::Ro.weekday(chrono::Weekday::Tue, false), "marți");
+    assert_eq!(Lang::Fr.month(8), "août");
+
+    let breakdown =
+        DurationBreakdown::new(TimeDelta::seconds(3 * 3600 + 60), TimedeltaFlags::all());
+    assert_eq!(
+        breakdown.long(Lang::De),
+        "3 Stunden, 1 Minute, 0 Sekunden, "
+    );
+    assert_eq!(breakdown.long(Lang::Fr), "3 heures, 1 minute, 0 secondes, ");
+    assert_eq!(
+        DurationBreakdown::new(-TimeDelta::hours(25), TimedeltaFlags::all()).long(Lang::Es),
+        "menos 1 día, 1 hora, 0 segundos, "
+    );
+    assert_eq!(
+        breakdown.long(Lang::En),
+        timedelta_str_to_preety(&breakdown.to_string())
+    );
+}
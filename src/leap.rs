@@ -0,0 +1,280 @@
+use chrono::Timelike;
+
+use crate::{ntp::NTP_UNIX_OFFSET, Config, Error};
+
+type Utc = chrono::DateTime<chrono::Utc>;
+
+/// GPS time is behind TAI by this many seconds, it was UTC when it started
+pub const GPS_TAI_OFFSET: i64 = 19;
+
+/// The leap seconds until the one of 2017, like the `leap-seconds.list` of the IERS
+const BUNDLED: &str = "\
+2272060800	10	# 1 Jan 1972
+2287785600	11	# 1 Jul 1972
+2303683200	12	# 1 Jan 1973
+2335219200	13	# 1 Jan 1974
+2366755200	14	# 1 Jan 1975
+2398291200	15	# 1 Jan 1976
+2429913600	16	# 1 Jan 1977
+2461449600	17	# 1 Jan 1978
+2492985600	18	# 1 Jan 1979
+2524521600	19	# 1 Jan 1980
+2571782400	20	# 1 Jul 1981
+2603318400	21	# 1 Jul 1982
+2634854400	22	# 1 Jul 1983
+2698012800	23	# 1 Jul 1985
+2776982400	24	# 1 Jan 1988
+2840140800	25	# 1 Jan 1990
+2871676800	26	# 1 Jan 1991
+2918937600	27	# 1 Jul 1992
+2950473600	28	# 1 Jul 1993
+2982009600	29	# 1 Jul 1994
+3029443200	30	# 1 Jan 1996
+3076704000	31	# 1 Jul 1997
+3124137600	32	# 1 Jan 1999
+3345062400	33	# 1 Jan 2006
+3439756800	34	# 1 Jan 2009
+3550089600	35	# 1 Jul 2012
+3644697600	36	# 1 Jul 2015
+3692217600	37	# 1 Jan 2017
+";
+
+/// A scale of time, the dates of TAI and GPS are their clock without an offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimeScale {
+    Utc,
+    Tai,
+    Gps,
+}
+
+/// When TAI - UTC changed and to how many seconds, with a leap second before each change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeapSeconds {
+    pub changes: Vec<(Utc, i64)>,
+    /// When the list should be replaced with a newer one, the `#@` line
+    pub expires: Option<Utc>,
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        Self::parse(BUNDLED).expect("the bundled leap seconds are valid")
+    }
+}
+
+impl LeapSeconds {
+    /// Parses a `leap-seconds.list` of the IERS, the lines are the NTP seconds and TAI - UTC
+    pub fn parse(list: &str) -> Result<Self, Error> {
+        let ntp = |seconds: &str| {
+            let seconds = seconds.parse::<i64>().ok()?;
+            chrono::DateTime::from_timestamp(seconds - NTP_UNIX_OFFSET, 0)
+        };
+        let mut leap_seconds = Self {
+            changes: Vec::new(),
+            expires: None,
+        };
+        for (i, line) in list.lines().enumerate() {
+            let invalid = || Error::InvalidLeapSeconds(format!("line {}: {line}", i + 1));
+            if let Some(expires) = line.strip_prefix("#@") {
+                leap_seconds.expires = Some(ntp(expires.trim()).ok_or_else(invalid)?);
+                continue;
+            }
+            let line = line.split_once('#').map_or(line, |(line, _)| line);
+            let mut fields = line.split_whitespace();
+            let Some(date) = fields.next() else {
+                continue;
+            };
+            let date = ntp(date).ok_or_else(invalid)?;
+            let offset = fields.next().and_then(|offset| offset.parse().ok());
+            leap_seconds
+                .changes
+                .push((date, offset.ok_or_else(invalid)?));
+        }
+
+        leap_seconds.changes.sort_unstable();
+        if leap_seconds.changes.is_empty() {
+            return Err(Error::InvalidLeapSeconds(
+                "there are no leap seconds".to_owned(),
+            ));
+        }
+        Ok(leap_seconds)
+    }
+
+    /// The `leap-seconds.list` of `Config::leap_seconds_path()`, when there is no file
+    /// it is the bundled one
+    pub fn load() -> Result<Self, Error> {
+        let Some(path) = Config::leap_seconds_path() else {
+            return Ok(Self::default());
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(list) => Self::parse(&list),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::InvalidLeapSeconds(format!(
+                "{}: {err}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// TAI - UTC in seconds at `utc`, before 1972 it is the first one of the list
+    pub fn tai_utc(&self, utc: Utc) -> i64 {
+        let i = self.changes.partition_point(|(date, _)| *date <= utc);
+        self.changes[i.saturating_sub(1)].1
+    }
+
+    /// The TAI clock at `utc`, a leap second like `23:59:60` is the second before the change
+    pub fn to_tai(&self, utc: Utc) -> chrono::NaiveDateTime {
+        let leap = utc.timestamp_subsec_nanos() / 1_000_000_000;
+        let utc = utc - chrono::TimeDelta::nanoseconds(leap as i64 * 1_000_000_000);
+        utc.naive_utc() + chrono::TimeDelta::seconds(self.tai_utc(utc) + leap as i64)
+    }
+
+    /// `utc` is in a leap second of the list, like `2016-12-31T23:59:60.5Z`
+    pub fn is_leap_second(&self, utc: Utc) -> bool {
+        if utc.timestamp_subsec_nanos() < 1_000_000_000 {
+            return false;
+        }
+        let next = utc
+            .with_nanosecond(0)
+            .map(|utc| utc + chrono::TimeDelta::seconds(1));
+        self.changes.iter().any(|(date, _)| Some(*date) == next)
+    }
+
+    /// The time from `from` to `to` with the leap seconds between them
+    pub fn between(&self, from: Utc, to: Utc) -> chrono::TimeDelta {
+        self.to_tai(to) - self.to_tai(from)
+    }
+
+    /// The UTC of the TAI clock, in a leap second it is `23:59:60`
+    pub fn from_tai(&self, tai: chrono::NaiveDateTime) -> Utc {
+        for (i, (date, offset)) in self.changes.iter().enumerate().rev() {
+            let start = date.naive_utc();
+            if tai >= start + chrono::TimeDelta::seconds(*offset) {
+                return (tai - chrono::TimeDelta::seconds(*offset)).and_utc();
+            }
+            let Some((_, before)) = i.checked_sub(1).map(|i| self.changes[i]) else {
+                break;
+            };
+            let leap = start + chrono::TimeDelta::seconds(before);
+            if tai >= leap {
+                // the seconds of the leap second go after 59 like 60.5
+                let nanos = (tai - leap).subsec_nanos() as u32;
+                let second = start - chrono::TimeDelta::seconds(1);
+                return second
+                    .with_nanosecond(1_000_000_000 + nanos)
+                    .unwrap_or(second)
+                    .and_utc();
+            }
+        }
+        (tai - chrono::TimeDelta::seconds(self.changes[0].1)).and_utc()
+    }
+}
+
+/// The start of the GPS weeks, 1980-01-06 00:00:00 of the GPS clock
+pub fn gps_epoch() -> chrono::NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(1980, 1, 6)
+        .unwrap_or_default()
+        .and_time(chrono::NaiveTime::MIN)
+}
+
+/// The GPS week and the time from its start, the time of week
+pub fn gps_week(gps: chrono::NaiveDateTime) -> (i64, chrono::TimeDelta) {
+    let since = gps - gps_epoch();
+    let week = since.num_seconds().div_euclid(7 * 86_400);
+    (week, since - chrono::TimeDelta::weeks(week))
+}
+
+/// Parses a GPS week and time of week like `2310:345600.5`
+pub fn parse_gps_week(str: &str) -> Option<chrono::NaiveDateTime> {
+    let (week, seconds) = str.split_once(':')?;
+    let week = week.trim().parse::<i64>().ok()?;
+    let seconds = seconds.trim().parse::<f64>().ok()?;
+    let nanos = (seconds * 1e9).round();
+    if !(0.0..7.0 * 86_400e9).contains(&nanos) {
+        return None;
+    }
+    let week = chrono::TimeDelta::try_weeks(week)?;
+    gps_epoch()
+        .checked_add_signed(week)?
+        .checked_add_signed(chrono::TimeDelta::nanoseconds(nanos as i64))
+}
+
+#[cfg(test)]
+#[test]
+fn leap_seconds() {
+    use chrono::Datelike;
+
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap().to_utc();
+    let naive = |date: &str| date.parse::<chrono::NaiveDateTime>().unwrap();
+    let leap_seconds = LeapSeconds::default();
+
+    // every leap second is at the end of June or December
+    for (date, _) in &leap_seconds.changes {
+        assert_eq!(date.time(), chrono::NaiveTime::MIN);
+        assert!(date.day() == 1 && [1, 7].contains(&date.month()));
+    }
+    let offsets = leap_seconds.changes.windows(2);
+    assert!(offsets.into_iter().all(|pair| pair[1].1 == pair[0].1 + 1));
+    assert_eq!(leap_seconds.tai_utc(date("1970-01-01T00:00:00Z")), 10);
+    assert_eq!(leap_seconds.tai_utc(date("2016-12-31T23:59:59Z")), 36);
+    assert_eq!(leap_seconds.tai_utc(date("2017-01-01T00:00:00Z")), 37);
+
+    assert_eq!(
+        leap_seconds.to_tai(date("2024-04-26T12:00:00Z")),
+        naive("2024-04-26T12:00:37")
+    );
+    assert_eq!(
+        leap_seconds.from_tai(naive("2024-04-26T12:00:37")),
+        date("2024-04-26T12:00:00Z")
+    );
+    // the leap second of 2016
+    let leap = date("2016-12-31T23:59:60.5Z");
+    assert_eq!(leap_seconds.to_tai(leap), naive("2017-01-01T00:00:36.5"));
+    assert_eq!(leap_seconds.from_tai(naive("2017-01-01T00:00:36.5")), leap);
+    assert_eq!(
+        leap_seconds.from_tai(naive("2017-01-01T00:00:35.5")),
+        date("2016-12-31T23:59:59.5Z")
+    );
+    assert_eq!(
+        leap_seconds.from_tai(naive("2017-01-01T00:00:37")),
+        date("2017-01-01T00:00:00Z")
+    );
+
+    assert!(leap_seconds.is_leap_second(leap));
+    assert!(!leap_seconds.is_leap_second(date("2016-12-31T23:59:59.5Z")));
+    assert!(!leap_seconds.is_leap_second(date("2016-12-30T23:59:60Z")));
+    assert_eq!(
+        leap_seconds.between(date("2016-12-31T23:59:59Z"), date("2017-01-01T00:00:01Z")),
+        chrono::TimeDelta::seconds(3)
+    );
+    assert_eq!(
+        leap_seconds.between(date("2016-12-31T23:59:59Z"), leap),
+        chrono::TimeDelta::milliseconds(1500)
+    );
+    assert_eq!(
+        leap_seconds.between(date("2015-01-01T00:00:00Z"), date("2018-01-01T00:00:00Z")),
+        chrono::TimeDelta::days(1096) + chrono::TimeDelta::seconds(2)
+    );
+
+    // GPS time is TAI - 19s
+    let gps = leap_seconds.to_tai(date("2024-04-26T12:00:00Z"))
+        - chrono::TimeDelta::seconds(GPS_TAI_OFFSET);
+    assert_eq!(
+        gps_week(gps),
+        (
+            2311,
+            chrono::TimeDelta::seconds(5 * 86_400 + 12 * 3600 + 18)
+        )
+    );
+    assert_eq!(parse_gps_week("2311:475218"), Some(gps));
+    assert_eq!(parse_gps_week("2311:604800"), None);
+    assert_eq!(parse_gps_week("2311"), None);
+
+    let list =
+        LeapSeconds::parse("#@\t3960057600\n# comment\n\n3692217600\t37\t# 1 Jan 2017\n").unwrap();
+    assert_eq!(list.changes, [(date("2017-01-01T00:00:00Z"), 37)]);
+    assert_eq!(list.expires, Some(date("2025-06-28T00:00:00Z")));
+    assert_eq!(
+        LeapSeconds::parse("3692217600"),
+        Err(Error::InvalidLeapSeconds("line 1: 3692217600".to_owned()))
+    );
+}
@@ -0,0 +1,227 @@
+//! TimeMan is a time and date calculator
+//!
+//! The `tm` binary is a thin cli over this library,
+//! the parsing and formatting of dates and durations can be reused from here.
+//!
+//! Most of the work is done by chrono
+
+pub mod age;
+pub mod boot;
+pub mod business;
+pub mod calendar;
+pub mod clock;
+pub mod config;
+pub mod cron;
+pub mod date;
+pub mod duration;
+pub mod epoch;
+pub mod expr;
+pub mod file;
+pub mod fiscal;
+pub mod format;
+pub mod freebusy;
+pub mod guess;
+pub mod holidays;
+#[cfg(feature = "http")]
+pub mod http_date;
+pub mod ics;
+pub mod interval;
+pub mod julian;
+pub mod lang;
+pub mod leap;
+pub mod nth;
+pub mod ntp;
+pub mod oncalendar;
+pub mod period;
+#[cfg(feature = "pick")]
+pub mod pick;
+pub mod random;
+pub mod relative;
+pub mod rewrite;
+pub mod rrule;
+pub mod seq;
+pub mod stopwatch;
+pub mod store;
+pub mod stream;
+pub mod track;
+pub mod week;
+pub mod zone;
+
+pub use age::Age;
+pub use boot::boot_time;
+pub use business::{parse_holidays, BusinessCalendar};
+pub use calendar::{month_weeks, render_month, render_year, weekdays, CalendarStyle};
+pub use clock::set_system_time;
+pub use config::Config;
+pub use cron::{Cron, CronTimes};
+pub use date::{
+    parse_date, parse_date_auto, parse_date_lenient, parse_date_prefix, round_date, AUTO_FORMATS,
+};
+pub use duration::{
+    decimal_to_str, parse_factor, round_timedelta, scale_timedelta, timedelta_from_str,
+    timedelta_in, timedelta_str_to_preety, timedelta_to_str, CalendarDuration, DurationBreakdown,
+    DurationStyle, DurationTemplate, Rounding, TimedeltaFlags,
+};
+pub use epoch::LegacyEpoch;
+pub use expr::{eval, Value};
+pub use file::{file_mtime, file_times, FileTimes};
+pub use fiscal::FiscalYear;
+pub use format::{find_preset, FormatPreset, FORMAT_PRESETS};
+pub use freebusy::{free_slots, intersect, merge_busy, WorkingHours};
+pub use guess::{guess_format, GuessedFormat};
+pub use holidays::{easter, Feast, Holiday, HolidayRule};
+#[cfg(feature = "http")]
+pub use http_date::{query_http_date, HttpDate};
+pub use ics::{occurrences, parse_ics, Event, EventTime, Occurrence, Occurrences};
+pub use interval::Interval;
+pub use julian::{from_julian_day, to_julian_day, JD_UNIX_EPOCH, MJD_OFFSET};
+pub use lang::Lang;
+pub use leap::{gps_epoch, gps_week, parse_gps_week, LeapSeconds, TimeScale, GPS_TAI_OFFSET};
+pub use nth::{parse_nth_weekday, NthWeekday};
+pub use ntp::{query_ntp, NtpTime};
+pub use oncalendar::{OnCalendar, OnCalendarTimes};
+pub use period::{days_in_month, days_in_year, is_leap_year, Period};
+#[cfg(feature = "pick")]
+pub use pick::pick_date;
+pub use random::random_date;
+pub use relative::{parse_relative, weekday_occurrence};
+pub use rewrite::{rewrite_dates, rewrite_regex};
+pub use rrule::{Frequency, RRule, RRuleTimes};
+pub use seq::DateSeq;
+pub use stopwatch::{Run, Stopwatch};
+pub use store::{load_json, save_json};
+pub use stream::{group_by, histogram, sessions, GapStats, GroupBy, Session, StreamStats};
+pub use track::{TimeLog, TrackEntry};
+pub use week::WeekStart;
+pub use zone::{search_zones, Transition, Transitions, Zone};
+
+/// Everything that can go wrong when parsing dates, durations or zones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The date is not in the format
+    Format(chrono::format::ParseError),
+    /// The format has no offset in it, use `%z` or `%:z`
+    NoOffset,
+    /// The date is in the format but is not a real date
+    InvalidDate,
+    /// The date doesn't exist or is ambiguous in the offset (DST)
+    AmbiguousDate,
+    /// The date is not in any of the `AUTO_FORMATS`
+    NoAutoFormat,
+    /// The date is not like `2 hours ago`, `in 3 days` or `next tuesday 14:00`
+    InvalidRelative,
+    /// The day is not like `3rd thursday of november 2025` or `last friday of the month`
+    InvalidNthWeekday,
+    /// The duration is not like `P1DT2H3M4.5S`
+    InvalidDuration,
+    /// The ISO 8601 duration is malformed, with where and why
+    MalformedDuration(String),
+    /// The cron expression is not like `*/15 2 * * 1-5`, with why
+    InvalidCron(String),
+    /// The calendar event is not like `Mon..Fri *-*-* 02:00`, with why
+    InvalidCalendar(String),
+    /// The recurrence rule is not like `FREQ=MONTHLY;BYDAY=2TU`, with why
+    InvalidRRule(String),
+    /// The iCalendar file is malformed, with where and why
+    InvalidIcs(String),
+    /// The expression is not valid or mixes dates and durations in a way that has no meaning
+    InvalidExpression(String),
+    /// The duration flags have a letter that is not a flag or a range that is reversed
+    InvalidDurationFlags(String),
+    /// The duration template is not like `{h:02}:{m:02}:{s:02}`
+    InvalidTemplate,
+    /// The interval is not like `2024-04-01/2024-04-03`, `2024-04-01/P2D` or `P2D/2024-04-03`,
+    /// or it ends before it starts
+    InvalidInterval,
+    /// The working hours are not like `9-17` or `09:00-17:30`
+    InvalidWorkingHours,
+    /// The zone is not an offset like `+03:00` or a time zone name like `Europe/Bucharest`
+    InvalidZone,
+    /// A line of the holidays file is not a `YYYY-MM-DD` date
+    InvalidHolidays(String),
+    /// A line of the leap seconds file is not like `3692217600 37`, or the file cannot be read
+    InvalidLeapSeconds(String),
+    /// The stopwatch is already running
+    StopwatchRunning,
+    /// The stopwatch is not running
+    StopwatchStopped,
+    /// The boot time cannot be read from the system, with why
+    NoBootTime(String),
+    /// The SNTP query failed, with the server and why
+    NtpFailed(String),
+    /// The HEAD request failed or its answer has no valid `Date`, with the url and why
+    HttpFailed(String),
+    /// The clock of the system cannot be set, with why
+    SetClockFailed(String),
+    /// The project is already tracked
+    AlreadyTracking(String),
+    /// No project is tracked
+    NotTracking,
+    /// The json file of the stopwatches or of the tracked time cannot be read or written
+    /// or is not valid
+    InvalidDataFile(String),
+    /// The config file cannot be read or is not valid toml
+    InvalidConfig(String),
+    /// The `@name` format is not in the profiles of the config
+    NoProfile(String),
+    /// The dates are too far apart for the histogram buckets, more than `MAX_BUCKETS`
+    TooManyBuckets,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Format(err) => write!(f, "the date is not in the format: {err}"),
+            Error::NoOffset => write!(f, "the date has no utc offset"),
+            Error::InvalidDate => write!(f, "invalid date"),
+            Error::AmbiguousDate => write!(f, "invalid date or ambiguous time"),
+            Error::NoAutoFormat => write!(f, "the date is not in any of the auto formats"),
+            Error::InvalidRelative => write!(f, "invalid relative date"),
+            Error::InvalidNthWeekday => write!(
+                f,
+                "the day should look like \"3rd thursday of november 2025\" or \"last friday of the month\""
+            ),
+            Error::InvalidDuration => write!(f, "invalid duration"),
+            Error::MalformedDuration(err) => write!(f, "invalid duration, {err}"),
+            Error::InvalidCron(err) => write!(f, "invalid cron expression, {err}"),
+            Error::InvalidCalendar(err) => write!(f, "invalid calendar event, {err}"),
+            Error::InvalidRRule(err) => write!(f, "invalid recurrence rule, {err}"),
+            Error::InvalidIcs(err) => write!(f, "invalid iCalendar file, {err}"),
+            Error::InvalidExpression(err) => write!(f, "invalid expression, {err}"),
+            Error::InvalidDurationFlags(err) => write!(f, "invalid duration flags, {err}"),
+            Error::InvalidTemplate => write!(f, "invalid duration template"),
+            Error::InvalidInterval => write!(
+                f,
+                "the interval should look like \"2024-04-01/2024-04-03\", \"2024-04-01/P2D\" or \"P2D/2024-04-03\" and not end before it starts"
+            ),
+            Error::InvalidWorkingHours => write!(
+                f,
+                "the working hours should look like \"9-17\" or \"09:00-17:30\""
+            ),
+            Error::InvalidZone => write!(
+                f,
+                "the offset should look like \"+00:00\" or a time zone name like \"Europe/Bucharest\""
+            ),
+            Error::InvalidHolidays(line) => write!(f, "invalid holiday at {line}"),
+            Error::InvalidLeapSeconds(err) => write!(f, "invalid leap seconds at {err}"),
+            Error::StopwatchRunning => write!(f, "the stopwatch is already running"),
+            Error::StopwatchStopped => write!(f, "the stopwatch is not running"),
+            Error::NoBootTime(err) => write!(f, "cannot read the boot time, {err}"),
+            Error::NtpFailed(err) => write!(f, "the NTP query failed, {err}"),
+            Error::HttpFailed(err) => write!(f, "the HEAD request failed, {err}"),
+            Error::SetClockFailed(err) => write!(f, "cannot set the clock, {err}"),
+            Error::AlreadyTracking(project) => write!(f, "`{project}` is already tracked"),
+            Error::NotTracking => write!(f, "no project is tracked"),
+            Error::InvalidDataFile(err) => write!(f, "invalid data file: {err}"),
+            Error::InvalidConfig(err) => write!(f, "invalid config: {err}"),
+            Error::NoProfile(name) => write!(f, "there is no format profile named \"{name}\""),
+            Error::TooManyBuckets => write!(
+                f,
+                "there would be more than {} buckets, use a longer bucket",
+                crate::stream::MAX_BUCKETS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
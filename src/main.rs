@@ -1,24 +1,61 @@
-use std::{collections::BTreeMap, fmt::Write, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Read, Write},
+    str::FromStr,
+};
 
-use chrono::{Offset, TimeZone};
-use clap::{Parser, Subcommand};
+use chrono::{Datelike, Timelike};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use timeman::{
+    timedelta_from_str, timedelta_to_str, CalendarDuration, DurationBreakdown, DurationStyle,
+    DurationTemplate, GroupBy, Lang, Period, TimedeltaFlags, WeekStart, Zone,
+};
 
 /// A simple date and time manipulator
 /// You can get the time, get how much time elapsed sice,
 /// subtract time to get the duration and add/substrat duration from date and time
 ///
 /// Most of the work is done by clap and chrono
+///
+/// A date argument can be `-r FILE`, the time the file was modified like `touch -r`,
+/// like `tm since -r build/output` or `tm sub -r a.log -r b.log`
 #[derive(Parser)]
 #[command(version, author)]
 pub struct TimeMan {
-    /// The date format
-    #[arg(short = 'f', default_value = "%a, %d %b %Y %T %z")]
-    format: String,
+    /// The date format or a preset like `rfc3339`, `unix` or `http-date`, see `help-format`,
+    /// the default is from the config or "%a, %d %b %Y %T %z"
+    #[arg(short = 'f')]
+    format: Option<String>,
 
-    /// UTC/Universal
+    /// UTC offset like "+03:00" or a time zone name like "Europe/Bucharest",
+    /// the default is from the config or the local time zone
     #[arg(short = 'o')]
     utc_offset: Option<String>,
 
+    /// If a date cannot be parsed with the format, try the common formats
+    /// like RFC 3339, RFC 2822, unix timestamp, `%Y-%m-%d`...
+    #[arg(short = 'a', long)]
+    auto: bool,
+
+    /// Read the date of `since`, `until`, `translate`, `add-duration`, `sub-duration` and `unix to`
+    /// from every line of stdin and print a result for every line, the date argument is left out,
+    /// a line that fails is printed with its number and the exit code is of the first one
+    #[arg(long, global = true)]
+    stdin: bool,
+
+    /// The first day of the week, the default is from the config or Monday like ISO 8601
+    #[arg(long, global = true, value_enum)]
+    week_start: Option<WeekStart>,
+
+    /// Count the leap seconds, the durations across them are in TAI seconds and a time
+    /// like 23:59:60 should be a leap second of `convert-scale`, the default is from the config
+    #[arg(long, global = true)]
+    leap_seconds: bool,
+
+    /// How the result is printed, `json` is for scripts
+    #[arg(long, value_enum, default_value_t = OutputMode::Text)]
+    output: OutputMode,
+
     #[command(subcommand)]
     command: TimeManCommand,
 }
@@ -26,17 +63,42 @@ pub struct TimeMan {
 #[derive(Subcommand)]
 pub enum TimeManCommand {
     /// get the current time you can use `-o` before to set the utc offset
-    Now,
+    Now {
+        /// print the time in every one of the zones, like a world clock,
+        /// the default is the `zones` of the config
+        #[arg(long = "zone", value_name = "ZONE")]
+        zones: Vec<String>,
+    },
 
     /// alias: s
     #[command(alias = "s")]
     Since {
-        date: String,
+        date: Option<String>,
+
+        duration_flags: Option<String>,
+
+        /// exit with 3 when more than the duration elapsed, like "PT15M" for a heartbeat file,
+        /// with `--stdin` when it is so for any of the dates
+        #[arg(long, value_name = "DURATION")]
+        fail_over: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+
+    /// how much time is left until the date, alias: u
+    #[command(alias = "u")]
+    Until {
+        date: Option<String>,
 
         duration_flags: Option<String>,
 
-        #[arg(short)]
-        preety: bool,
+        #[command(flatten)]
+        style: DurationArgs,
+
+        /// print a negative duration if the date is in the past instead of failing
+        #[arg(short = 'n', long)]
+        allow_negative: bool,
     },
 
     /// alias: -
@@ -47,32 +109,790 @@ pub enum TimeManCommand {
 
         duration_flags: Option<String>,
 
-        #[arg(short)]
-        preety: bool,
+        #[command(flatten)]
+        style: DurationArgs,
     },
 
     /// alias: -d
-    #[command(alias = "-d")]
+    #[command(alias = "-d", allow_missing_positional = true)]
     SubDuration {
-        from_date: String,
+        #[arg(allow_hyphen_values = true)]
+        from_date: Option<String>,
+        #[arg(allow_hyphen_values = true)]
         duration: String,
+
+        /// use a year of 365 days and a month of a twelfth of it,
+        /// instead of moving in the calendar
+        #[arg(long)]
+        fixed: bool,
+
+        /// move the days in the wall clock of `-o` so 09:00 + P1D is 09:00 with DST,
+        /// is the default when `-o` is a time zone name
+        #[arg(long, conflicts_with = "absolute")]
+        wall_clock: bool,
+
+        /// a day is always 24 hours
+        #[arg(long)]
+        absolute: bool,
     },
 
     /// alias: +d
-    #[command(alias = "+d")]
+    #[command(alias = "+d", allow_missing_positional = true)]
     AddDuration {
-        from_date: String,
+        #[arg(allow_hyphen_values = true)]
+        from_date: Option<String>,
+        #[arg(allow_hyphen_values = true)]
         duration: String,
+
+        /// use a year of 365 days and a month of a twelfth of it,
+        /// instead of moving in the calendar
+        #[arg(long)]
+        fixed: bool,
+
+        /// move the days in the wall clock of `-o` so 09:00 + P1D is 09:00 with DST,
+        /// is the default when `-o` is a time zone name
+        #[arg(long, conflicts_with = "absolute")]
+        wall_clock: bool,
+
+        /// a day is always 24 hours
+        #[arg(long)]
+        absolute: bool,
     },
 
     /// alias: t
     #[command(alias = "t")]
     Translate {
+        date: Option<String>,
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// show the time left until the date and update it every second,
+    /// exits when the date is reached
+    Countdown {
+        date: String,
+
+        /// print the time left only once
+        #[arg(short, long)]
+        quiet: bool,
+    },
+    /// block until the date, the time left is printed on SIGUSR1 or SIGINFO
+    SleepUntil {
+        date: String,
+    },
+    /// print when the file was modified, accessed, changed and created in the `-f` format,
+    /// the times that the system doesn't keep are "-"
+    File {
+        path: std::path::PathBuf,
+
+        /// print how long ago every time is too
+        #[arg(long)]
+        since: bool,
+
+        /// the duration flags of `--since`
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// print when the system was booted and how long it is up
+    Uptime {
+        /// the duration flags of the uptime, the default is "Dhms"
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// ask an SNTP server for its time and print it, how much the local clock is behind it
+    /// and the round-trip delay, like `ntp time.google.com`
+    Ntp {
+        /// like "pool.ntp.org" or "time.google.com:123"
+        #[arg(default_value = "pool.ntp.org")]
+        server: String,
+
+        /// how long to wait for the answer
+        #[arg(long, default_value = "5s")]
+        timeout: String,
+
+        /// the duration flags of the offset and the delay
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// send a HEAD request and print the `Date` of the answer and how much the local clock
+    /// is behind it, like `http-date example.com` when NTP is blocked
+    #[cfg(feature = "http")]
+    HttpDate {
+        /// the url without a scheme is https
+        url: String,
+
+        /// how long to wait for the answer
+        #[arg(long, default_value = "10s")]
+        timeout: String,
+
+        /// the duration flags of the offset and the delay
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// set the clock of the system to the date and print how much it moved,
+    /// it needs root, like `set-system "2025-01-01 12:00" --dry-run`
+    SetSystem {
+        /// the date without an offset is in the `-o` offset or time zone
+        date: String,
+
+        /// only print how much the clock would move
+        #[arg(long)]
+        dry_run: bool,
+
+        /// the duration flags of how much the clock moves
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// block until the date and then run the command and exit with its exit code,
+    /// the time left is printed on SIGUSR1 or SIGINFO, like `at "tomorrow 9:00" -- backup.sh`
+    At {
+        /// the date without an offset is in the `-o` offset or time zone, so it is after a DST change
+        date: String,
+
+        /// the command and its arguments, after `--`
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// block for the duration, the time left is printed on SIGUSR1 or SIGINFO
+    SleepFor {
+        duration: String,
+    },
+    /// count down the duration, then ring the bell, show a desktop notification or run a command,
+    /// like `timer 25m --notify "Stand up"`
+    Timer {
+        duration: String,
+
+        /// show a desktop notification with the message when it is done
+        #[arg(long, num_args = 0..=1, default_missing_value = "The timer is done")]
+        notify: Option<String>,
+
+        /// ring the terminal bell when it is done
+        #[arg(long)]
+        bell: bool,
+
+        /// run the command with `sh -c` when it is done and exit with its exit code,
+        /// like `--command "paplay done.ogg"`
+        #[arg(long)]
+        command: Option<String>,
+
+        /// don't print the time that is left
+        #[arg(short, long)]
+        quiet: bool,
+    },
+    /// run the command and print how long it ran, then exit with its exit code,
+    /// like `measure -- cargo build --release`
+    Measure {
+        /// the command and its arguments, after `--`
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+
+        /// the duration flags of how long it ran
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// run the command at every multiple of the duration in the `-o` offset, like on the minute
+    /// for "PT1M", a run that is missed while the command is running is skipped,
+    /// like `periodic PT10M -- backup.sh`
+    Periodic {
+        /// like "PT10M" or "30s", without years and months
+        every: String,
+
+        /// the command and its arguments, after `--`
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+
+        /// wait a random time up to it after the multiple, like "PT30S"
+        #[arg(long)]
+        jitter: Option<String>,
+
+        /// stop after this many runs
+        #[arg(long)]
+        max_runs: Option<u64>,
+    },
+    /// work and take a break in turns with a live countdown, then print how long the work was,
+    /// like `pomodoro --work 25m --break 5m --rounds 4`, Ctrl-C stops it early
+    Pomodoro {
+        /// how long a round of work is
+        #[arg(long, default_value = "25m")]
+        work: String,
+
+        /// how long the break after a round is, there is none after the last round
+        #[arg(long = "break", default_value = "5m")]
+        pause: String,
+
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        rounds: u32,
+
+        /// ring the terminal bell when a round or a break ends
+        #[arg(long)]
+        bell: bool,
+
+        /// show a desktop notification when a round or a break ends
+        #[arg(long)]
+        notify: bool,
+
+        /// the duration flags of the summary, the default is "hms"
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// named stopwatches that keep going between the shell sessions and after a reboot,
+    /// they are saved in `timeman/stopwatches.json` in `$XDG_DATA_HOME` or `~/.local/share`
+    Stopwatch {
+        #[command(subcommand)]
+        command: StopwatchCommand,
+    },
+    /// track the time spent on projects, like `track start timeman` and `track report --since monday`,
+    /// the entries are saved in `timeman/track.json` in `$XDG_DATA_HOME` or `~/.local/share`
+    Track {
+        #[command(subcommand)]
+        command: TrackCommand,
+    },
+    /// calculate with durations, like `dur add P1DT2H PT30M`
+    Dur {
+        #[command(subcommand)]
+        command: DurCommand,
+    },
+    /// evaluate an expression over dates and durations,
+    /// like `expr "now + 3d - 2h30m"` or `expr "(2025-01-01 - now) / 2"`
+    Expr {
+        /// the operators `+ - * /` are between spaces, the dates are parsed like with `-a`,
+        /// it is after `--` when it starts with `-`
+        #[arg(required = true)]
+        expression: Vec<String>,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+    /// evaluate `expr` expressions line by line with history, `$1` is the first result,
+    /// `:format` and `:offset` change the settings of the next lines, see `:help`
+    Repl {
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+    /// pick a day in a calendar in the terminal and print it in the `-f` format,
+    /// the time is the time of `date`
+    #[cfg(feature = "pick")]
+    Pick {
+        /// the day where it starts, the default is now
+        date: Option<String>,
+
+        /// the language of the weekdays, the default is from `LC_MESSAGES`
+        #[arg(long, value_enum)]
+        locale: Option<Lang>,
+    },
+    /// print a month like `cal` with today or `--date` highlighted
+    Cal {
+        /// like "4", "april" or "apr", the default is the month of the date
+        #[arg(value_parser = parse_month)]
+        month: Option<u32>,
+        /// the default is the year of the date
+        #[arg(allow_negative_numbers = true)]
+        year: Option<i32>,
+
+        /// the highlighted day, the default is now
+        #[arg(long)]
+        date: Option<String>,
+
+        /// print the ISO week numbers on the left
+        #[arg(short = 'w', long)]
+        week_numbers: bool,
+
+        /// print all the months of the year, the default is the year of the date
+        #[arg(long = "year", value_name = "YEAR", num_args = 0..=1, allow_negative_numbers = true, conflicts_with_all = ["month", "year"])]
+        whole_year: Option<Option<i32>>,
+
+        /// the months in a row of `--year`
+        #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=12))]
+        columns: u8,
+
+        /// a day that is highlighted too, can be repeated
+        #[arg(long)]
+        mark: Vec<String>,
+
+        /// the language of the month and the weekdays, the default is from `LC_MESSAGES`
+        #[arg(long, value_enum)]
+        locale: Option<Lang>,
+    },
+    /// the times of a cron schedule in the zone of `-o`, like `cron next "*/15 2 * * 1-5" 5`
+    Cron {
+        #[command(subcommand)]
+        command: CronCommand,
+    },
+    /// the elapses of a systemd `OnCalendar=` event in the zone of `-o` or of the event,
+    /// like `oncalendar next "Mon..Fri *-*-* 02:00"`
+    Oncalendar {
+        #[command(subcommand)]
+        command: OnCalendarCommand,
+    },
+    /// the events of an iCalendar file with their times in the zone of `-o`
+    /// and how long until they start
+    Ics {
+        #[command(subcommand)]
+        command: IcsCommand,
+    },
+    /// the times in the working hours of all the zones that are at least `--duration` long,
+    /// like `meet --zones Europe/Berlin,America/New_York --hours 9-18`,
+    /// the `--weekend` and the `--holidays` are the days off in every zone
+    Meet {
+        /// the zones of everyone in the meeting, the default is `zones` of the config
+        #[arg(long, value_delimiter = ',')]
+        zones: Vec<String>,
+
+        /// the working hours in every zone, like "9-17" or "09:00-17:30",
+        /// the default is `working_hours` of the config or 9-17
+        #[arg(long, value_parser = parse_working_hours)]
+        hours: Option<timeman::WorkingHours>,
+
+        /// how long the meeting is, like "PT1H" or "45m"
+        #[arg(long, default_value = "PT1H")]
+        duration: String,
+
+        /// the default is now
+        #[arg(long)]
+        from: Option<String>,
+
+        /// how many days from `--from`
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+
+        #[command(flatten)]
+        calendar: BusinessArgs,
+    },
+    /// the occurrences of an iCalendar recurrence rule from `--from` in the zone of `-o`,
+    /// like `rrule "FREQ=MONTHLY;BYDAY=2TU" --count 10`
+    Rrule {
+        /// like "FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20251231", the "RRULE:" before it is optional
+        rule: String,
+
+        /// the start of the rule like `DTSTART`, the time of the occurrences is from it,
+        /// the default is now
+        #[arg(long)]
+        from: Option<String>,
+
+        /// at most this many occurrences, the rule can end before with `COUNT` or `UNTIL`
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+    },
+    /// ISO 8601 intervals like "2024-04-01/2024-04-03", "2024-04-01/P2D" or "P2D/2024-04-03",
+    /// the dates without an offset are in the zone of `-o`
+    Interval {
+        #[command(subcommand)]
+        command: IntervalCommand,
+    },
+    /// convert between unix timestamps and dates
+    Unix {
+        /// The precision of the timestamp
+        #[arg(short, long, global = true, value_enum, default_value_t = EpochUnit::S)]
+        unit: EpochUnit,
+
+        #[command(subcommand)]
+        command: UnixCommand,
+    },
+    /// print the date in UTC, TAI and GPS time and the GPS week and time of week,
+    /// like `convert-scale gps 2311:475218`, the leap seconds are bundled
+    /// or in `timeman/leap-seconds.list` of `$XDG_DATA_HOME`
+    ConvertScale {
+        /// the scale of the date, a GPS date can also be a week and time of week like "2311:475218.5"
+        #[arg(value_enum)]
+        scale: timeman::TimeScale,
+
+        /// the TAI and GPS dates are their clock, the offset is ignored
+        date: String,
+    },
+    /// convert between dates and the timestamps of Windows FILETIME, .NET ticks,
+    /// Apple Cocoa and HFS+, like `convert-epoch filetime from 133586064001234567`
+    ConvertEpoch {
+        #[arg(value_enum)]
+        epoch: timeman::LegacyEpoch,
+
+        #[command(subcommand)]
+        command: ConvertEpochCommand,
+    },
+    /// convert between Julian Days and dates, the time of day is the fraction of the day
+    Jd {
+        /// the Modified Julian Date, the Julian Day minus 2400000.5
+        #[arg(long, global = true)]
+        mjd: bool,
+
+        #[command(subcommand)]
+        command: JdCommand,
+    },
+    /// print the date in every one of the zones, with `--around` the hours before and after too,
+    /// like a table for scheduling across time zones
+    TzTable {
+        date: String,
+
+        #[arg(required = true)]
+        zones: Vec<String>,
+
+        /// how many hours before and after the date are printed
+        #[arg(long, default_value_t = 0)]
+        around: u32,
+
+        /// the format or a preset of the cells
+        #[arg(short = 'F', default_value = "%a %H:%M")]
+        to_format: String,
+    },
+    /// find the time zone names to use with `-o`
+    Tz {
+        #[command(subcommand)]
+        command: TzCommand,
+    },
+    /// print the offset, the abbreviation, if DST is active and the next transitions of a zone,
+    /// the default zone is the `-o` one
+    TzInfo {
+        zone: Option<String>,
+
+        /// how many transitions are printed
+        #[arg(short = 'n', long, default_value_t = 4)]
+        transitions: usize,
+    },
+    /// the first instant of the day, week, month, quarter or year of the date in the `-o` offset
+    StartOf {
+        #[arg(value_enum)]
+        period: Period,
+        date: Option<String>,
+    },
+    /// the last instant of the day, week, month, quarter or year of the date in the `-o` offset
+    EndOf {
+        #[arg(value_enum)]
+        period: Period,
+        date: Option<String>,
+    },
+    /// print how much of the day, week, month, quarter or year of the date is elapsed
+    /// and how long until its end, the date is now by default
+    Progress {
+        #[arg(value_enum)]
+        period: Period,
+        date: Option<String>,
+
+        /// the duration flags of how long until the end
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// snap the date to the nearest multiple of the duration in the `-o` offset,
+    /// like "PT5M" for every 5 minutes or "P1D" for the midnight
+    #[command(allow_missing_positional = true)]
+    Round {
+        date: Option<String>,
+        duration: String,
+
+        /// round down
+        #[arg(long, conflicts_with = "ceil")]
+        floor: bool,
+
+        /// round up
+        #[arg(long)]
+        ceil: bool,
+    },
+    /// the first weekday after the date, by default from now,
+    /// like `tm next friday` or `tm next fri from "$(tm now)" --at 9:30`
+    Next {
+        #[command(flatten)]
+        weekday: WeekdayArgs,
+    },
+    /// the last weekday before the date, by default from now, like `tm prev monday`
+    Prev {
+        #[command(flatten)]
+        weekday: WeekdayArgs,
+    },
+    /// the day of an expression like "3rd thursday of november 2025", "last friday of the month"
+    /// or "first monday of next month 9:30", the month without a year is this year
+    Nth {
+        expression: Vec<String>,
+    },
+    /// count the business days from `from_date` to the day before `date`,
+    /// so Monday to Friday is 4, the days are in the `-o` offset
+    BusinessDays {
+        from_date: String,
+        date: String,
+
+        #[command(flatten)]
+        calendar: BusinessArgs,
+    },
+    /// move the date by business days, a negative number moves it back
+    #[command(allow_missing_positional = true, allow_negative_numbers = true)]
+    AddBusinessDays {
+        from_date: Option<String>,
+        days: i64,
+
+        #[command(flatten)]
+        calendar: BusinessArgs,
+    },
+    /// the day of Easter or of a feast that moves with it, in the `-o` offset
+    Easter {
+        /// the year, this year by default
+        year: Option<i32>,
+
+        /// like "good-friday" or "pentecost"
+        #[arg(long, value_enum, default_value_t)]
+        feast: timeman::Feast,
+
+        /// from the Orthodox Easter
+        #[arg(long)]
+        orthodox: bool,
+
+        /// print every feast in a table
+        #[arg(long, conflicts_with = "feast")]
+        all: bool,
+    },
+    /// print the fiscal year and quarter of the date and their first and last days,
+    /// the fiscal year is named by the year that it ends in
+    Fiscal {
+        date: Option<String>,
+
+        /// the month that starts the fiscal year, like "4" or "apr" in the UK
+        /// or "oct" for the US federal government, January by default
+        #[arg(long, value_parser = parse_month)]
+        start_month: Option<u32>,
+    },
+    /// print the age in calendar years, months and days and the days until the next birthday,
+    /// the days are in the `-o` offset
+    Age {
+        birthdate: String,
+
+        /// the age at this date instead of now
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// print the dates from `start` to `end` every `step`, like "PT6H" or "P1M",
+    /// the steps are in the wall clock when `-o` is a time zone name
+    Seq {
+        start: String,
+        end: String,
+        #[arg(allow_hyphen_values = true)]
+        step: String,
+    },
+    /// print random dates from `start` to before `end`, every instant is as likely
+    Random {
+        start: String,
+        end: String,
+        #[arg(default_value_t = 1)]
+        count: usize,
+
+        /// the same seed gives the same dates
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// print the dates from the earliest
+        #[arg(long)]
+        sort: bool,
+    },
+    /// print the day of the week of the date and its ISO number, Monday is 1 and Sunday is 7
+    Weekday {
+        date: Option<String>,
+
+        /// like "Fri" instead of "Friday"
+        #[arg(long)]
+        short: bool,
+
+        /// the language of the name, the default is from `LC_MESSAGES`
+        #[arg(long, value_enum)]
+        locale: Option<Lang>,
+    },
+    /// print the ISO week and the week of the year that starts on `--week-start`,
+    /// the week 1 has January 1 so with `--week-start sun` it is the US week
+    Week {
+        date: String,
+    },
+    /// print when the next DST change of the zone is, the offsets before and after it
+    /// and how long until it, the default zone is the `-o` one
+    DstNext {
+        zone: Option<String>,
+
+        /// the duration flags of how long until the change, an option and not an argument
+        /// like in `until` because the zone can be left out, so `tm dst-next sn` would be a zone
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// print "yes" if DST is active at the date in the zone or "no" and exit with 1,
+    /// the default zone is the `-o` one, an error exits with 2 or more
+    IsDst {
+        date: String,
+        zone: Option<String>,
+    },
+    /// print "yes" if the year has February 29 or "no" and exit with 1,
+    /// an error exits with 2 or more
+    IsLeap {
+        #[arg(allow_negative_numbers = true)]
+        year: i32,
+    },
+    /// print how many days a month or a year has
+    DaysIn {
+        #[command(subcommand)]
+        command: DaysInCommand,
+    },
+    /// print "yes" if the file was modified more than the duration ago or "no" and exit with 1,
+    /// like `older-than cache.json P1D`, the days and months are in the wall clock of `-o`,
+    /// an error like a missing file exits with 2 or more
+    OlderThan {
+        path: std::path::PathBuf,
+        duration: String,
+    },
+    /// print "yes" if the file was modified less than the duration ago or "no" and exit with 1,
+    /// like `newer-than backup.tar PT6H`, an error like a missing file exits with 2 or more
+    NewerThan {
+        path: std::path::PathBuf,
+        duration: String,
+    },
+    /// print "yes" if `date` is before `other` or "no" and exit with 1,
+    /// an error exits with 2 or more
+    Before {
         date: String,
-        #[arg(short = 'F')]
-        to_format: Option<String>,
-        #[arg(short = 'O')]
-        offset: Option<String>,
+        other: String,
+    },
+    /// print "yes" if `date` is after `other` or "no" and exit with 1,
+    /// an error exits with 2 or more
+    After {
+        date: String,
+        other: String,
+    },
+    /// print "yes" if `date` is the same instant as `other`, in any offset, or "no" and exit with 1,
+    /// an error exits with 2 or more
+    Eq {
+        date: String,
+        other: String,
+    },
+    /// print "yes" if `date` is from `start` to before `end` or "no" and exit with 1,
+    /// an error exits with 2 or more,
+    /// like `tm between now "$not_before" "$not_after" --inclusive`,
+    /// without `end` the `start` is an interval like "2024-04-01/P2D"
+    Between {
+        date: String,
+        start: String,
+        end: Option<String>,
+
+        /// `end` is in the range too
+        #[arg(long)]
+        inclusive: bool,
+    },
+    /// sort the dates from stdin, the earliest first
+    Sort {
+        /// the latest first
+        #[arg(short, long)]
+        reverse: bool,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// print the earliest of the dates from stdin
+    Min {
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// print the latest of the dates from stdin
+    Max {
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// count the dates from stdin in every bucket of a duration, like "PT1H" or "P1D",
+    /// the buckets start at the multiples of it in the `-o` offset
+    Histogram {
+        #[arg(long, default_value = "PT1H")]
+        bucket: String,
+
+        /// the width of the longest bar
+        #[arg(long, default_value_t = 50)]
+        width: usize,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// count the dates from stdin in every hour of the day or every weekday in the `-o` offset
+    /// and print a bar for every count
+    GroupBy {
+        #[arg(value_enum)]
+        by: GroupBy,
+
+        /// the width of the longest bar
+        #[arg(long, default_value_t = 50)]
+        width: usize,
+
+        /// the language of the weekdays, the default is from `LC_MESSAGES`
+        #[arg(long, value_enum)]
+        locale: Option<Lang>,
+    },
+    /// print how many dates are in stdin, the first and the last of them, the time between them
+    /// and the min, mean, median and max of the gaps between the dates when they are sorted
+    Stats {
+        /// the duration flags of the span and the gaps
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// split the dates from stdin in to sessions where a gap longer than `--idle` starts a new one,
+    /// print the start, the end, the duration and the number of dates of every session
+    Sessions {
+        #[arg(long, default_value = "PT30M")]
+        idle: String,
+
+        /// the duration flags of the sessions
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// copy stdin to stdout with every date in the `-f` format translated like `translate`,
+    /// the rest of the text is left as it is
+    Rewrite {
+        /// the dates are the matches of the regex, parsed with `-f` or one of the auto formats
+        #[arg(long)]
+        regex: Option<String>,
+
+        #[command(flatten)]
+        to: ReformatArgs,
+    },
+    /// find the formats that can parse a sample date, the most likely is the first
+    GuessFormat {
+        sample: String,
+    },
+    /// print the shell completions, like `tm completions bash > /etc/bash_completion.d/tm`
+    Completions {
+        shell: clap_complete::Shell,
+    },
+    /// print the man page, or with `--dir` write a page for `tm` and every subcommand in the directory,
+    /// the nested ones like `tm-dur-add.1` too
+    Man {
+        #[arg(long)]
+        dir: Option<std::path::PathBuf>,
     },
     HelpFormat {
         get_or_search: Option<String>,
@@ -80,259 +900,3826 @@ pub enum TimeManCommand {
     HelpDuration,
 }
 
-fn main() {
-    let time_man = TimeMan::parse();
+#[derive(Subcommand)]
+pub enum UnixCommand {
+    /// the current unix timestamp
+    Now,
+    /// the date of a unix timestamp, you can use `-o` before to set the utc offset
+    From {
+        #[arg(allow_hyphen_values = true)]
+        timestamp: i64,
+    },
+    /// the unix timestamp of a date
+    To { date: Option<String> },
+}
 
-    let utc_offset = time_man
-        .utc_offset
-        .map(|offset| {
-            if let Ok(offset) = chrono::FixedOffset::from_str(&offset) {
-                offset
-            } else {
-                eprintln!("The offset should look like \"+00:00\"");
-                std::process::exit(1)
-            }
-        })
-        .unwrap_or(chrono::Local::now().offset().fix());
+#[derive(Subcommand)]
+pub enum ConvertEpochCommand {
+    /// the date of a timestamp, you can use `-o` before to set the utc offset
+    From {
+        #[arg(allow_hyphen_values = true)]
+        timestamp: String,
+    },
+    /// the timestamp of a date
+    To { date: Option<String> },
+}
 
-    let format = if let Ok(format) =
-        chrono::format::strftime::StrftimeItems::new(&time_man.format).parse()
-    {
-        format
-    } else {
-        eprintln!("Invalid format, run command `help-format`");
-        std::process::exit(1);
-    };
+#[derive(Subcommand)]
+pub enum JdCommand {
+    /// the date of a Julian Day like 2460427.25, you can use `-o` before to set the utc offset
+    From {
+        #[arg(allow_hyphen_values = true)]
+        number: f64,
+    },
+    /// the Julian Day of a date
+    To { date: Option<String> },
+}
 
-    let offset = chrono::FixedOffset::from_offset(&utc_offset);
+#[derive(Subcommand)]
+pub enum DurCommand {
+    /// the sum of the durations, a negative one can be first like "-PT5M",
+    /// the flags are before the durations
+    Add {
+        #[arg(required = true, allow_hyphen_values = true)]
+        durations: Vec<String>,
 
-    match time_man.command {
-        TimeManCommand::Now => {
-            let now = offset.from_utc_datetime(&chrono::Utc::now().naive_utc());
-            let date = now.format_with_items(format.iter());
-            println!("{date}");
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+    /// the first duration minus the rest of them, the flags are before the durations
+    Sub {
+        #[arg(required = true, allow_hyphen_values = true)]
+        durations: Vec<String>,
+
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+    /// the duration as a number of one unit, like "1.25" days for "P1DT6H"
+    In {
+        #[arg(value_enum)]
+        unit: RoundUnit,
+        #[arg(allow_hyphen_values = true)]
+        duration: String,
+    },
+    /// the duration times a factor like "3" or "1.5", rounded to the nanosecond
+    #[command(allow_negative_numbers = true)]
+    Scale {
+        #[arg(allow_hyphen_values = true)]
+        duration: String,
+        factor: String,
+
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+    /// the duration divided by a number like "4" or "2.5", rounded to the nanosecond
+    #[command(allow_negative_numbers = true)]
+    Div {
+        #[arg(allow_hyphen_values = true)]
+        duration: String,
+        divisor: String,
+
+        #[command(flatten)]
+        print: DurPrintArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TzCommand {
+    /// list the time zone names with their current offset,
+    /// only the ones that have the pattern in them or that have the offset like "+03:00"
+    List { pattern: Option<String> },
+}
+
+#[derive(Subcommand)]
+pub enum CronCommand {
+    /// the next times after `--from`
+    Next {
+        #[command(flatten)]
+        args: ScheduleArgs,
+    },
+    /// the previous times before `--from`, the latest is first
+    Prev {
+        #[command(flatten)]
+        args: ScheduleArgs,
+    },
+    /// describe the schedule in English
+    Explain { expression: String },
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ScheduleArgs {
+    /// like "*/15 2 * * 1-5" for `cron` or "Mon..Fri *-*-* 02:00" for `oncalendar`
+    expression: String,
+    #[arg(default_value_t = 1)]
+    count: usize,
+
+    /// the default is now
+    #[arg(long)]
+    from: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum OnCalendarCommand {
+    /// the next elapses after `--from` like `systemd-analyze calendar --iterations`
+    Next {
+        #[command(flatten)]
+        args: ScheduleArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IntervalCommand {
+    /// print the start and the end in the format of `-f` and the duration
+    Show {
+        interval: String,
+
+        /// the duration flags of the duration
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+    /// print "yes" if the intervals overlap or "no" and exit with 1, an error exits with 2 or more,
+    /// with the intersection and the union when they are intervals
+    Overlap { a: String, b: String },
+}
+
+#[derive(Subcommand)]
+pub enum StopwatchCommand {
+    /// start it, or continue it when it is stopped
+    Start {
+        #[command(flatten)]
+        args: StopwatchArgs,
+    },
+    /// save the time of a lap, it has to be running
+    Lap {
+        #[command(flatten)]
+        args: StopwatchArgs,
+    },
+    /// stop it, `start` continues it
+    Stop {
+        #[command(flatten)]
+        args: StopwatchArgs,
+    },
+    /// print if it is running, how long it was running and the laps
+    Show {
+        #[command(flatten)]
+        args: StopwatchArgs,
+    },
+    /// forget it
+    Reset {
+        #[command(flatten)]
+        args: StopwatchArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrackCommand {
+    /// start tracking the project, the one that is tracked is stopped and printed
+    Start {
+        project: String,
+
+        #[command(flatten)]
+        args: TrackArgs,
+    },
+    /// stop tracking and print the project and how long it was tracked
+    Stop {
+        #[command(flatten)]
+        args: TrackArgs,
+    },
+    /// print the project that is tracked and for how long, or exit with 3 when there is none
+    Status {
+        #[command(flatten)]
+        args: TrackArgs,
+    },
+    /// the time of every project and the total from `--since` to `--until`
+    Report {
+        /// the default is from the first entry
+        #[arg(long)]
+        since: Option<String>,
+
+        /// the default is now
+        #[arg(long)]
+        until: Option<String>,
+
+        #[command(flatten)]
+        args: TrackArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IcsCommand {
+    /// the events from `--from` to `--to` by start, the ones going on at `--from` too
+    List {
+        #[command(flatten)]
+        args: IcsArgs,
+
+        /// the default is a week after `--from`
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// the next events that start after `--from`
+    Next {
+        #[command(flatten)]
+        args: IcsArgs,
+
+        #[arg(default_value_t = 1)]
+        count: usize,
+    },
+    /// the times in the working hours of the next days that are free in all the files,
+    /// the events with `TRANSP:TRANSPARENT` are free
+    Free {
+        /// the .ics files, like the calendars of everyone in a meeting
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// the default is now
+        #[arg(long)]
+        from: Option<String>,
+
+        /// how many days from `--from`
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+
+        /// the working hours in the zone of `-o`, like "9-17" or "09:00-17:30",
+        /// the default is `working_hours` of the config or 9-17
+        #[arg(long, value_parser = parse_working_hours)]
+        hours: Option<timeman::WorkingHours>,
+
+        /// the shortest free time, like "PT30M" or "1h"
+        #[arg(long, default_value = "PT30M")]
+        min: String,
+
+        #[command(flatten)]
+        calendar: BusinessArgs,
+
+        /// the duration flags of the free times
+        #[arg(long)]
+        duration_flags: Option<String>,
+
+        #[command(flatten)]
+        style: DurationArgs,
+    },
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct IcsArgs {
+    /// the .ics file, the recurring events are expanded
+    file: String,
+
+    /// the default is now
+    #[arg(long)]
+    from: Option<String>,
+
+    /// the duration flags of how long until the events
+    #[arg(long)]
+    duration_flags: Option<String>,
+
+    #[command(flatten)]
+    style: DurationArgs,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct StopwatchArgs {
+    #[arg(default_value = "default")]
+    name: String,
+
+    /// the duration flags of the elapsed time and the laps, the default is "hms"
+    #[arg(long)]
+    duration_flags: Option<String>,
+
+    #[command(flatten)]
+    style: DurationArgs,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct TrackArgs {
+    /// the duration flags of the tracked time, the default is "hms"
+    #[arg(long)]
+    duration_flags: Option<String>,
+
+    #[command(flatten)]
+    style: DurationArgs,
+}
+
+#[derive(Subcommand)]
+pub enum DaysInCommand {
+    /// like "2024-02"
+    Month {
+        #[arg(value_parser = parse_year_month)]
+        month: (i32, u32),
+    },
+    Year {
+        #[arg(allow_negative_numbers = true)]
+        year: i32,
+    },
+}
+
+/// The format and the offset that the dates are printed in, instead of `-f` and their own offset
+#[derive(Debug, Clone, clap::Args)]
+pub struct ReformatArgs {
+    /// The format or a preset to translate to
+    #[arg(short = 'F')]
+    to_format: Option<String>,
+    /// UTC offset like "+03:00" or a time zone name like "America/New_York"
+    #[arg(short = 'O')]
+    offset: Option<String>,
+}
+
+impl ReformatArgs {
+    /// The format, none when it is `-f`, and the zone, a preset in UTC is in UTC
+    pub fn parse<'a>(
+        &'a self,
+        config: &'a timeman::Config,
+    ) -> (Option<Vec<chrono::format::Item<'a>>>, Option<Zone>) {
+        let to_format = self
+            .to_format
+            .as_deref()
+            .map(|to_format| parse_to_format(config, to_format));
+        let mut to_zone = self.offset.as_deref().map(parse_zone);
+        if to_format.as_ref().is_some_and(|(_, utc)| *utc) {
+            to_zone = Some(Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap()));
+        }
+        (to_format.map(|(to_format, _)| to_format), to_zone)
+    }
+}
+
+/// How the result of `dur` is printed
+#[derive(Debug, Clone, clap::Args)]
+pub struct DurPrintArgs {
+    /// the duration flags of the result
+    #[arg(long)]
+    duration_flags: Option<String>,
+
+    #[command(flatten)]
+    style: DurationArgs,
+}
+
+impl DurPrintArgs {
+    /// Prints the duration with the years and months of a fixed length
+    pub fn print(
+        mut self,
+        timedelta: chrono::TimeDelta,
+        config: &timeman::Config,
+        output: &Output,
+    ) {
+        self.style.preety |= config.preety;
+        let flags = self.style.flags(
+            self.duration_flags
+                .or_else(|| config.duration_flags.clone()),
+        );
+        let (timedelta, breakdown) = self.style.timedelta(timedelta, flags);
+        output.breakdown(timedelta, breakdown, &self.style, false);
+    }
+}
+
+/// The business calendar of `business-days` and `add-business-days`
+#[derive(Debug, Clone, clap::Args)]
+pub struct BusinessArgs {
+    /// a file with a "YYYY-MM-DD" holiday on every line, an iCal file,
+    /// or a country like "RO" or "US" for its public holidays, can be given more times
+    #[arg(long)]
+    holidays: Vec<String>,
+
+    /// the days that are not worked
+    #[arg(long, value_delimiter = ',', value_parser = parse_weekday, default_value = "sat,sun")]
+    weekend: Vec<chrono::Weekday>,
+}
+
+impl BusinessArgs {
+    pub fn calendar(&self) -> timeman::BusinessCalendar {
+        let mut calendar = timeman::BusinessCalendar {
+            weekend: self.weekend.clone(),
+            ..Default::default()
+        };
+
+        for holidays in &self.holidays {
+            let path = std::path::Path::new(holidays);
+            #[cfg(feature = "holidays")]
+            if !path.exists() {
+                if let Some(country) = timeman::holidays::country(holidays) {
+                    calendar.rules.extend_from_slice(country);
+                    continue;
+                }
+            }
+
+            let file = match std::fs::read_to_string(path) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("Cannot read the holidays from `{holidays}`: {err}");
+                    std::process::exit(19)
+                }
+            };
+            match timeman::parse_holidays(&file) {
+                Ok(dates) => calendar.holidays.extend(dates),
+                Err(err) => {
+                    eprintln!("`{holidays}` has an {err}");
+                    std::process::exit(19)
+                }
+            }
+        }
+        calendar
+    }
+}
+
+/// The weekday of `next` and `prev`
+#[derive(Debug, Clone, clap::Args)]
+pub struct WeekdayArgs {
+    #[arg(value_parser = parse_weekday)]
+    weekday: chrono::Weekday,
+    #[arg(value_name = "[from] DATE", num_args = 0..=2)]
+    date: Vec<String>,
+
+    /// the time of the day instead of the midnight, like "14:00" or "9:30:15"
+    #[arg(long, value_parser = parse_time_of_day)]
+    at: Option<chrono::NaiveTime>,
+}
+
+impl WeekdayArgs {
+    /// The date operand without `from`, it is now when there is none and no `--stdin`
+    pub fn date(&self, stdin: bool) -> Option<String> {
+        match self.date.as_slice() {
+            [] if stdin => None,
+            [] => Some("now".to_owned()),
+            [date] => Some(date.clone()),
+            [from, date] if from == "from" => Some(date.clone()),
+            _ => {
+                eprintln!("The date should look like `from <date>` or `<date>`!");
+                std::process::exit(2)
+            }
+        }
+    }
+
+    /// Prints the weekday after the date, or before it when not `forward`
+    pub fn print(&self, forward: bool, stdin: bool, parser: &DateParser, output: &Output) {
+        for_each_date(stdin, self.date(stdin), "date", |date| {
+            let date = parser.try_parse(date, "date")?;
+            let Some(res) =
+                timeman::weekday_occurrence(date, self.weekday, forward, self.at, parser.zone)
+            else {
+                return Err(DateError::out_of_range("result"));
+            };
+            output.date(&res, parser.format);
+            Ok(())
+        });
+    }
+}
+
+/// How the duration of `since`, `until` and `sub` is printed
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct DurationArgs {
+    /// print it like "1 Day, 2 Hours, ", same as `--style long`
+    #[arg(short)]
+    preety: bool,
+
+    /// how the duration is printed
+    #[arg(long, value_enum)]
+    style: Option<DurationStyle>,
+
+    /// the language of `--style long`, the default is from `LC_MESSAGES`
+    #[arg(long, value_enum)]
+    lang: Option<Lang>,
+
+    /// a template like "{h:02}:{m:02}:{s:02}" or "{D}d {h}h",
+    /// the duration is split only in to the units of the template, see `help-duration`
+    #[arg(long)]
+    dur_format: Option<DurationTemplate>,
+
+    /// keep only the N biggest units, like "1 Year, 2 Months, "
+    #[arg(long, value_name = "N")]
+    max_units: Option<usize>,
+
+    /// print only the biggest unit with the rest rounded in it, like "about 3 weeks"
+    #[arg(long, conflicts_with_all = ["max_units", "relative", "dur_format"])]
+    largest: bool,
+
+    /// round to the nearest unit instead of showing the smaller units, like "PT3H" for "PT2H59M59.9S"
+    #[arg(long, value_enum)]
+    round: Option<RoundUnit>,
+
+    /// the digits after the "." of the seconds, the duration is rounded to them
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=9))]
+    precision: Option<u8>,
+
+    /// print it like "3 days ago", "in 2 hours" or "just now"
+    #[arg(long)]
+    relative: bool,
+
+    /// the smallest unit of `--relative`, a shorter duration is "just now"
+    #[arg(long, value_enum, requires = "relative", default_value_t = RoundUnit::Second)]
+    granularity: RoundUnit,
+}
+
+impl DurationArgs {
+    /// The breakdown with `--max-units` and the text of it in the selected style,
+    /// `future` is when a positive duration is in the future like the one of `until`
+    pub fn format(
+        &self,
+        breakdown: DurationBreakdown,
+        future: bool,
+    ) -> (DurationBreakdown, String) {
+        let mut breakdown = match self.max_units {
+            Some(max_units) => breakdown.truncate(max_units),
+            None => breakdown,
+        };
+        breakdown.precision = self.precision;
+        let formatted = if let Some(template) = &self.dur_format {
+            template.render(&breakdown)
+        } else if self.largest {
+            breakdown.approximate()
+        } else if self.relative {
+            DurationBreakdown {
+                negative: breakdown.negative != future,
+                ..breakdown
+            }
+            .relative(self.granularity.flag())
+        } else {
+            breakdown.render(self.style(), self.lang.unwrap_or_else(Lang::from_env))
+        };
+        (breakdown, formatted)
+    }
+
+    pub fn style(&self) -> DurationStyle {
+        match self.style {
+            Some(style) => style,
+            None if self.preety => DurationStyle::Long,
+            None => DurationStyle::Iso,
+        }
+    }
+
+    /// The duration from `from` to `to`, rounded with `--round`
+    pub fn between(
+        &self,
+        from: chrono::DateTime<chrono::FixedOffset>,
+        to: chrono::DateTime<chrono::FixedOffset>,
+        flags: TimedeltaFlags,
+    ) -> (chrono::TimeDelta, DurationBreakdown) {
+        // the leap seconds between them are added to the end, a `from` in a leap second
+        // is moved out of it because chrono cannot add to it
+        let (from, to) = match LEAP_SECONDS.get() {
+            Some(leap_seconds) => {
+                let timedelta = leap_seconds.between(from.to_utc(), to.to_utc());
+                let from = from
+                    .with_nanosecond(from.nanosecond() % 1_000_000_000)
+                    .unwrap_or(from);
+                let to = from.checked_add_signed(timedelta).unwrap_or_else(|| {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                });
+                (from, to)
+            }
+            None => (from, to),
+        };
+        let Some(unit) = self.round_unit() else {
+            return (to - from, DurationBreakdown::between(from, to, flags));
+        };
+
+        let Some(timedelta) = timeman::round_timedelta(to - from, unit, timeman::Rounding::Nearest)
+        else {
+            eprintln!("The result is out of range!");
+            std::process::exit(13)
+        };
+        let Some(to) = from.checked_add_signed(timedelta) else {
+            eprintln!("The result is out of range!");
+            std::process::exit(13)
+        };
+        (
+            timedelta,
+            DurationBreakdown::between(from, to, self.round_flags(flags)),
+        )
+    }
+
+    /// The unit of `--round`, or the smallest digit of `--precision`
+    fn round_unit(&self) -> Option<chrono::TimeDelta> {
+        match (self.round, self.precision) {
+            (Some(round), _) => Some(round.unit()),
+            (None, Some(precision)) => Some(chrono::TimeDelta::nanoseconds(
+                10i64.pow(9 - u32::from(precision)),
+            )),
+            (None, None) => None,
+        }
+    }
+
+    /// `flags` without the units that are smaller than `--round`
+    fn round_flags(&self, flags: TimedeltaFlags) -> TimedeltaFlags {
+        self.round.map_or(flags, |round| round.flags(flags))
+    }
+
+    /// Like `between` but for a duration that is not between two dates,
+    /// so the years and months have a fixed length
+    pub fn timedelta(
+        &self,
+        timedelta: chrono::TimeDelta,
+        flags: TimedeltaFlags,
+    ) -> (chrono::TimeDelta, DurationBreakdown) {
+        let Some(unit) = self.round_unit() else {
+            return (timedelta, DurationBreakdown::new(timedelta, flags));
+        };
+
+        let Some(timedelta) = timeman::round_timedelta(timedelta, unit, timeman::Rounding::Nearest)
+        else {
+            eprintln!("The result is out of range!");
+            std::process::exit(13)
+        };
+        (
+            timedelta,
+            DurationBreakdown::new(timedelta, self.round_flags(flags)),
+        )
+    }
+
+    /// The units of the template, or the `duration_flags` or all of them
+    pub fn flags(&self, duration_flags: Option<String>) -> TimedeltaFlags {
+        if let Some(template) = &self.dur_format {
+            return template.flags();
+        }
+        let Some(duration_flags) = duration_flags else {
+            return TimedeltaFlags::all();
+        };
+        duration_flags.parse().unwrap_or_else(|err| {
+            match err {
+                timeman::Error::InvalidDurationFlags(err) => {
+                    eprintln!("Invalid duration flags, {err}!")
+                }
+                err => eprintln!("{err}!"),
+            }
+            std::process::exit(2)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// only the formatted result
+    Text,
+    /// a json object with the formatted result and the structured fields
+    Json,
+}
+
+/// Prints the results of the commands in the selected `OutputMode`
+pub struct Output {
+    pub mode: OutputMode,
+}
+
+impl Output {
+    pub fn date(
+        &self,
+        date: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+    ) {
+        let formatted = date.format_with_items(format.iter()).to_string();
+        match self.mode {
+            OutputMode::Text => println!("{formatted}"),
+            OutputMode::Json => println!("{}", date_json(date, formatted)),
+        }
+    }
+
+    /// A row for every zone with the name of the zone and the date in it
+    pub fn zoned_dates(
+        &self,
+        dates: &[(&str, chrono::DateTime<chrono::FixedOffset>)],
+        format: &[chrono::format::Item],
+    ) {
+        let rows = dates.iter().map(|(zone, date)| {
+            let formatted = date.format_with_items(format.iter()).to_string();
+            (zone, date, formatted)
+        });
+        match self.mode {
+            OutputMode::Text => print_table(
+                &rows
+                    .map(|(zone, _, formatted)| vec![zone.to_string(), formatted])
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::Value::from_iter(rows.map(|(zone, date, formatted)| {
+                    let mut json = date_json(date, formatted);
+                    json["zone"] = serde_json::Value::from(*zone);
+                    json
+                }))
+            ),
+        }
+    }
+
+    pub fn duration(&self, timedelta: chrono::TimeDelta, flags: TimedeltaFlags, preety: bool) {
+        let style = DurationArgs {
+            preety,
+            ..Default::default()
+        };
+        self.breakdown(
+            timedelta,
+            DurationBreakdown::new(timedelta, flags),
+            &style,
+            false,
+        );
+    }
+
+    /// `future` is when a positive duration is in the future like the one of `until`
+    pub fn breakdown(
+        &self,
+        timedelta: chrono::TimeDelta,
+        breakdown: DurationBreakdown,
+        style: &DurationArgs,
+        future: bool,
+    ) {
+        let (breakdown, formatted) = style.format(breakdown, future);
+        match self.mode {
+            OutputMode::Text => println!("{formatted}"),
+            OutputMode::Json => println!("{}", duration_json(timedelta, &breakdown, formatted)),
+        }
+    }
+
+    /// A row for every bucket with its count and a bar that is as long as the count
+    pub fn histogram(
+        &self,
+        buckets: &[(chrono::DateTime<chrono::FixedOffset>, usize)],
+        format: &[chrono::format::Item],
+        width: usize,
+    ) {
+        let max = buckets
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let rows = buckets.iter().map(|(date, count)| {
+            let formatted = date.format_with_items(format.iter()).to_string();
+            (date, count, formatted)
+        });
+        match self.mode {
+            OutputMode::Text => print_table(
+                &rows
+                    .map(|(_, count, formatted)| {
+                        vec![formatted, count.to_string(), bar(*count, max, width)]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => {
+                for (date, count, formatted) in rows {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "bucket": date_json(date, formatted),
+                            "count": count,
+                        })
+                    );
+                }
+            }
+        }
+    }
+
+    /// A month like `cal` or all the months of the year in rows of `columns` with no month,
+    /// in json a line for every month with its weeks
+    pub fn calendar(
+        &self,
+        year: i32,
+        month: Option<u32>,
+        columns: u8,
+        style: &timeman::CalendarStyle,
+        highlighted: &[chrono::NaiveDate],
+    ) {
+        let months = month.map_or(1..=12, |month| month..=month);
+        if self.mode == OutputMode::Json {
+            for month in months {
+                self.month_json(year, month, style, highlighted);
+            }
+            return;
+        }
+
+        let lines = match month {
+            Some(month) => timeman::render_month(year, month, style, highlighted),
+            None => timeman::render_year(year, columns.into(), style, highlighted),
+        };
+        let Some(lines) = lines else {
+            eprintln!("The result is out of range!");
+            std::process::exit(13)
+        };
+        for line in lines {
+            println!("{}", line.trim_end());
+        }
+    }
+
+    fn month_json(
+        &self,
+        year: i32,
+        month: u32,
+        style: &timeman::CalendarStyle,
+        highlighted: &[chrono::NaiveDate],
+    ) {
+        let Some(weeks) = timeman::month_weeks(year, month, style.week_start) else {
+            eprintln!("The result is out of range!");
+            std::process::exit(13)
+        };
+        let in_month = |day: &&chrono::NaiveDate| day.year() == year && day.month() == month;
+        let weeks = weeks.iter().map(|week| {
+            let monday = week[week
+                .iter()
+                .position(|day| day.weekday() == chrono::Weekday::Mon)
+                .unwrap_or(0)];
+            serde_json::json!({
+                "iso_week": monday.iso_week().week(),
+                "days": week
+                    .iter()
+                    .map(|day| in_month(&day).then(|| day.to_string()))
+                    .collect::<Vec<_>>(),
+            })
+        });
+        println!(
+            "{}",
+            serde_json::json!({
+                "year": year,
+                "month": month,
+                "name": style.lang.month(month),
+                "weeks": weeks.collect::<Vec<_>>(),
+                "highlighted": highlighted
+                    .iter()
+                    .filter(in_month)
+                    .map(|day| day.to_string())
+                    .collect::<Vec<_>>(),
+            })
+        );
+    }
+
+    /// Like `systemd-analyze calendar`, the forms of the event and its elapses
+    pub fn elapses(
+        &self,
+        original: &str,
+        calendar: &timeman::OnCalendar,
+        elapses: &[chrono::DateTime<chrono::FixedOffset>],
+        now: chrono::DateTime<chrono::Utc>,
+        format: &[chrono::format::Item],
+    ) {
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        match self.mode {
+            OutputMode::Text => {
+                println!("  Original form: {original}");
+                println!("Normalized form: {calendar}");
+                for (i, date) in elapses.iter().enumerate() {
+                    if i == 0 {
+                        println!("    Next elapse: {}", formatted(date));
+                        let left = date.to_utc() - now;
+                        let breakdown = DurationBreakdown::new(
+                            left.abs(),
+                            TimedeltaFlags::DAY
+                                | TimedeltaFlags::HOUR
+                                | TimedeltaFlags::MINUTE
+                                | TimedeltaFlags::SECOND,
+                        );
+                        let when = if left < chrono::TimeDelta::zero() {
+                            "ago"
+                        } else {
+                            "left"
+                        };
+                        println!(
+                            "       From now: {} {when}",
+                            breakdown.render(DurationStyle::Compact, Lang::En)
+                        );
+                    } else {
+                        println!(
+                            "{:>15}: {}",
+                            format!("Iteration #{}", i + 1),
+                            formatted(date)
+                        );
+                    }
+                }
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "original": original,
+                    "normalized": calendar.to_string(),
+                    "elapses": elapses
+                        .iter()
+                        .map(|date| date_json(date, formatted(date)))
+                        .collect::<Vec<_>>(),
+                })
+            ),
+        }
+    }
+
+    /// A row for every occurrence with its start, end, how long from `now` until it starts
+    /// and its summary, the times are in `zone`
+    pub fn events(
+        &self,
+        occurrences: &[timeman::Occurrence],
+        now: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        format: &[chrono::format::Item],
+        style: &DurationArgs,
+        flags: TimedeltaFlags,
+    ) {
+        let rows = occurrences.iter().map(|occurrence| {
+            let start = zone.from_utc_datetime(&occurrence.start.naive_utc());
+            let end = zone.from_utc_datetime(&occurrence.end.naive_utc());
+            let (timedelta, breakdown) = style.between(now, start, flags);
+            let (breakdown, until) = style.format(breakdown, true);
+            (occurrence.event, start, end, timedelta, breakdown, until)
+        });
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        match self.mode {
+            OutputMode::Text => print_table(
+                &rows
+                    .map(|(event, start, end, _, _, until)| {
+                        let summary = match &event.location {
+                            Some(location) => format!("{} @ {location}", event.summary),
+                            None => event.summary.clone(),
+                        };
+                        vec![formatted(&start), formatted(&end), until, summary]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => {
+                for (event, start, end, timedelta, breakdown, until) in rows {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "summary": event.summary,
+                            "location": event.location,
+                            "uid": event.uid,
+                            "all_day": event.all_day(),
+                            "start": date_json(&start, formatted(&start)),
+                            "end": date_json(&end, formatted(&end)),
+                            "until": duration_json(timedelta, &breakdown, until),
+                        })
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn interval(
+        &self,
+        interval: &timeman::Interval,
+        format: &[chrono::format::Item],
+        timedelta: chrono::TimeDelta,
+        breakdown: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let start = interval.start.format_with_items(format.iter()).to_string();
+        let end = interval.end.format_with_items(format.iter()).to_string();
+        let (breakdown, duration) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("start    : {start}");
+                println!("end      : {end}");
+                println!("duration : {duration}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "interval": interval.to_string(),
+                    "start": date_json(&interval.start, start),
+                    "end": date_json(&interval.end, end),
+                    "duration": duration_json(timedelta, &breakdown, duration),
+                })
+            ),
+        }
+    }
+
+    /// The project, when it was started and how long it was tracked
+    pub fn track_entry(
+        &self,
+        project: &str,
+        start: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        (timedelta, breakdown): (chrono::TimeDelta, DurationBreakdown),
+        style: &DurationArgs,
+    ) {
+        let formatted = start.format_with_items(format.iter()).to_string();
+        let (breakdown, duration) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("project  : {project}");
+                println!("start    : {formatted}");
+                println!("duration : {duration}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "project": project,
+                    "start": date_json(start, formatted),
+                    "duration": duration_json(timedelta, &breakdown, duration),
+                })
+            ),
+        }
+    }
+
+    /// A row with the time of every project and the total at the end
+    pub fn track_report(
+        &self,
+        totals: &[(&str, (chrono::TimeDelta, DurationBreakdown))],
+        (timedelta, breakdown): (chrono::TimeDelta, DurationBreakdown),
+        style: &DurationArgs,
+    ) {
+        let totals = totals.iter().map(|(project, (timedelta, breakdown))| {
+            let (breakdown, formatted) = style.format(*breakdown, false);
+            (project, timedelta, breakdown, formatted)
+        });
+        let (breakdown, total) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                let mut rows: Vec<_> = totals
+                    .map(|(project, _, _, formatted)| vec![project.to_string(), formatted])
+                    .collect();
+                rows.push(vec!["total".to_owned(), total]);
+                print_table(&rows);
+            }
+            OutputMode::Json => {
+                let projects = totals
+                    .map(|(project, timedelta, breakdown, formatted)| {
+                        (
+                            project.to_string(),
+                            duration_json(*timedelta, &breakdown, formatted),
+                        )
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "projects": projects,
+                        "total": duration_json(timedelta, &breakdown, total),
+                    })
+                )
+            }
+        }
+    }
+
+    /// If it is running, how long it was running and the time of every lap
+    pub fn stopwatch(
+        &self,
+        name: &str,
+        running: bool,
+        (timedelta, breakdown): (chrono::TimeDelta, DurationBreakdown),
+        laps: Vec<(chrono::TimeDelta, DurationBreakdown)>,
+        style: &DurationArgs,
+    ) {
+        let (breakdown, elapsed) = style.format(breakdown, false);
+        let laps: Vec<_> = laps
+            .into_iter()
+            .map(|(timedelta, breakdown)| {
+                let (breakdown, formatted) = style.format(breakdown, false);
+                (timedelta, breakdown, formatted)
+            })
+            .collect();
+        match self.mode {
+            OutputMode::Text => {
+                let state = if running { "running" } else { "stopped" };
+                let mut lines = vec![
+                    ("name".to_owned(), name),
+                    ("state".to_owned(), state),
+                    ("elapsed".to_owned(), elapsed.as_str()),
+                ];
+                for (i, (_, _, lap)) in laps.iter().enumerate() {
+                    lines.push((format!("lap {}", i + 1), lap.as_str()));
+                }
+                let width = lines.iter().map(|(label, _)| label.len()).max();
+                for (label, value) in &lines {
+                    println!("{label:<width$} : {value}", width = width.unwrap_or(0));
+                }
+            }
+            OutputMode::Json => {
+                let laps = laps.into_iter().map(|(timedelta, breakdown, formatted)| {
+                    duration_json(timedelta, &breakdown, formatted)
+                });
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "name": name,
+                        "running": running,
+                        "elapsed": duration_json(timedelta, &breakdown, elapsed),
+                        "laps": laps.collect::<Vec<_>>(),
+                    })
+                )
+            }
+        }
+    }
+
+    /// Every time of the file, with how long ago it is when there is a duration
+    #[allow(clippy::type_complexity)]
+    pub fn file_times(
+        &self,
+        times: &[(
+            &str,
+            Option<(
+                chrono::DateTime<chrono::FixedOffset>,
+                Option<(chrono::TimeDelta, DurationBreakdown)>,
+            )>,
+        )],
+        format: &[chrono::format::Item],
+        style: &DurationArgs,
+    ) {
+        let times = times.iter().map(|(name, time)| {
+            let time = time.map(|(time, since)| {
+                let formatted = time.format_with_items(format.iter()).to_string();
+                let since = since.map(|(timedelta, breakdown)| {
+                    let (breakdown, since) = style.format(breakdown, false);
+                    (timedelta, breakdown, since)
+                });
+                (time, formatted, since)
+            });
+            (name, time)
+        });
+        match self.mode {
+            OutputMode::Text => {
+                for (name, time) in times {
+                    match time {
+                        Some((_, formatted, Some((_, _, since)))) => {
+                            println!("{name:<8} : {formatted}  {since}")
+                        }
+                        Some((_, formatted, None)) => println!("{name:<8} : {formatted}"),
+                        None => println!("{name:<8} : -"),
+                    }
+                }
+            }
+            OutputMode::Json => {
+                let times = times
+                    .map(|(name, time)| {
+                        let time = time.map(|(time, formatted, since)| {
+                            let mut json = date_json(&time, formatted);
+                            if let Some((timedelta, breakdown, since)) = since {
+                                json["since"] = duration_json(timedelta, &breakdown, since);
+                            }
+                            json
+                        });
+                        (name.to_string(), time.into())
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                println!("{}", serde_json::Value::Object(times));
+            }
+        }
+    }
+
+    pub fn uptime(
+        &self,
+        boot: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        timedelta: chrono::TimeDelta,
+        breakdown: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let formatted = boot.format_with_items(format.iter()).to_string();
+        let (breakdown, uptime) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("boot   : {formatted}");
+                println!("uptime : {uptime}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "boot": date_json(boot, formatted),
+                    "uptime": duration_json(timedelta, &breakdown, uptime),
+                })
+            ),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn ntp(
+        &self,
+        server: &str,
+        time: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        stratum: u8,
+        (offset_timedelta, offset): (chrono::TimeDelta, DurationBreakdown),
+        (delay_timedelta, delay): (chrono::TimeDelta, DurationBreakdown),
+        style: &DurationArgs,
+    ) {
+        let formatted = time.format_with_items(format.iter()).to_string();
+        let (offset, offset_str) = style.format(offset, false);
+        let (delay, delay_str) = style.format(delay, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("server  : {server}");
+                println!("time    : {formatted}");
+                println!("offset  : {offset_str}");
+                println!("delay   : {delay_str}");
+                println!("stratum : {stratum}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "server": server,
+                    "time": date_json(time, formatted),
+                    "offset": duration_json(offset_timedelta, &offset, offset_str),
+                    "delay": duration_json(delay_timedelta, &delay, delay_str),
+                    "stratum": stratum,
+                })
+            ),
+        }
+    }
+
+    #[cfg(feature = "http")]
+    pub fn http_date(
+        &self,
+        url: &str,
+        date: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        (offset_timedelta, offset): (chrono::TimeDelta, DurationBreakdown),
+        (delay_timedelta, delay): (chrono::TimeDelta, DurationBreakdown),
+        style: &DurationArgs,
+    ) {
+        let formatted = date.format_with_items(format.iter()).to_string();
+        let (offset, offset_str) = style.format(offset, false);
+        let (delay, delay_str) = style.format(delay, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("url    : {url}");
+                println!("date   : {formatted}");
+                println!("offset : {offset_str}");
+                println!("delay  : {delay_str}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "url": url,
+                    "date": date_json(date, formatted),
+                    "offset": duration_json(offset_timedelta, &offset, offset_str),
+                    "delay": duration_json(delay_timedelta, &delay, delay_str),
+                })
+            ),
+        }
+    }
+
+    /// The clock before and after and how much it moved
+    pub fn set_system(
+        &self,
+        before: &chrono::DateTime<chrono::FixedOffset>,
+        after: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        (timedelta, breakdown): (chrono::TimeDelta, DurationBreakdown),
+        dry_run: bool,
+        style: &DurationArgs,
+    ) {
+        let before_str = before.format_with_items(format.iter()).to_string();
+        let after_str = after.format_with_items(format.iter()).to_string();
+        let (breakdown, delta) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("before : {before_str}");
+                println!("after  : {after_str}");
+                println!("delta  : {delta}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "before": date_json(before, before_str),
+                    "after": date_json(after, after_str),
+                    "delta": duration_json(timedelta, &breakdown, delta),
+                    "dry_run": dry_run,
+                })
+            ),
+        }
+    }
+
+    /// How long the command ran, the json has its exit code too
+    pub fn measure(
+        &self,
+        command: &[String],
+        code: Option<i32>,
+        timedelta: chrono::TimeDelta,
+        breakdown: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let (breakdown, duration) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => println!("{duration}"),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "command": command,
+                    "exit_code": code,
+                    "duration": duration_json(timedelta, &breakdown, duration),
+                })
+            ),
+        }
+    }
+
+    /// How many of the `rounds` are done and how long the work was
+    pub fn pomodoro(
+        &self,
+        done: u32,
+        rounds: u32,
+        timedelta: chrono::TimeDelta,
+        breakdown: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let (breakdown, focused) = style.format(breakdown, false);
+        match self.mode {
+            OutputMode::Text => {
+                println!("rounds  : {done}/{rounds}");
+                println!("focused : {focused}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "done": done,
+                    "rounds": rounds,
+                    "focused": duration_json(timedelta, &breakdown, focused),
+                })
+            ),
+        }
+    }
+
+    /// A column for every zone with the start and end of the slot in it, and the duration
+    pub fn meet(
+        &self,
+        slots: &[timeman::Interval],
+        zones: &[(&str, Zone)],
+        zone: Zone,
+        format: &[chrono::format::Item],
+    ) {
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        let duration = |slot: &timeman::Interval| {
+            DurationBreakdown::new(
+                slot.duration(),
+                TimedeltaFlags::DAY | TimedeltaFlags::HOUR | TimedeltaFlags::MINUTE,
+            )
+            .render(DurationStyle::Compact, Lang::En)
+        };
+        let local = |zone: &Zone, slot: &timeman::Interval| {
+            (
+                zone.from_utc_datetime(&slot.start.naive_utc()),
+                zone.from_utc_datetime(&slot.end.naive_utc()),
+            )
+        };
+        match self.mode {
+            OutputMode::Text => {
+                let mut table = vec![zones
+                    .iter()
+                    .map(|(name, _)| name.to_string())
+                    .chain([String::new()])
+                    .collect::<Vec<_>>()];
+                for slot in slots {
+                    let mut row: Vec<String> = zones
+                        .iter()
+                        .map(|(_, zone)| {
+                            let (start, end) = local(zone, slot);
+                            format!("{} - {}", formatted(&start), formatted(&end))
+                        })
+                        .collect();
+                    row.push(duration(slot));
+                    table.push(row);
+                }
+                print_table(&table);
+            }
+            OutputMode::Json => {
+                for slot in slots {
+                    let zones = zones.iter().map(|(name, zone)| {
+                        let (start, end) = local(zone, slot);
+                        serde_json::json!({
+                            "zone": name,
+                            "start": date_json(&start, formatted(&start)),
+                            "end": date_json(&end, formatted(&end)),
+                        })
+                    });
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "interval": timeman::Interval {
+                                start: zone.from_utc_datetime(&slot.start.naive_utc()),
+                                end: zone.from_utc_datetime(&slot.end.naive_utc()),
+                            }
+                            .to_string(),
+                            "duration": duration(slot),
+                            "seconds": slot.duration().num_seconds(),
+                            "zones": zones.collect::<Vec<_>>(),
+                        })
+                    );
+                }
+            }
+        }
+    }
+
+    /// If the intervals overlap, their intersection and their union, `-` when it is not an interval
+    pub fn overlap(
+        &self,
+        overlaps: bool,
+        intersection: Option<timeman::Interval>,
+        union: Option<timeman::Interval>,
+        format: &[chrono::format::Item],
+    ) {
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        match self.mode {
+            OutputMode::Text => {
+                let interval = |interval: Option<timeman::Interval>| match interval {
+                    Some(interval) => {
+                        format!(
+                            "{}/{}",
+                            formatted(&interval.start),
+                            formatted(&interval.end)
+                        )
+                    }
+                    None => "-".to_owned(),
+                };
+                println!("overlap      : {}", if overlaps { "yes" } else { "no" });
+                println!("intersection : {}", interval(intersection));
+                println!("union        : {}", interval(union));
+            }
+            OutputMode::Json => {
+                let interval = |interval: Option<timeman::Interval>| {
+                    interval.map(|interval| {
+                        serde_json::json!({
+                            "interval": interval.to_string(),
+                            "start": date_json(&interval.start, formatted(&interval.start)),
+                            "end": date_json(&interval.end, formatted(&interval.end)),
+                        })
+                    })
+                };
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "overlap": overlaps,
+                        "intersection": interval(intersection),
+                        "union": interval(union),
+                    })
+                );
+            }
+        }
+    }
+
+    /// A row for every slot with its start and end in `zone` and its duration
+    pub fn slots(
+        &self,
+        slots: &[timeman::Interval],
+        zone: Zone,
+        format: &[chrono::format::Item],
+        style: &DurationArgs,
+        flags: TimedeltaFlags,
+    ) {
+        let rows = slots.iter().map(|slot| {
+            let start = zone.from_utc_datetime(&slot.start.naive_utc());
+            let end = zone.from_utc_datetime(&slot.end.naive_utc());
+            let (timedelta, breakdown) = style.between(start, end, flags);
+            let (breakdown, duration) = style.format(breakdown, false);
+            (start, end, timedelta, breakdown, duration)
+        });
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        match self.mode {
+            OutputMode::Text => print_table(
+                &rows
+                    .map(|(start, end, _, _, duration)| {
+                        vec![formatted(&start), formatted(&end), duration]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => {
+                for (start, end, timedelta, breakdown, duration) in rows {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "start": date_json(&start, formatted(&start)),
+                            "end": date_json(&end, formatted(&end)),
+                            "duration": duration_json(timedelta, &breakdown, duration),
+                        })
+                    );
+                }
+            }
+        }
+    }
+
+    /// A row for every hour or weekday with its count and a bar
+    pub fn groups(&self, by: GroupBy, groups: &[(u32, usize)], locale: Lang, width: usize) {
+        let max = groups
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let name = |group: u32| match by {
+            GroupBy::Hour => format!("{group:02}:00"),
+            GroupBy::Weekday => {
+                let weekday = chrono::Weekday::try_from(group as u8 - 1).unwrap();
+                locale.weekday(weekday, false).to_string()
+            }
+        };
+        match self.mode {
+            OutputMode::Text => print_table(
+                &groups
+                    .iter()
+                    .map(|(group, count)| {
+                        vec![name(*group), count.to_string(), bar(*count, max, width)]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => {
+                for (group, count) in groups {
+                    let json = match by {
+                        GroupBy::Hour => serde_json::json!({ "hour": group, "count": count }),
+                        GroupBy::Weekday => serde_json::json!({
+                            "weekday": name(*group),
+                            "iso_weekday": group,
+                            "count": count,
+                        }),
+                    };
+                    println!("{json}");
+                }
+            }
+        }
+    }
+
+    /// A row for every session with its start, end, duration and number of dates
+    pub fn sessions(
+        &self,
+        sessions: &[timeman::Session],
+        format: &[chrono::format::Item],
+        style: &DurationArgs,
+        flags: TimedeltaFlags,
+    ) {
+        let rows = sessions.iter().map(|session| {
+            let start = session.start.format_with_items(format.iter()).to_string();
+            let end = session.end.format_with_items(format.iter()).to_string();
+            let (timedelta, breakdown) = style.between(session.start, session.end, flags);
+            let (breakdown, duration) = style.format(breakdown, false);
+            (session, start, end, timedelta, breakdown, duration)
+        });
+        match self.mode {
+            OutputMode::Text => print_table(
+                &rows
+                    .map(|(session, start, end, _, _, duration)| {
+                        vec![start, end, duration, session.count.to_string()]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            OutputMode::Json => {
+                for (session, start, end, timedelta, breakdown, duration) in rows {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "start": date_json(&session.start, start),
+                            "end": date_json(&session.end, end),
+                            "duration": duration_json(timedelta, &breakdown, duration),
+                            "count": session.count,
+                        })
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn stats(
+        &self,
+        stats: &timeman::StreamStats,
+        format: &[chrono::format::Item],
+        style: &DurationArgs,
+        flags: TimedeltaFlags,
+    ) {
+        let date = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            let formatted = date.format_with_items(format.iter()).to_string();
+            (date_json(date, formatted.clone()), formatted)
+        };
+        let duration = |timedelta| {
+            let (timedelta, breakdown) = style.timedelta(timedelta, flags);
+            let (breakdown, formatted) = style.format(breakdown, false);
+            (
+                duration_json(timedelta, &breakdown, formatted.clone()),
+                formatted,
+            )
+        };
+
+        let (first, last, span) = (date(&stats.first), date(&stats.last), duration(stats.span));
+        let gaps = stats.gaps.map(|gaps| {
+            [
+                ("min", duration(gaps.min)),
+                ("mean", duration(gaps.mean)),
+                ("median", duration(gaps.median)),
+                ("max", duration(gaps.max)),
+            ]
+        });
+
+        match self.mode {
+            OutputMode::Text => {
+                println!("count      : {}", stats.count);
+                println!("first      : {}", first.1);
+                println!("last       : {}", last.1);
+                println!("span       : {}", span.1);
+                for (name, (_, formatted)) in gaps.into_iter().flatten() {
+                    println!("{:<10} : {formatted}", format!("{name} gap"));
+                }
+            }
+            OutputMode::Json => {
+                let gaps = gaps.map(|gaps| {
+                    gaps.into_iter()
+                        .map(|(name, (json, _))| (name.to_owned(), json))
+                        .collect::<serde_json::Map<_, _>>()
+                });
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "count": stats.count,
+                        "first": first.0,
+                        "last": last.0,
+                        "span": span.0,
+                        "gaps": gaps,
+                    })
+                );
+            }
+        }
+    }
+
+    /// Prints "yes" or "no" and exits with 1 on "no", for the shell conditionals,
+    /// the errors exit with 2 or more so they are not a "no"
+    pub fn answer(&self, yes: bool) {
+        match self.mode {
+            OutputMode::Text => println!("{}", if yes { "yes" } else { "no" }),
+            OutputMode::Json => println!("{}", serde_json::json!({ "result": yes })),
+        }
+        if !yes {
+            std::process::exit(1);
+        }
+    }
+
+    pub fn progress(
+        &self,
+        progress: f64,
+        timedelta: chrono::TimeDelta,
+        left: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let percent = progress * 100.0;
+        let (left, left_formatted) = style.format(left, true);
+        match self.mode {
+            OutputMode::Text => {
+                println!("elapsed : {percent:.2}%");
+                println!("left    : {left_formatted}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "percent": percent,
+                    "left": duration_json(timedelta, &left, left_formatted),
+                })
+            ),
+        }
+    }
+
+    pub fn dst_next(
+        &self,
+        transition: &timeman::Transition,
+        at: &chrono::DateTime<chrono::FixedOffset>,
+        format: &[chrono::format::Item],
+        timedelta: chrono::TimeDelta,
+        until: DurationBreakdown,
+        style: &DurationArgs,
+    ) {
+        let formatted = at.format_with_items(format.iter()).to_string();
+        let (until, until_formatted) = style.format(until, true);
+        match self.mode {
+            OutputMode::Text => {
+                println!("at     : {formatted}");
+                println!("offset : {} -> {}", transition.before, transition.after);
+                println!("in     : {until_formatted}");
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "at": date_json(at, formatted),
+                    "before": transition.before.to_string(),
+                    "after": transition.after.to_string(),
+                    "in": duration_json(timedelta, &until, until_formatted),
+                })
+            ),
+        }
+    }
+
+    /// A column for every zone and a row for every hour from the date,
+    /// with `hours` the first column is the hours from the date
+    #[allow(clippy::type_complexity)]
+    pub fn tz_table(
+        &self,
+        rows: &[(i64, Vec<(&str, chrono::DateTime<chrono::FixedOffset>)>)],
+        format: &[chrono::format::Item],
+        hours: bool,
+    ) {
+        match self.mode {
+            OutputMode::Text => {
+                let mut table = Vec::new();
+                let mut header = Vec::new();
+                if hours {
+                    header.push(String::new());
+                }
+                if let Some((_, dates)) = rows.first() {
+                    header.extend(dates.iter().map(|(zone, _)| zone.to_string()));
+                }
+                table.push(header);
+
+                for (offset, dates) in rows {
+                    let mut row = Vec::new();
+                    if hours {
+                        row.push(format!("{offset:+}h"));
+                    }
+                    row.extend(
+                        dates
+                            .iter()
+                            .map(|(_, date)| date.format_with_items(format.iter()).to_string()),
+                    );
+                    table.push(row);
+                }
+                print_table(&table);
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::Value::from_iter(rows.iter().map(|(offset, dates)| {
+                    serde_json::json!({
+                        "hours": offset,
+                        "dates": serde_json::Value::from_iter(dates.iter().map(|(zone, date)| {
+                            let formatted = date.format_with_items(format.iter()).to_string();
+                            let mut json = date_json(date, formatted);
+                            json["zone"] = serde_json::Value::from(*zone);
+                            json
+                        })),
+                    })
+                }))
+            ),
+        }
+    }
+
+    pub fn tz_info(
+        &self,
+        zone: Zone,
+        now: &chrono::DateTime<chrono::Utc>,
+        transitions: &[timeman::Transition],
+        format: &[chrono::format::Item],
+    ) {
+        let now = now.naive_utc();
+        let offset = zone.offset_from_utc_datetime(&now);
+        let abbreviation = zone.abbreviation(&now);
+        let dst = zone.is_dst(&now);
+        let transitions = transitions.iter().map(|transition| {
+            let at = transition.at.with_timezone(&transition.after);
+            (transition, at.format_with_items(format.iter()).to_string())
+        });
+
+        match self.mode {
+            OutputMode::Text => {
+                println!("zone         : {zone}");
+                println!("offset       : {offset}");
+                if let Some(abbreviation) = &abbreviation {
+                    println!("abbreviation : {abbreviation}");
+                }
+                println!("dst          : {}", if dst { "yes" } else { "no" });
+                for (transition, at) in transitions {
+                    println!(
+                        "transition   : {at} {} -> {}",
+                        transition.before, transition.after
+                    );
+                }
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "zone": zone.to_string(),
+                    "offset": offset.to_string(),
+                    "offset_seconds": offset.local_minus_utc(),
+                    "abbreviation": abbreviation,
+                    "dst": dst,
+                    "transitions": serde_json::Value::from_iter(transitions.map(|(transition, at)| {
+                        serde_json::json!({
+                            "formatted": at,
+                            "epoch": transition.at.timestamp(),
+                            "before": transition.before.to_string(),
+                            "after": transition.after.to_string(),
+                        })
+                    })),
+                })
+            ),
+        }
+    }
+
+    /// One format on every line, the formats that don't give back the sample are marked with `~`
+    pub fn guessed_formats(&self, guessed: &[timeman::GuessedFormat]) {
+        match self.mode {
+            OutputMode::Text => {
+                for guessed in guessed {
+                    let mark = if guessed.round_trip { "" } else { "~ " };
+                    println!("{mark}{}", guessed.format);
+                }
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::Value::from_iter(guessed.iter().map(|guessed| {
+                    serde_json::json!({
+                        "format": guessed.format,
+                        "round_trip": guessed.round_trip,
+                    })
+                }))
+            ),
+        }
+    }
+
+    pub fn days(&self, days: i64) {
+        match self.mode {
+            OutputMode::Text => println!("{days}"),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "formatted": days.to_string(),
+                    "days": days,
+                })
+            ),
+        }
+    }
+
+    /// The date in UTC, TAI and GPS time, the GPS week and its seconds
+    pub fn time_scales(
+        &self,
+        utc: &chrono::DateTime<chrono::FixedOffset>,
+        tai: chrono::NaiveDateTime,
+        gps: chrono::NaiveDateTime,
+        format: &[chrono::format::Item],
+    ) {
+        let formatted = |date: &chrono::DateTime<chrono::FixedOffset>| {
+            date.format_with_items(format.iter()).to_string()
+        };
+        let clock = |clock: chrono::NaiveDateTime| clock.and_utc().fixed_offset();
+        let (tai, gps, tai_utc) = (clock(tai), clock(gps), tai - utc.naive_utc());
+        let (week, time_of_week) = timeman::gps_week(gps.naive_utc());
+        let seconds = time_of_week.num_seconds() as f64 + time_of_week.subsec_nanos() as f64 / 1e9;
+        match self.mode {
+            OutputMode::Text => {
+                println!("utc     : {}", formatted(utc));
+                println!("tai     : {}", formatted(&tai));
+                println!("gps     : {}", formatted(&gps));
+                println!("week    : {week}");
+                println!("seconds : {seconds}");
+                println!("tai-utc : {}", tai_utc.num_seconds());
+            }
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "utc": date_json(utc, formatted(utc)),
+                    "tai": date_json(&tai, formatted(&tai)),
+                    "gps": date_json(&gps, formatted(&gps)),
+                    "gps_week": week,
+                    "gps_seconds": seconds,
+                    "tai_utc": tai_utc.num_seconds(),
+                })
+            ),
+        }
+    }
+
+    /// The Julian Day or with `mjd` the Modified Julian Date, the json has both
+    pub fn julian_day(&self, jd: f64, mjd: bool) {
+        let formatted = if mjd { jd - timeman::MJD_OFFSET } else { jd }.to_string();
+        match self.mode {
+            OutputMode::Text => println!("{formatted}"),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "formatted": formatted,
+                    "jd": jd,
+                    "mjd": jd - timeman::MJD_OFFSET,
+                })
+            ),
+        }
+    }
+
+    pub fn legacy_timestamp(&self, timestamp: &str, epoch: timeman::LegacyEpoch) {
+        match self.mode {
+            OutputMode::Text => println!("{timestamp}"),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "formatted": timestamp,
+                    "timestamp": timestamp.parse::<serde_json::Number>().ok(),
+                    "epoch": epoch.to_possible_value().map(|v| v.get_name().to_owned()),
+                })
+            ),
+        }
+    }
+
+    pub fn timestamp(&self, timestamp: i64, unit: EpochUnit) {
+        match self.mode {
+            OutputMode::Text => println!("{timestamp}"),
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "formatted": timestamp.to_string(),
+                    "timestamp": timestamp,
+                    "unit": unit.to_possible_value().map(|v| v.get_name().to_owned()),
+                })
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RoundUnit {
+    #[default]
+    #[value(alias = "seconds")]
+    Second,
+    #[value(alias = "minutes")]
+    Minute,
+    #[value(alias = "hours")]
+    Hour,
+    #[value(alias = "days")]
+    Day,
+}
+
+impl RoundUnit {
+    pub fn unit(self) -> chrono::TimeDelta {
+        match self {
+            RoundUnit::Second => chrono::TimeDelta::seconds(1),
+            RoundUnit::Minute => chrono::TimeDelta::minutes(1),
+            RoundUnit::Hour => chrono::TimeDelta::hours(1),
+            RoundUnit::Day => chrono::TimeDelta::days(1),
+        }
+    }
+
+    pub fn flag(self) -> TimedeltaFlags {
+        match self {
+            RoundUnit::Second => TimedeltaFlags::SECOND,
+            RoundUnit::Minute => TimedeltaFlags::MINUTE,
+            RoundUnit::Hour => TimedeltaFlags::HOUR,
+            RoundUnit::Day => TimedeltaFlags::DAY,
+        }
+    }
+
+    /// `flags` without the units that are smaller than this
+    pub fn flags(self, flags: TimedeltaFlags) -> TimedeltaFlags {
+        let smaller: &[TimedeltaFlags] = match self {
+            RoundUnit::Second => &[TimedeltaFlags::NANOS],
+            RoundUnit::Minute => &[TimedeltaFlags::SECOND, TimedeltaFlags::NANOS],
+            RoundUnit::Hour => &[
+                TimedeltaFlags::MINUTE,
+                TimedeltaFlags::SECOND,
+                TimedeltaFlags::NANOS,
+            ],
+            RoundUnit::Day => &[
+                TimedeltaFlags::HOUR,
+                TimedeltaFlags::MINUTE,
+                TimedeltaFlags::SECOND,
+                TimedeltaFlags::NANOS,
+            ],
+        };
+        smaller
+            .iter()
+            .fold(flags, |flags, flag| flags.remove(*flag))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EpochUnit {
+    /// seconds
+    S,
+    /// milliseconds
+    Ms,
+    /// microseconds
+    Us,
+    /// nanoseconds
+    Ns,
+}
+
+impl EpochUnit {
+    pub fn to_timestamp(self, date: chrono::DateTime<chrono::FixedOffset>) -> Option<i64> {
+        match self {
+            EpochUnit::S => Some(date.timestamp()),
+            EpochUnit::Ms => Some(date.timestamp_millis()),
+            EpochUnit::Us => Some(date.timestamp_micros()),
+            EpochUnit::Ns => date.timestamp_nanos_opt(),
+        }
+    }
+
+    pub fn from_timestamp(self, timestamp: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            EpochUnit::S => chrono::DateTime::from_timestamp(timestamp, 0),
+            EpochUnit::Ms => chrono::DateTime::from_timestamp_millis(timestamp),
+            EpochUnit::Us => chrono::DateTime::from_timestamp_micros(timestamp),
+            EpochUnit::Ns => Some(chrono::DateTime::from_timestamp_nanos(timestamp)),
+        }
+    }
+}
+
+/// The format when there is no `-f` and no format in the config, same as `date -R`
+pub const DEFAULT_FORMAT: &str = "%a, %d %b %Y %T %z";
+
+/// The leap seconds of `--leap-seconds`, it is not set without it
+static LEAP_SECONDS: std::sync::OnceLock<timeman::LeapSeconds> = std::sync::OnceLock::new();
+
+fn main() {
+    let time_man = TimeMan::parse_from(expand_references(std::env::args_os()));
+
+    let config = match timeman::Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Cannot load the config: {err}");
+            std::process::exit(17)
+        }
+    };
+
+    if time_man.leap_seconds || config.leap_seconds {
+        let leap_seconds = timeman::LeapSeconds::load().unwrap_or_else(|err| {
+            eprintln!("{err}!");
+            std::process::exit(22)
+        });
+        let _ = LEAP_SECONDS.set(leap_seconds);
+    }
+
+    let format_str = time_man
+        .format
+        .or_else(|| config.format.clone())
+        .unwrap_or_else(|| DEFAULT_FORMAT.to_owned());
+    let format_str = resolve_profile(&config, &format_str);
+    let preset = timeman::find_preset(format_str);
+    let format_str = preset.map_or(format_str, |preset| preset.format);
+
+    let mut zone = time_man
+        .utc_offset
+        .or_else(|| config.offset.clone())
+        .map(|offset| parse_zone(&offset))
+        .unwrap_or(Zone::Local);
+    if preset.is_some_and(|preset| preset.utc) {
+        zone = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    }
+
+    let format =
+        if let Ok(format) = chrono::format::strftime::StrftimeItems::new(format_str).parse() {
+            format
+        } else {
+            eprintln!("Invalid format, run command `help-format`");
+            std::process::exit(2);
+        };
+
+    let parser = DateParser {
+        format: &format,
+        format_str,
+        zone,
+        auto: time_man.auto,
+        lenient: preset.is_some(),
+        now: chrono::Utc::now(),
+        stdin_used: Default::default(),
+    };
+
+    let week_start = time_man
+        .week_start
+        .or(config.week_start)
+        .unwrap_or_default();
+
+    let output = Output {
+        mode: time_man.output,
+    };
+
+    match time_man.command {
+        TimeManCommand::Now { mut zones } => {
+            let now = chrono::Utc::now().naive_utc();
+            if zones.is_empty() {
+                zones = config.zones.clone();
+            }
+            if zones.is_empty() {
+                output.date(&zone.from_utc_datetime(&now), &format);
+                return;
+            }
+
+            let dates = zones
+                .iter()
+                .map(|name| (name.as_str(), parse_zone(name).from_utc_datetime(&now)))
+                .collect::<Vec<_>>();
+            output.zoned_dates(&dates, &format);
+        }
+        TimeManCommand::Since {
+            date,
+            mut style,
+            duration_flags,
+            fail_over,
+        } => {
+            style.preety |= config.preety;
+            let (date, duration_flags) = shift_stdin_operand(time_man.stdin, date, duration_flags);
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+            let fail_over = fail_over.map(|duration| parse_duration(&duration, false));
+            let mut failed = false;
+
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+
+                let (timedelta, since) = style.between(date, now, flags);
+                output.breakdown(timedelta, since, &style, false);
+
+                if let Some(fail_over) = &fail_over {
+                    let Some(limit) = fail_over.add_to_zoned(date, zone) else {
+                        return Err(DateError::out_of_range("result"));
+                    };
+                    failed |= now > limit;
+                }
+                Ok(())
+            });
+            if failed {
+                std::process::exit(3)
+            }
+        }
+        TimeManCommand::Until {
+            date,
+            mut style,
+            duration_flags,
+            allow_negative,
+        } => {
+            style.preety |= config.preety;
+            let (date, duration_flags) = shift_stdin_operand(time_man.stdin, date, duration_flags);
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+
+                if date < now && !allow_negative {
+                    return Err(DateError::new(
+                        "`date` is in the past, use `-n` to get a negative duration",
+                        12,
+                    ));
+                }
+
+                let (timedelta, until) = style.between(now, date, flags);
+                output.breakdown(timedelta, until, &style, true);
+                Ok(())
+            });
+        }
+        TimeManCommand::Sub {
+            from_date,
+            date,
+            mut style,
+            duration_flags,
+        } => {
+            style.preety |= config.preety;
+            let from_date = parser.parse(&from_date, "from_date");
+            let date = parser.parse(&date, "date");
+
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let (timedelta, res) = style.between(date, from_date, flags);
+            output.breakdown(timedelta, res, &style, false);
+        }
+        TimeManCommand::SubDuration {
+            from_date,
+            duration,
+            fixed,
+            wall_clock,
+            absolute,
+        } => {
+            let wall_clock = wall_clock || (!absolute && matches!(zone, Zone::Named(_)));
+            let duration = -parse_duration(&duration, fixed);
+
+            for_each_date(time_man.stdin, from_date, "from_date", |from_date| {
+                let from_date = parser.try_parse(from_date, "from_date")?;
+                let res = if wall_clock {
+                    duration.add_to_zoned(from_date, zone)
+                } else {
+                    duration.add_to(from_date)
+                };
+                let Some(res) = res else {
+                    return Err(DateError::out_of_range("result"));
+                };
+                output.date(&res, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::AddDuration {
+            from_date,
+            duration,
+            fixed,
+            wall_clock,
+            absolute,
+        } => {
+            let wall_clock = wall_clock || (!absolute && matches!(zone, Zone::Named(_)));
+            let duration = parse_duration(&duration, fixed);
+
+            for_each_date(time_man.stdin, from_date, "from_date", |from_date| {
+                let from_date = parser.try_parse(from_date, "from_date")?;
+                let res = if wall_clock {
+                    duration.add_to_zoned(from_date, zone)
+                } else {
+                    duration.add_to(from_date)
+                };
+                let Some(res) = res else {
+                    return Err(DateError::out_of_range("result"));
+                };
+                output.date(&res, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::Translate { date, to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+                let format = to_format.as_deref().unwrap_or(&format);
+
+                if let Some(zone) = to_zone {
+                    let t = zone.from_utc_datetime(&date.naive_utc());
+                    output.date(&t, format);
+                    return Ok(());
+                }
+
+                output.date(&date, format);
+                Ok(())
+            });
+        }
+        TimeManCommand::Sort { reverse, to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            let mut dates = read_dates(&parser);
+            dates.sort();
+            if reverse {
+                dates.reverse();
+            }
+            for date in dates {
+                let date = to_zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+                output.date(&date, to_format.as_deref().unwrap_or(&format));
+            }
+        }
+        TimeManCommand::Min { to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            let date = read_dates(&parser).into_iter().min().unwrap();
+            let date = to_zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+            output.date(&date, to_format.as_deref().unwrap_or(&format));
+        }
+        TimeManCommand::Max { to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            let date = read_dates(&parser).into_iter().max().unwrap();
+            let date = to_zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+            output.date(&date, to_format.as_deref().unwrap_or(&format));
+        }
+        TimeManCommand::Rewrite { regex, to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            let to_format = to_format.as_deref().unwrap_or(&format);
+            let regex = regex.map(|regex| {
+                regex::Regex::new(&regex).unwrap_or_else(|err| {
+                    eprintln!("Invalid regex: {err}");
+                    std::process::exit(2)
+                })
+            });
+            let translate = |date: chrono::DateTime<chrono::FixedOffset>| {
+                let date = to_zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+                date.format_with_items(to_format.iter()).to_string()
+            };
+
+            let mut stdout = std::io::stdout().lock();
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    eprintln!("Cannot read from stdin!");
+                    std::process::exit(14)
+                };
+                let line = match &regex {
+                    Some(regex) => {
+                        timeman::rewrite_regex(&line, regex, parser.format, parser.zone, translate)
+                    }
+                    None => timeman::rewrite_dates(&line, parser.format, parser.zone, translate),
+                };
+                if writeln!(stdout, "{line}").is_err() {
+                    return;
+                }
+            }
+        }
+        TimeManCommand::Countdown { date, quiet } => {
+            let date = parser.parse(&date, "date");
+            let left = || date.to_utc() - chrono::Utc::now();
+            if quiet {
+                println!("{}", countdown_str(left()));
+                return;
+            }
+            live_countdown("", left, &std::sync::atomic::AtomicBool::new(false));
+        }
+        TimeManCommand::File {
+            path,
+            since,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let times = timeman::file_times(&path).unwrap_or_else(|err| {
+                eprintln!("Cannot read `{}`: {err}", path.display());
+                std::process::exit(14)
+            });
+            let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+            let times = [
+                ("modified", times.modified),
+                ("accessed", times.accessed),
+                ("changed", times.changed),
+                ("born", times.born),
+            ]
+            .map(|(name, time)| {
+                let time = time.map(|time| {
+                    let time = zone.from_utc_datetime(&time.naive_utc());
+                    (time, since.then(|| style.between(time, now, flags)))
+                });
+                (name, time)
+            });
+            output.file_times(&times, &format, &style);
+        }
+        TimeManCommand::Uptime {
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(
+                duration_flags
+                    .or_else(|| config.duration_flags.clone())
+                    .or_else(|| Some("Dhms".to_owned())),
+            );
+            let boot = timeman::boot_time().unwrap_or_else(|err| {
+                eprintln!("Cannot get the uptime, {err}!");
+                std::process::exit(14)
+            });
+            let boot = zone.from_utc_datetime(&boot.naive_utc());
+            let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+            let (timedelta, breakdown) = style.between(boot, now, flags);
+            output.uptime(&boot, &format, timedelta, breakdown, &style);
+        }
+        TimeManCommand::Ntp {
+            server,
+            timeout,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let timeout = parse_timedelta(&timeout).to_std().unwrap_or_else(|_| {
+                eprintln!("The timeout should be positive!");
+                std::process::exit(10)
+            });
+            let ntp = timeman::query_ntp(&server, timeout).unwrap_or_else(|err| {
+                eprintln!("{err}!");
+                std::process::exit(23)
+            });
+            let time = zone.from_utc_datetime(&ntp.time.naive_utc());
+            let offset = style.timedelta(ntp.offset, flags);
+            let delay = style.timedelta(ntp.delay, flags);
+            output.ntp(&server, &time, &format, ntp.stratum, offset, delay, &style);
+        }
+        #[cfg(feature = "http")]
+        TimeManCommand::HttpDate {
+            url,
+            timeout,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let timeout = parse_timedelta(&timeout).to_std().unwrap_or_else(|_| {
+                eprintln!("The timeout should be positive!");
+                std::process::exit(10)
+            });
+            let http = timeman::query_http_date(&url, timeout).unwrap_or_else(|err| {
+                eprintln!("{err}!");
+                std::process::exit(23)
+            });
+            let date = zone.from_utc_datetime(&http.date.naive_utc());
+            let offset = style.timedelta(http.offset, flags);
+            let delay = style.timedelta(http.delay, flags);
+            output.http_date(&url, &date, &format, offset, delay, &style);
+        }
+        TimeManCommand::SetSystem {
+            date,
+            dry_run,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let date = parser.parse(&date, "date");
+            let now = parser.now;
+            if !dry_run {
+                timeman::set_system_time(date.to_utc()).unwrap_or_else(|err| {
+                    eprintln!("{err}!");
+                    std::process::exit(14)
+                });
+            }
+            let now = zone.from_utc_datetime(&now.naive_utc());
+            let delta = style.between(now, date, flags);
+            output.set_system(&now, &date, &format, delta, dry_run, &style);
+        }
+        TimeManCommand::At { date, command } => {
+            let date = parser.parse(&date, "date");
+            if date.to_utc() < parser.now {
+                eprintln!("The date is in the past!");
+                std::process::exit(2)
+            }
+            sleep_while(|| date.to_utc() - chrono::Utc::now());
+            let status = run_command(&command);
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1))
+            }
+        }
+        TimeManCommand::SleepUntil { date } => {
+            let date = parser.parse(&date, "date");
+            // the wall clock is checked every time so a clock adjustment is seen
+            sleep_while(|| date.to_utc() - chrono::Utc::now());
+        }
+        TimeManCommand::SleepFor { duration } => {
+            let duration = parse_timedelta(&duration);
+            let start = std::time::Instant::now();
+            sleep_while(|| {
+                duration - chrono::TimeDelta::from_std(start.elapsed()).unwrap_or(duration)
+            });
+        }
+        TimeManCommand::Timer {
+            duration,
+            notify,
+            bell,
+            command,
+            quiet,
+        } => {
+            #[cfg(not(feature = "notify"))]
+            if notify.is_some() {
+                eprintln!("`--notify` needs tm to be built with the `notify` feature!");
+                std::process::exit(2)
+            }
+            let duration = parse_timedelta(&duration);
+            let start = std::time::Instant::now();
+            let left =
+                || duration - chrono::TimeDelta::from_std(start.elapsed()).unwrap_or(duration);
+            if quiet {
+                sleep_while(left);
+            } else {
+                live_countdown("", left, &std::sync::atomic::AtomicBool::new(false));
+            }
+
+            alert(
+                bell,
+                notify.is_some(),
+                notify.as_deref().unwrap_or_default(),
+            );
+            if let Some(command) = command {
+                let status = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .status()
+                    .unwrap_or_else(|err| {
+                        eprintln!("Cannot run `{command}`: {err}");
+                        std::process::exit(14)
+                    });
+                if !status.success() {
+                    std::process::exit(status.code().unwrap_or(1))
+                }
+            }
+        }
+        TimeManCommand::Measure {
+            command,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let start = std::time::Instant::now();
+            let status = run_command(&command);
+            let elapsed = chrono::TimeDelta::from_std(start.elapsed()).unwrap_or_else(|_| {
+                eprintln!("The result is out of range!");
+                std::process::exit(13)
+            });
+
+            let (timedelta, breakdown) = style.timedelta(elapsed, flags);
+            output.measure(&command, status.code(), timedelta, breakdown, &style);
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1))
+            }
+        }
+        TimeManCommand::Periodic {
+            every,
+            command,
+            jitter,
+            max_runs,
+        } => {
+            use rand::Rng;
+
+            let every = parse_duration(&every, false);
+            let unit = match every.to_timedelta() {
+                Some(unit) if every.months == 0 && unit > chrono::TimeDelta::zero() => unit,
+                _ => {
+                    eprintln!("The duration should be positive and without years and months!");
+                    std::process::exit(10)
+                }
+            };
+            let jitter = jitter.map_or(0, |jitter| {
+                parse_timedelta(&jitter)
+                    .num_nanoseconds()
+                    .unwrap_or(i64::MAX)
+                    .max(0)
+            });
+            let mut rng = rand::rng();
+
+            let mut last = None;
+            for _ in 0..max_runs.unwrap_or(u64::MAX) {
+                let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+                let next = timeman::round_date(now, unit, timeman::Rounding::Ceil, zone)
+                    .filter(|next| last.is_none_or(|last| *next > last))
+                    .or_else(|| {
+                        timeman::round_date(now + unit, unit, timeman::Rounding::Floor, zone)
+                    });
+                let Some(next) = next else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                last = Some(next);
+
+                let delay = chrono::TimeDelta::nanoseconds(rng.random_range(0..=jitter));
+                sleep_while(|| next.to_utc() + delay - chrono::Utc::now());
+
+                let status = run_command(&command);
+                if !status.success() {
+                    eprintln!("`{}` exited with {status}", command[0])
+                }
+            }
+        }
+        TimeManCommand::Pomodoro {
+            work,
+            pause,
+            rounds,
+            bell,
+            notify,
+            duration_flags,
+            mut style,
+        } => {
+            #[cfg(not(feature = "notify"))]
+            if notify {
+                eprintln!("`--notify` needs tm to be built with the `notify` feature!");
+                std::process::exit(2)
+            }
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| Some("hms".to_owned())));
+            let work_time = parse_timedelta(&work);
+            let pause_time = parse_timedelta(&pause);
+
+            let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            #[cfg(unix)]
+            let _ = signal_hook::flag::register(signal_hook::consts::SIGINT, stop.clone());
+
+            let mut focused = chrono::TimeDelta::zero();
+            let mut done = 0;
+            for round in 1..=rounds {
+                let start = std::time::Instant::now();
+                let elapsed = || chrono::TimeDelta::from_std(start.elapsed()).unwrap_or(work_time);
+                let finished = live_countdown(
+                    &format!("work {round}/{rounds}  "),
+                    || work_time - elapsed(),
+                    &stop,
+                );
+                focused += elapsed().min(work_time);
+                if !finished {
+                    break;
+                }
+                done = round;
+                if round == rounds {
+                    alert(bell, notify, &format!("All the {rounds} rounds are done"));
+                    break;
+                }
+
+                alert(
+                    bell,
+                    notify,
+                    &format!("Round {round} is done, take a {pause} break"),
+                );
+                let start = std::time::Instant::now();
+                let finished = live_countdown(
+                    "break  ",
+                    || {
+                        pause_time
+                            - chrono::TimeDelta::from_std(start.elapsed()).unwrap_or(pause_time)
+                    },
+                    &stop,
+                );
+                if !finished {
+                    break;
+                }
+                alert(
+                    bell,
+                    notify,
+                    &format!("The break is over, start round {}", round + 1),
+                );
+            }
+
+            let (timedelta, breakdown) = style.timedelta(focused, flags);
+            output.pomodoro(done, rounds, timedelta, breakdown, &style);
+        }
+        TimeManCommand::Track { command } => {
+            let (TrackCommand::Start { args, .. }
+            | TrackCommand::Stop { args }
+            | TrackCommand::Status { args }
+            | TrackCommand::Report { args, .. }) = &command;
+            let mut style = args.style.clone();
+            style.preety |= config.preety;
+            let flags = style.flags(
+                args.duration_flags
+                    .clone()
+                    .or_else(|| Some("hms".to_owned())),
+            );
+            let Some(path) = timeman::Config::track_path() else {
+                eprintln!("There is no `$XDG_DATA_HOME` or `$HOME` for the tracked time!");
+                std::process::exit(22)
+            };
+            let mut log: timeman::TimeLog = timeman::load_json(&path).unwrap_or_else(|err| {
+                eprintln!("{err}!");
+                std::process::exit(22)
+            });
+            let save = |log: &timeman::TimeLog| {
+                if let Err(err) = timeman::save_json(&path, log) {
+                    eprintln!("{err}!");
+                    std::process::exit(22)
+                }
+            };
+            let now = parser.now;
+
+            let entry = match command {
+                TrackCommand::Start { project, .. } => {
+                    let stopped = log.start(&project, now).unwrap_or_else(|err| {
+                        eprintln!("Cannot start tracking, {err}!");
+                        std::process::exit(2)
+                    });
+                    save(&log);
+                    stopped
+                }
+                TrackCommand::Stop { .. } => {
+                    let stopped = log.stop(now).cloned().unwrap_or_else(|err| {
+                        eprintln!("Cannot stop tracking, {err}!");
+                        std::process::exit(2)
+                    });
+                    save(&log);
+                    Some(stopped)
+                }
+                TrackCommand::Status { .. } => {
+                    let Some(entry) = log.current() else {
+                        eprintln!("No project is tracked");
+                        std::process::exit(3)
+                    };
+                    Some(entry.clone())
+                }
+                TrackCommand::Report { since, until, .. } => {
+                    let since = since.map(|since| parser.parse(&since, "since").to_utc());
+                    let until = until.map_or(now, |until| parser.parse(&until, "until").to_utc());
+                    let totals = log.totals(since, until);
+                    let total = style.timedelta(totals.values().sum(), flags);
+                    let totals = totals
+                        .into_iter()
+                        .map(|(project, total)| (project, style.timedelta(total, flags)))
+                        .collect::<Vec<_>>();
+                    output.track_report(&totals, total, &style);
+                    return;
+                }
+            };
+            if let Some(entry) = entry {
+                let elapsed = style.timedelta(entry.duration(now), flags);
+                let start = zone.from_utc_datetime(&entry.start.naive_utc());
+                output.track_entry(&entry.project, &start, &format, elapsed, &style);
+            }
+        }
+        TimeManCommand::Stopwatch { command } => {
+            let (StopwatchCommand::Start { args }
+            | StopwatchCommand::Lap { args }
+            | StopwatchCommand::Stop { args }
+            | StopwatchCommand::Show { args }
+            | StopwatchCommand::Reset { args }) = &command;
+            let Some(path) = timeman::Config::stopwatches_path() else {
+                eprintln!("There is no `$XDG_DATA_HOME` or `$HOME` for the stopwatches!");
+                std::process::exit(22)
+            };
+            let mut stopwatches = timeman::load_json::<BTreeMap<String, timeman::Stopwatch>>(&path)
+                .unwrap_or_else(|err| {
+                    eprintln!("{err}!");
+                    std::process::exit(22)
+                });
+
+            let now = parser.now;
+            let changed = match &command {
+                StopwatchCommand::Start { .. } => {
+                    stopwatches.entry(args.name.clone()).or_default().start(now)
+                }
+                StopwatchCommand::Lap { .. } => stopwatch(&mut stopwatches, &args.name).lap(now),
+                StopwatchCommand::Stop { .. } => stopwatch(&mut stopwatches, &args.name).stop(now),
+                StopwatchCommand::Show { .. } => {
+                    stopwatch(&mut stopwatches, &args.name);
+                    Ok(())
+                }
+                StopwatchCommand::Reset { .. } => {
+                    stopwatch(&mut stopwatches, &args.name);
+                    stopwatches.remove(&args.name);
+                    Ok(())
+                }
+            };
+            if let Err(err) = changed {
+                eprintln!("`{}`: {err}!", args.name);
+                std::process::exit(2)
+            }
+            if !matches!(command, StopwatchCommand::Show { .. }) {
+                if let Err(err) = timeman::save_json(&path, &stopwatches) {
+                    eprintln!("{err}!");
+                    std::process::exit(22)
+                }
+            }
+
+            let Some(stopwatch) = stopwatches.get(&args.name) else {
+                return;
+            };
+            let mut style = args.style.clone();
+            style.preety |= config.preety;
+            let flags = style.flags(
+                args.duration_flags
+                    .clone()
+                    .or_else(|| Some("hms".to_owned())),
+            );
+            let elapsed = style.timedelta(stopwatch.elapsed(now), flags);
+            let laps: Vec<_> = stopwatch
+                .splits()
+                .into_iter()
+                .map(|split| style.timedelta(split, flags))
+                .collect();
+            output.stopwatch(&args.name, stopwatch.running(), elapsed, laps, &style);
+        }
+        TimeManCommand::Expr {
+            expression,
+            to,
+            print,
+        } => {
+            let expression = expression.join(" ");
+            let value = timeman::eval(&expression, parser.zone, |date| {
+                timeman::parse_date_auto(parser.format, date, parser.zone)
+                    .map(|(date, _)| date)
+                    .or_else(|_| timeman::parse_relative(date, parser.now, parser.zone))
+                    .ok()
+                    .map(timeman::Value::Date)
+            });
+            let value = value.unwrap_or_else(|err| {
+                eprintln!("{}!", expression_err(err));
+                std::process::exit(2)
+            });
+            let (to_format, to_zone) = to.parse(&config);
+            let format = to_format.as_deref().unwrap_or(&format);
+            if !print_value(value, format, to_zone, &print, &config, &output) {
+                eprintln!("The result is out of range!");
+                std::process::exit(13)
+            }
+        }
+        #[cfg(feature = "pick")]
+        TimeManCommand::Pick { date, locale } => {
+            let start = parser.parse(date.as_deref().unwrap_or("now"), "date");
+            let start = zone.from_utc_datetime(&start.naive_utc()).naive_local();
+            let today = zone.from_utc_datetime(&parser.now.naive_utc()).date_naive();
+            let picked = timeman::pick_date(
+                start.date(),
+                today,
+                week_start,
+                locale.unwrap_or_else(Lang::from_env),
+            );
+            let picked = match picked {
+                Ok(Some(picked)) => picked,
+                Ok(None) => std::process::exit(20),
+                Err(err) => {
+                    eprintln!("Cannot open the calendar: {err}");
+                    std::process::exit(14)
+                }
+            };
+            let Some(date) = zone.resolve_local(&picked.and_time(start.time())) else {
+                eprintln!("The result is out of range!");
+                std::process::exit(13)
+            };
+            output.date(&date, &format);
         }
-        TimeManCommand::Since {
+        TimeManCommand::Cal {
+            month,
+            year,
             date,
-            preety,
-            duration_flags,
+            week_numbers,
+            whole_year,
+            columns,
+            mark,
+            locale,
         } => {
-            let date = parse_date(&format, &time_man.format, &date, "date");
-            let now = offset.from_utc_datetime(&chrono::Utc::now().naive_utc());
-
-            let since = now - date;
-            let buf = timedelta_to_str(
-                since,
-                duration_flags
-                    .map(|str| TimedeltaFlags::new(&str))
-                    .unwrap_or(TimedeltaFlags::all()),
-            );
+            let day = |date: &str, field| {
+                let date = parser.parse(date, field);
+                zone.from_utc_datetime(&date.naive_utc()).date_naive()
+            };
+            let today = day(date.as_deref().unwrap_or("now"), "date");
+            let mut highlighted = vec![today];
+            highlighted.extend(mark.iter().map(|mark| day(mark, "mark")));
 
-            if preety {
-                println!("{}", timedelta_str_to_preety(&buf));
-            } else {
-                println!("{}", buf);
+            let style = timeman::CalendarStyle {
+                week_start,
+                lang: locale.unwrap_or_else(Lang::from_env),
+                week_numbers,
+                highlight: highlight_codes(),
+            };
+            match whole_year {
+                Some(year) => {
+                    let year = year.unwrap_or(today.year());
+                    output.calendar(year, None, columns, &style, &highlighted);
+                }
+                None => {
+                    let month = month.unwrap_or(today.month());
+                    let year = year.unwrap_or(today.year());
+                    output.calendar(year, Some(month), columns, &style, &highlighted);
+                }
             }
         }
-        TimeManCommand::Sub {
-            from_date,
-            date,
-            preety,
-            duration_flags,
+        TimeManCommand::Cron { command } => {
+            let (args, forward) = match command {
+                CronCommand::Next { args } => (args, true),
+                CronCommand::Prev { args } => (args, false),
+                CronCommand::Explain { expression } => {
+                    let explained = parse_cron(&expression).explain();
+                    match output.mode {
+                        OutputMode::Text => println!("{explained}"),
+                        OutputMode::Json => println!(
+                            "{}",
+                            serde_json::json!({ "expression": expression, "explained": explained })
+                        ),
+                    }
+                    return;
+                }
+            };
+            let cron = parse_cron(&args.expression);
+            let from = parser.parse(args.from.as_deref().unwrap_or("now"), "from");
+            let mut found = 0;
+            for date in cron.times(from, zone, forward).take(args.count) {
+                output.date(&date, &format);
+                found += 1;
+            }
+            if found == 0 {
+                eprintln!("The schedule has no times in the next 400 years!");
+                std::process::exit(13)
+            }
+        }
+        TimeManCommand::Oncalendar {
+            command: OnCalendarCommand::Next { args },
         } => {
-            let from_date = parse_date(&format, &time_man.format, &from_date, "from_date");
-            let date = parse_date(&format, &time_man.format, &date, "date");
+            let calendar = parse_calendar(&args.expression);
+            let from = parser.parse(args.from.as_deref().unwrap_or("now"), "from");
+            let elapses: Vec<_> = calendar
+                .times(from, zone)
+                .take(args.count)
+                .map(|date| zone.from_utc_datetime(&date.naive_utc()))
+                .collect();
+            if elapses.is_empty() {
+                eprintln!("The event has no elapses in the next 400 years!");
+                std::process::exit(13)
+            }
+            output.elapses(&args.expression, &calendar, &elapses, parser.now, &format);
+        }
+        TimeManCommand::Ics { command } => {
+            let (mut args, to, count) = match command {
+                IcsCommand::List { args, to } => (args, Some(to), usize::MAX),
+                IcsCommand::Next { args, count } => (args, None, count),
+                IcsCommand::Free {
+                    files,
+                    from,
+                    days,
+                    hours,
+                    min,
+                    calendar,
+                    duration_flags,
+                    mut style,
+                } => {
+                    style.preety |= config.preety;
+                    let flags = style.flags(
+                        duration_flags
+                            .or_else(|| config.duration_flags.clone())
+                            .or_else(|| Some("Dhms".to_owned())),
+                    );
+                    let min = parse_timedelta(&min);
+                    let from = parser.parse(from.as_deref().unwrap_or("now"), "from");
+                    let Some(to) = from.checked_add_days(chrono::Days::new(days.into())) else {
+                        eprintln!("The result is out of range!");
+                        std::process::exit(13)
+                    };
 
-            let res = from_date - date;
-            let buf = timedelta_to_str(
-                res,
-                duration_flags
-                    .map(|str| TimedeltaFlags::new(&str))
-                    .unwrap_or(TimedeltaFlags::all()),
+                    let calendars: Vec<_> = files.iter().map(|file| read_events(file)).collect();
+                    let busy = timeman::merge_busy(calendars.iter().flat_map(|events| {
+                        timeman::occurrences(events, from, zone)
+                            .take_while(|occurrence| occurrence.start < to)
+                            .filter(|occurrence| !occurrence.event.transparent)
+                            .map(|occurrence| timeman::Interval {
+                                start: occurrence.start,
+                                end: occurrence.end,
+                            })
+                    }));
+                    let hours = working_hours(hours, &config);
+                    let windows = hours.windows(from, to, zone, &calendar.calendar());
+                    let free = timeman::free_slots(&windows, &busy, min);
+                    output.slots(&free, zone, &format, &style, flags);
+                    return;
+                }
+            };
+            args.style.preety |= config.preety;
+            // the events are on whole seconds, so the default is without the nanoseconds
+            let flags = args.style.flags(
+                args.duration_flags
+                    .clone()
+                    .or_else(|| config.duration_flags.clone())
+                    .or_else(|| Some("Dhms".to_owned())),
             );
+            let events = read_events(&args.file);
+            let from = parser.parse(args.from.as_deref().unwrap_or("now"), "from");
 
-            if preety {
-                println!("{}", timedelta_str_to_preety(&buf));
-            } else {
-                println!("{}", buf);
+            let found: Vec<_> = match to {
+                Some(to) => {
+                    let to = match to {
+                        Some(to) => parser.parse(&to, "to"),
+                        None => from + chrono::TimeDelta::weeks(1),
+                    };
+                    timeman::occurrences(&events, from, zone)
+                        .take_while(|occurrence| occurrence.start < to)
+                        .collect()
+                }
+                None => timeman::occurrences(&events, from, zone)
+                    .filter(|occurrence| occurrence.start >= from)
+                    .take(count)
+                    .collect(),
+            };
+            output.events(&found, from, zone, &format, &args.style, flags);
+        }
+        TimeManCommand::Interval {
+            command:
+                IntervalCommand::Show {
+                    interval,
+                    duration_flags,
+                    mut style,
+                },
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let interval = parse_interval(&interval, zone);
+            let (timedelta, breakdown) = style.between(interval.start, interval.end, flags);
+            output.interval(&interval, &format, timedelta, breakdown, &style);
+        }
+        TimeManCommand::Interval {
+            command: IntervalCommand::Overlap { a, b },
+        } => {
+            let a = parse_interval(&a, zone);
+            let b = parse_interval(&b, zone);
+            let overlaps = a.overlaps(&b);
+            output.overlap(overlaps, a.intersection(&b), a.union(&b), &format);
+            if !overlaps {
+                std::process::exit(1)
             }
         }
-        TimeManCommand::SubDuration {
-            from_date,
+        TimeManCommand::Meet {
+            mut zones,
+            hours,
             duration,
+            from,
+            days,
+            calendar,
         } => {
-            let from_date = parse_date(&format, &time_man.format, &from_date, "from_date");
-            let Some(duration) = timedelta_from_str(&duration) else {
-                eprintln!("Invalid duration!");
-                std::process::exit(10)
+            if zones.is_empty() {
+                zones = config.zones.clone();
+            }
+            if zones.is_empty() {
+                eprintln!("There are no zones, use `--zones` or `zones` of the config!");
+                std::process::exit(2)
+            }
+            let zones: Vec<_> = zones
+                .iter()
+                .map(|name| (name.as_str(), parse_zone(name)))
+                .collect();
+            let hours = working_hours(hours, &config);
+            let duration = parse_timedelta(&duration);
+            let calendar = calendar.calendar();
+            let from = parser.parse(from.as_deref().unwrap_or("now"), "from");
+            let Some(to) = from.checked_add_days(chrono::Days::new(days.into())) else {
+                eprintln!("The result is out of range!");
+                std::process::exit(13)
             };
 
-            let res = (from_date - duration).format_with_items(format.iter());
-            println!("{res}");
+            let mut slots = vec![timeman::Interval {
+                start: from,
+                end: to,
+            }];
+            for (_, zone) in &zones {
+                let windows = hours.windows(from, to, *zone, &calendar);
+                slots = timeman::intersect(&slots, &windows);
+            }
+            slots.retain(|slot| slot.duration() >= duration);
+            output.meet(&slots, &zones, zone, &format);
         }
-        TimeManCommand::AddDuration {
-            from_date,
-            duration,
-        } => {
-            let from_date = parse_date(&format, &time_man.format, &from_date, "from_date");
-            let Some(duration) = timedelta_from_str(&duration) else {
-                eprintln!("Invalid duration!");
-                std::process::exit(10)
+        TimeManCommand::Rrule { rule, from, count } => {
+            let rrule = parse_rrule(&rule);
+            let from = parser.parse(from.as_deref().unwrap_or("now"), "from");
+            let mut found = 0;
+            for date in rrule.times(from, zone).take(count) {
+                output.date(&date, &format);
+                found += 1;
+            }
+            if found == 0 {
+                eprintln!("The rule has no occurrences after the start!");
+                std::process::exit(13)
+            }
+        }
+        TimeManCommand::Repl { print } => {
+            repl(&config, format_str, zone, &print, &output);
+        }
+        TimeManCommand::Dur { command } => match command {
+            DurCommand::Add { durations, print } => {
+                let sum = durations
+                    .iter()
+                    .map(|duration| parse_timedelta(duration))
+                    .try_fold(chrono::TimeDelta::zero(), |sum, duration| {
+                        sum.checked_add(&duration)
+                    });
+                let Some(sum) = sum else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                print.print(sum, &config, &output);
+            }
+            DurCommand::Sub { durations, print } => {
+                let first = parse_timedelta(&durations[0]);
+                let res = durations[1..]
+                    .iter()
+                    .map(|duration| parse_timedelta(duration))
+                    .try_fold(first, |res, duration| res.checked_sub(&duration));
+                let Some(res) = res else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                print.print(res, &config, &output);
+            }
+            DurCommand::In { unit, duration } => {
+                let Some(value) = timeman::timedelta_in(parse_timedelta(&duration), unit.unit())
+                else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                match output.mode {
+                    OutputMode::Text => println!("{value}"),
+                    OutputMode::Json => println!(
+                        "{}",
+                        serde_json::json!({
+                            "value": value.parse::<f64>().ok(),
+                            "formatted": value,
+                            "unit": unit.to_possible_value().unwrap().get_name(),
+                        })
+                    ),
+                }
+            }
+            DurCommand::Scale {
+                duration,
+                factor,
+                print,
+            } => {
+                let (numerator, denominator) = parse_number(&factor);
+                let Some(res) =
+                    timeman::scale_timedelta(parse_timedelta(&duration), numerator, denominator)
+                else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                print.print(res, &config, &output);
+            }
+            DurCommand::Div {
+                duration,
+                divisor,
+                print,
+            } => {
+                let (numerator, denominator) = parse_number(&divisor);
+                if numerator == 0 {
+                    eprintln!("The divisor should not be 0!");
+                    std::process::exit(2)
+                }
+                let Some(res) =
+                    timeman::scale_timedelta(parse_timedelta(&duration), denominator, numerator)
+                else {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13)
+                };
+                print.print(res, &config, &output);
+            }
+        },
+        TimeManCommand::Unix { unit, command } => match command {
+            UnixCommand::Now => {
+                let now = zone.from_utc_datetime(&chrono::Utc::now().naive_utc());
+                let Some(timestamp) = unit.to_timestamp(now) else {
+                    eprintln!("The timestamp is out of range!");
+                    std::process::exit(13);
+                };
+                output.timestamp(timestamp, unit);
+            }
+            UnixCommand::From { timestamp } => {
+                let Some(date) = unit.from_timestamp(timestamp) else {
+                    eprintln!("The timestamp is out of range!");
+                    std::process::exit(13);
+                };
+                let date = zone.from_utc_datetime(&date.naive_utc());
+                output.date(&date, &format);
+            }
+            UnixCommand::To { date } => {
+                for_each_date(time_man.stdin, date, "date", |date| {
+                    let date = parser.try_parse(date, "date")?;
+                    let Some(timestamp) = unit.to_timestamp(date) else {
+                        return Err(DateError::out_of_range("timestamp"));
+                    };
+                    output.timestamp(timestamp, unit);
+                    Ok(())
+                });
+            }
+        },
+        TimeManCommand::ConvertScale { scale, date } => {
+            let leap_seconds = timeman::LeapSeconds::load().unwrap_or_else(|err| {
+                eprintln!("{err}!");
+                std::process::exit(22)
+            });
+            if let Some(expires) = leap_seconds.expires.filter(|expires| *expires < parser.now) {
+                eprintln!(
+                    "The leap seconds expired on {}, replace them with a newer leap-seconds.list!",
+                    expires.date_naive()
+                );
+            }
+            let gps_tai = chrono::TimeDelta::seconds(timeman::GPS_TAI_OFFSET);
+            let utc = match scale {
+                timeman::TimeScale::Utc => parser.parse(&date, "date").to_utc(),
+                timeman::TimeScale::Tai => {
+                    leap_seconds.from_tai(parser.parse(&date, "date").naive_local())
+                }
+                timeman::TimeScale::Gps => {
+                    let gps = timeman::parse_gps_week(&date)
+                        .unwrap_or_else(|| parser.parse(&date, "date").naive_local());
+                    leap_seconds.from_tai(gps + gps_tai)
+                }
             };
-
-            let res = (from_date + duration).format_with_items(format.iter());
-            println!("{res}");
+            let tai = leap_seconds.to_tai(utc);
+            let utc = zone.from_utc_datetime(&utc.naive_utc());
+            output.time_scales(&utc, tai, tai - gps_tai, &format);
         }
-        TimeManCommand::Translate {
+        TimeManCommand::ConvertEpoch { epoch, command } => match command {
+            ConvertEpochCommand::From { timestamp } => {
+                let Some(date) = epoch.to_date(&timestamp) else {
+                    eprintln!("The timestamp is invalid or out of range!");
+                    std::process::exit(13);
+                };
+                let date = zone.from_utc_datetime(&date.naive_utc());
+                output.date(&date, &format);
+            }
+            ConvertEpochCommand::To { date } => {
+                for_each_date(time_man.stdin, date, "date", |date| {
+                    let date = parser.try_parse(date, "date")?;
+                    let Some(timestamp) = epoch.from_date(date.to_utc()) else {
+                        return Err(DateError::out_of_range("timestamp"));
+                    };
+                    output.legacy_timestamp(&timestamp, epoch);
+                    Ok(())
+                });
+            }
+        },
+        TimeManCommand::Jd { mjd, command } => match command {
+            JdCommand::From { number } => {
+                let jd = if mjd {
+                    number + timeman::MJD_OFFSET
+                } else {
+                    number
+                };
+                let Some(date) = timeman::from_julian_day(jd) else {
+                    eprintln!("The Julian Day is out of range!");
+                    std::process::exit(13);
+                };
+                let date = zone.from_utc_datetime(&date.naive_utc());
+                output.date(&date, &format);
+            }
+            JdCommand::To { date } => {
+                for_each_date(time_man.stdin, date, "date", |date| {
+                    let date = parser.try_parse(date, "date")?;
+                    output.julian_day(timeman::to_julian_day(date.to_utc()), mjd);
+                    Ok(())
+                });
+            }
+        },
+        TimeManCommand::Tz { command } => match command {
+            TzCommand::List { pattern } => {
+                let now = chrono::Utc::now().naive_utc();
+                let zones = timeman::search_zones(pattern.as_deref().unwrap_or_default(), &now);
+                match output.mode {
+                    OutputMode::Text => print_table(
+                        &zones
+                            .iter()
+                            .map(|(name, offset)| vec![name.to_string(), offset.to_string()])
+                            .collect::<Vec<_>>(),
+                    ),
+                    OutputMode::Json => println!(
+                        "{}",
+                        serde_json::Value::from_iter(zones.iter().map(|(name, offset)| {
+                            serde_json::json!({
+                                "zone": name,
+                                "offset": offset.to_string(),
+                                "offset_seconds": offset.local_minus_utc(),
+                            })
+                        }))
+                    ),
+                }
+            }
+        },
+        TimeManCommand::TzTable {
             date,
+            zones,
+            around,
             to_format,
-            offset,
         } => {
-            let date = parse_date(&format, &time_man.format, &date, "date");
-            let mut format = format;
+            let date = parser.parse(&date, "date");
+            let (to_format, _) = parse_to_format(&config, &to_format);
+            let zones = zones
+                .iter()
+                .map(|name| (name.as_str(), parse_zone(name)))
+                .collect::<Vec<_>>();
 
-            if let Some(to_format) = &to_format {
-                let Ok(f) = chrono::format::strftime::StrftimeItems::new(to_format).parse() else {
-                    eprintln!("Invalid to_format, look at `format-help`");
-                    std::process::exit(11);
+            let hours = -(around as i64)..=around as i64;
+            let rows = hours
+                .map(|hours| {
+                    let Some(date) = date.checked_add_signed(chrono::TimeDelta::hours(hours))
+                    else {
+                        eprintln!("The result is out of range!");
+                        std::process::exit(13);
+                    };
+                    let dates = zones
+                        .iter()
+                        .map(|(name, zone)| (*name, zone.from_utc_datetime(&date.naive_utc())))
+                        .collect::<Vec<_>>();
+                    (hours, dates)
+                })
+                .collect::<Vec<_>>();
+            output.tz_table(&rows, &to_format, around > 0);
+        }
+        TimeManCommand::TzInfo {
+            zone: info_zone,
+            transitions,
+        } => {
+            let info_zone = info_zone.map_or(zone, |info_zone| parse_zone(&info_zone));
+            let now = chrono::Utc::now();
+            let transitions = info_zone
+                .transitions(now, 10)
+                .take(transitions)
+                .collect::<Vec<_>>();
+            output.tz_info(info_zone, &now, &transitions, &format);
+        }
+        TimeManCommand::StartOf { period, date } => {
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+                let Some(start) = period.start_of(date, zone, week_start) else {
+                    return Err(DateError::out_of_range("result"));
                 };
-                format = f;
-            }
+                output.date(&start, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::EndOf { period, date } => {
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+                let Some(end) = period.end_of(date, zone, week_start) else {
+                    return Err(DateError::out_of_range("result"));
+                };
+                output.date(&end, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::Progress {
+            period,
+            date,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let date = parser.parse(date.as_deref().unwrap_or("now"), "date");
+            let (Some((_, end)), Some(progress)) = (
+                period.bounds(date, zone, week_start),
+                period.progress(date, zone, week_start),
+            ) else {
+                eprintln!("The result is out of range!");
+                std::process::exit(13);
+            };
+            let (timedelta, left) = style.between(date, end, flags);
+            output.progress(progress, timedelta, left, &style);
+        }
+        TimeManCommand::Round {
+            date,
+            duration,
+            floor,
+            ceil,
+        } => {
+            let duration = parse_duration(&duration, false);
+            let unit = match duration.to_timedelta() {
+                Some(unit) if duration.months == 0 && unit > chrono::TimeDelta::zero() => unit,
+                _ => {
+                    eprintln!("The duration should be positive and without years and months!");
+                    std::process::exit(10)
+                }
+            };
+            let rounding = if floor {
+                timeman::Rounding::Floor
+            } else if ceil {
+                timeman::Rounding::Ceil
+            } else {
+                timeman::Rounding::Nearest
+            };
 
-            if let Some(offset) = offset {
-                let offset = if let Ok(offset) = chrono::FixedOffset::from_str(&offset) {
-                    offset
-                } else {
-                    eprintln!("The offset should look like \"+00:00\"");
-                    std::process::exit(1)
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+                let Some(res) = timeman::round_date(date, unit, rounding, zone) else {
+                    return Err(DateError::out_of_range("result"));
+                };
+                output.date(&res, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::Next { weekday } => weekday.print(true, time_man.stdin, &parser, &output),
+        TimeManCommand::Prev { weekday } => weekday.print(false, time_man.stdin, &parser, &output),
+        TimeManCommand::Nth { expression } => {
+            let expression = (!expression.is_empty()).then(|| expression.join(" "));
+            for_each_date(time_man.stdin, expression, "expression", |expression| {
+                match timeman::parse_nth_weekday(expression, parser.now, zone) {
+                    Ok(date) => {
+                        output.date(&date, &format);
+                        Ok(())
+                    }
+                    Err(timeman::Error::InvalidDate) => Err(DateError::new(
+                        format!("The month of `{expression}` doesn't have that day!"),
+                        7,
+                    )),
+                    Err(err) => Err(DateError::new(
+                        format!("Cannot parse `{expression}`, {err}"),
+                        5,
+                    )),
+                }
+            });
+        }
+        TimeManCommand::BusinessDays {
+            from_date,
+            date,
+            calendar,
+        } => {
+            let calendar = calendar.calendar();
+            let day = |date: &str, field| {
+                let date = parser.parse(date, field);
+                zone.from_utc_datetime(&date.naive_utc()).date_naive()
+            };
+            let days = calendar.count(day(&from_date, "from_date"), day(&date, "date"));
+            output.days(days);
+        }
+        TimeManCommand::AddBusinessDays {
+            from_date,
+            days,
+            calendar,
+        } => {
+            let calendar = calendar.calendar();
+            for_each_date(time_man.stdin, from_date, "from_date", |from_date| {
+                let from_date = parser.try_parse(from_date, "from_date")?;
+                let local = zone.from_utc_datetime(&from_date.naive_utc()).naive_local();
+                let Some(res) = calendar
+                    .add(local.date(), days)
+                    .and_then(|date| zone.resolve_local(&date.and_time(local.time())))
+                else {
+                    return Err(DateError::out_of_range("result"));
                 };
+                output.date(&res, &format);
+                Ok(())
+            });
+        }
+        TimeManCommand::Easter {
+            year,
+            feast,
+            orthodox,
+            all,
+        } => {
+            let year =
+                year.unwrap_or_else(|| zone.from_utc_datetime(&parser.now.naive_utc()).year());
+            let feasts = if all {
+                timeman::Feast::ALL.to_vec()
+            } else {
+                vec![feast]
+            };
 
-                let t = offset.from_utc_datetime(&date.naive_utc());
-                println!("{}", t.format_with_items(format.iter()));
-                return;
+            let dates = feasts
+                .into_iter()
+                .map(|feast| {
+                    let date = feast.date(year, orthodox).and_then(|date| {
+                        zone.resolve_local(&date.and_time(chrono::NaiveTime::MIN))
+                    });
+                    let Some(date) = date else {
+                        eprintln!("The result is out of range!");
+                        std::process::exit(13);
+                    };
+                    (feast.name(), date)
+                })
+                .collect::<Vec<_>>();
+            match dates.as_slice() {
+                [(_, date)] => output.date(date, &format),
+                dates => output.zoned_dates(dates, &format),
             }
-
-            println!("{}", date.format_with_items(format.iter()));
         }
-        TimeManCommand::HelpFormat { get_or_search } => {
-            let mut items = BTreeMap::new();
-
-            items.insert(
-                "%A",
-                r#"Full day of the week names.
-
-Prints a full name in the title case, reads either a short or full name in any case."#,
-            );
-
-            items.insert(
-                "%B",
-                r#"Full month names.
+        TimeManCommand::Fiscal { date, start_month } => {
+            let start = start_month.or(config.fiscal_start).unwrap_or(1);
+            if !(1..=12).contains(&start) {
+                eprintln!("The fiscal year should start in a month from 1 to 12!");
+                std::process::exit(17)
+            }
+            let fiscal = timeman::FiscalYear { start };
 
-Prints a full name in the title case, reads either a short or full name in any case."#,
-            );
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let date = parser.try_parse(date, "date")?;
+                let date = zone.from_utc_datetime(&date.naive_utc()).date_naive();
+                let year = fiscal.year(date);
+                let quarter = fiscal.quarter(date);
+                let (Some((year_start, year_end)), Some((quarter_start, quarter_end))) = (
+                    fiscal.year_bounds(year),
+                    fiscal.quarter_bounds(year, quarter),
+                ) else {
+                    return Err(DateError::out_of_range("result"));
+                };
 
-            items.insert(
-                "%C",
-                r#"Gregorian year divided by 100. Implies the non-negative year."#,
-            );
+                match output.mode {
+                    OutputMode::Text => {
+                        println!("year    : FY{year} {year_start} - {year_end}");
+                        println!("quarter : Q{quarter} {quarter_start} - {quarter_end}");
+                    }
+                    OutputMode::Json => println!(
+                        "{}",
+                        serde_json::json!({
+                            "fiscal_year": year,
+                            "quarter": quarter,
+                            "year_start": year_start.to_string(),
+                            "year_end": year_end.to_string(),
+                            "quarter_start": quarter_start.to_string(),
+                            "quarter_end": quarter_end.to_string(),
+                            "start_month": start,
+                        })
+                    ),
+                }
+                Ok(())
+            });
+        }
+        TimeManCommand::Age { birthdate, at } => {
+            let day = |date: &str, field| {
+                let date = parser.parse(date, field);
+                zone.from_utc_datetime(&date.naive_utc()).date_naive()
+            };
+            let birthdate = day(&birthdate, "birthdate");
+            let today = day(at.as_deref().unwrap_or("now"), "at");
+            let Some(age) = timeman::Age::new(birthdate, today) else {
+                eprintln!("The birthdate is after the date!");
+                std::process::exit(12)
+            };
 
-            items.insert(
-                "%D",
-                r#"Date in format: 4/22/2024
+            match output.mode {
+                OutputMode::Text => {
+                    println!(
+                        "age      : {} years, {} months, {} days",
+                        age.years, age.months, age.days
+                    );
+                    println!(
+                        "birthday : {} in {} days",
+                        age.next_birthday, age.days_until_birthday
+                    );
+                }
+                OutputMode::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "years": age.years,
+                        "months": age.months,
+                        "days": age.days,
+                        "next_birthday": age.next_birthday.to_string(),
+                        "days_until_birthday": age.days_until_birthday,
+                    })
+                ),
+            }
+        }
+        TimeManCommand::Seq { start, end, step } => {
+            let start = parser.parse(&start, "start");
+            let end = parser.parse(&end, "end");
+            let step = parse_duration(&step, false);
+            let step_zone = match zone {
+                Zone::Named(_) => zone,
+                _ => Zone::Fixed(*start.offset()),
+            };
 
-Same as format: "%m/%d/%Y""#,
-            );
-            items.insert(
-                "%F",
-                r#"Date in format: 2024-4-22
+            let Some(dates) = timeman::DateSeq::new(start, end, step, step_zone) else {
+                eprintln!("The step should move the date!");
+                std::process::exit(10)
+            };
+            for date in dates {
+                output.date(&zone.from_utc_datetime(&date.naive_utc()), &format);
+            }
+        }
+        TimeManCommand::Random {
+            start,
+            end,
+            count,
+            seed,
+            sort,
+        } => {
+            use rand::SeedableRng;
 
-Same as format: "%Y-%m-%d""#,
-            );
-            items.insert("%G", r#"IsoYear 2024 or 100BCE"#);
-            items.insert("%H", r#"Hour 0-24"#);
-            items.insert("%I", r#"Hour 0-12 zero pad like: 06"#);
-            items.insert("%M", r#"Minute 0-59"#);
-            items.insert("%P", r#"pm/am"#);
-            items.insert(
-                "%R",
-                r#"Hour 0-24 and minute like: 17:00
+            let start = parser.parse(&start, "start");
+            let end = parser.parse(&end, "end");
+            let mut rng = match seed {
+                Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+                None => rand::rngs::StdRng::from_os_rng(),
+            };
 
-Same as format: "%H:%M""#,
-            );
-            items.insert("%S", r#"Second 0-59 zero pad like: 06"#);
-            items.insert(
-                "%T",
-                r#"Time like: 17:46:05
+            let mut dates = (0..count)
+                .map(|_| {
+                    let Some(date) = timeman::random_date(start, end, &mut rng) else {
+                        eprintln!("The result is out of range!");
+                        std::process::exit(13);
+                    };
+                    date
+                })
+                .collect::<Vec<_>>();
+            if sort {
+                dates.sort();
+            }
+            for date in dates {
+                output.date(&zone.from_utc_datetime(&date.naive_utc()), &format);
+            }
+        }
+        TimeManCommand::Weekday {
+            date,
+            short,
+            locale,
+        } => {
+            let locale = locale.unwrap_or_else(Lang::from_env);
+            for_each_date(time_man.stdin, date, "date", |date| {
+                let weekday = parser.try_parse(date, "date")?.weekday();
+                let name = locale.weekday(weekday, short);
+                let number = weekday.number_from_monday();
+                match output.mode {
+                    OutputMode::Text => println!("{name} {number}"),
+                    OutputMode::Json => println!(
+                        "{}",
+                        serde_json::json!({
+                            "weekday": name,
+                            "iso_weekday": number,
+                        })
+                    ),
+                }
+                Ok(())
+            });
+        }
+        TimeManCommand::Week { date } => {
+            let date = parser.parse(&date, "date").date_naive();
+            let iso = date.iso_week();
+            let week = week_start.week_of_year(date);
+            match output.mode {
+                OutputMode::Text => {
+                    println!(
+                        "iso  : {}-W{:02}-{}",
+                        iso.year(),
+                        iso.week(),
+                        date.weekday().number_from_monday()
+                    );
+                    println!("week : {week}");
+                }
+                OutputMode::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "iso_year": iso.year(),
+                        "iso_week": iso.week(),
+                        "iso_weekday": date.weekday().number_from_monday(),
+                        "week": week,
+                        "week_start": week_start.weekday().to_string(),
+                    })
+                ),
+            }
+        }
+        TimeManCommand::DstNext {
+            zone: dst_zone,
+            duration_flags,
+            mut style,
+        } => {
+            style.preety |= config.preety;
+            let dst_zone = dst_zone.map_or(zone, |dst_zone| parse_zone(&dst_zone));
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let now = chrono::Utc::now();
 
-Same as format: "%H:%M:%S""#,
-            );
-            items.insert("%U", r#"Week of the year like: 16"#);
-            items.insert("%V", r#"ISO Week of the year like: 17"#);
-            items.insert("%Y", r#"Year like: 2024"#);
-            items.insert(
-                "%Z",
-                r#"Time zone name like: +03:00, don't use!
+            let Some(transition) = dst_zone.transitions(now, 10).next() else {
+                eprintln!("`{dst_zone}` has no DST change in the next 10 years");
+                std::process::exit(18);
+            };
+            let at = transition.at.with_timezone(&transition.after);
+            let (timedelta, until) = style.between(now.fixed_offset(), at, flags);
+            output.dst_next(&transition, &at, &format, timedelta, until, &style);
+        }
+        TimeManCommand::OlderThan { path, duration } => {
+            let (modified, limit) = modified_and_limit(&path, &duration, parser.now, zone);
+            output.answer(modified < limit);
+        }
+        TimeManCommand::NewerThan { path, duration } => {
+            let (modified, limit) = modified_and_limit(&path, &duration, parser.now, zone);
+            output.answer(modified > limit);
+        }
+        TimeManCommand::Before { date, other } => {
+            let date = parser.parse(&date, "date");
+            let other = parser.parse(&other, "other");
+            output.answer(date < other);
+        }
+        TimeManCommand::After { date, other } => {
+            let date = parser.parse(&date, "date");
+            let other = parser.parse(&other, "other");
+            output.answer(date > other);
+        }
+        TimeManCommand::Eq { date, other } => {
+            let date = parser.parse(&date, "date");
+            let other = parser.parse(&other, "other");
+            output.answer(date == other);
+        }
+        TimeManCommand::Between {
+            date,
+            start,
+            end,
+            inclusive,
+        } => {
+            let date = parser.parse(&date, "date");
+            let (start, end) = match end {
+                Some(end) => (parser.parse(&start, "start"), parser.parse(&end, "end")),
+                None => {
+                    let interval = parse_interval(&start, zone);
+                    (interval.start, interval.end)
+                }
+            };
+            output.answer(start <= date && (date < end || (inclusive && date == end)));
+        }
+        TimeManCommand::Histogram { bucket, width, to } => {
+            let (to_format, to_zone) = to.parse(&config);
+            let bucket = parse_duration(&bucket, false);
+            let unit = match bucket.to_timedelta() {
+                Some(unit) if bucket.months == 0 && unit > chrono::TimeDelta::zero() => unit,
+                _ => {
+                    eprintln!("The bucket should be positive and without years and months!");
+                    std::process::exit(10)
+                }
+            };
 
-This cannot be parsed use "%:z""#,
-            );
-            items.insert("%a", r#"Short name of the day of the week, is 3 letters"#);
-            items.insert("%b", r#"Short name of month"#);
-            items.insert("%h", r#"Short name of month"#);
-            items.insert("%d", r#"Day of the month zero pad like: 07"#);
-            items.insert("%e", r#"Day of the month space pad like:  7"#);
-            items.insert("%f", r#"Nanoseconds zero pad like: 000000007"#);
-            items.insert("%g", r#"Year mod 100 like: 24"#);
-            items.insert("%j", r#"Day of the year zero pad like: 013"#);
-            items.insert("%k", r#"Hour 24 space pad"#);
-            items.insert("%l", r#"Hour 12 space pad"#);
-            items.insert("%m", r#"Month space pad"#);
-            items.insert("%n", r#"New line like "\n""#);
-            items.insert("%p", r#"AM/PM"#);
-            items.insert("%r", r#"Time like: 07:08:29 PM and fallback to 18:08:29"#);
-            items.insert(
-                "%s",
-                r#"Timestamp
+            let dates = read_dates(&parser);
+            let buckets = match timeman::histogram(&dates, unit, zone) {
+                Ok(buckets) => buckets,
+                Err(err @ timeman::Error::TooManyBuckets) => {
+                    eprintln!("{err}!");
+                    std::process::exit(2)
+                }
+                Err(_) => {
+                    eprintln!("The result is out of range!");
+                    std::process::exit(13);
+                }
+            };
+            let buckets = buckets
+                .into_iter()
+                .map(|(date, count)| {
+                    let date =
+                        to_zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+                    (date, count)
+                })
+                .collect::<Vec<_>>();
+            output.histogram(&buckets, to_format.as_deref().unwrap_or(&format), width);
+        }
+        TimeManCommand::GroupBy { by, width, locale } => {
+            let groups = timeman::group_by(&read_dates(&parser), by, zone, week_start);
+            output.groups(by, &groups, locale.unwrap_or_else(Lang::from_env), width);
+        }
+        TimeManCommand::Stats {
+            duration_flags,
+            mut style,
+            to,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let (to_format, to_zone) = to.parse(&config);
 
-The number of non-leap seconds since the midnight UTC on January 1, 1970.
-For formatting, it assumes UTC upon the absence of time zone offset."#,
-            );
-            items.insert("%t", r#"tab like: \t"#);
-            items.insert(
-                "%u",
-                r#"Day of the week, where Monday = 1 as Sunday = 7 like: 1"#,
+            let dates = read_dates(&parser);
+            let Some(mut stats) = timeman::StreamStats::new(&dates) else {
+                unreachable!("`read_dates` has at least one date");
+            };
+            if let Some(zone) = to_zone {
+                stats.first = zone.from_utc_datetime(&stats.first.naive_utc());
+                stats.last = zone.from_utc_datetime(&stats.last.naive_utc());
+            }
+            output.stats(
+                &stats,
+                to_format.as_deref().unwrap_or(&format),
+                &style,
+                flags,
             );
-            items.insert(
-                "%v",
-                r#"Date like: 22-Apr-2024
+        }
+        TimeManCommand::Sessions {
+            idle,
+            duration_flags,
+            mut style,
+            to,
+        } => {
+            style.preety |= config.preety;
+            let flags = style.flags(duration_flags.or_else(|| config.duration_flags.clone()));
+            let (to_format, to_zone) = to.parse(&config);
+            let duration = parse_duration(&idle, false);
+            let idle = match duration.to_timedelta() {
+                Some(idle) if duration.months == 0 && idle >= chrono::TimeDelta::zero() => idle,
+                _ => {
+                    eprintln!("The idle time should not be negative or have years and months!");
+                    std::process::exit(10)
+                }
+            };
 
-Same as format: "%d-%b-%Y""#,
-            );
-            items.insert(
-                "%w",
-                r#"Day of the week, where Sunday = 0 and Saturday = 6 like: 1"#,
+            let sessions = timeman::sessions(&read_dates(&parser), idle)
+                .into_iter()
+                .map(|mut session| {
+                    if let Some(zone) = to_zone {
+                        session.start = zone.from_utc_datetime(&session.start.naive_utc());
+                        session.end = zone.from_utc_datetime(&session.end.naive_utc());
+                    }
+                    session
+                })
+                .collect::<Vec<_>>();
+            output.sessions(
+                &sessions,
+                to_format.as_deref().unwrap_or(&format),
+                &style,
+                flags,
             );
-            items.insert("%y", r#"Year mod 100"#);
-            items.insert(
-                "%+",
-                r#"Date and time like: 2024-04-22T18:20:29.306665267+03:00
+        }
+        TimeManCommand::IsLeap { year } => {
+            let leap = timeman::is_leap_year(year);
+            match output.mode {
+                OutputMode::Text => println!("{}", if leap { "yes" } else { "no" }),
+                OutputMode::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "year": year,
+                        "leap": leap,
+                    })
+                ),
+            }
+            if !leap {
+                std::process::exit(1);
+            }
+        }
+        TimeManCommand::DaysIn { command } => {
+            let days = match command {
+                DaysInCommand::Month {
+                    month: (year, month),
+                } => timeman::days_in_month(year, month).unwrap_or_default(),
+                DaysInCommand::Year { year } => timeman::days_in_year(year),
+            };
+            output.days(days as i64);
+        }
+        TimeManCommand::IsDst {
+            date,
+            zone: dst_zone,
+        } => {
+            let date = parser.parse(&date, "date");
+            let dst_zone = dst_zone.map_or(zone, |dst_zone| parse_zone(&dst_zone));
+            let dst = dst_zone.is_dst(&date.naive_utc());
+            match output.mode {
+                OutputMode::Text => println!("{}", if dst { "yes" } else { "no" }),
+                OutputMode::Json => {
+                    let offset = dst_zone.offset_from_utc_datetime(&date.naive_utc());
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "dst": dst,
+                            "zone": dst_zone.to_string(),
+                            "offset": offset.to_string(),
+                            "offset_seconds": offset.local_minus_utc(),
+                        })
+                    );
+                }
+            }
+            if !dst {
+                std::process::exit(1);
+            }
+        }
+        TimeManCommand::GuessFormat { sample } => {
+            let guessed = timeman::guess_format(&sample);
+            if guessed.is_empty() {
+                eprintln!("Cannot find a format for the sample, look at `help-format`");
+                std::process::exit(16);
+            }
+            output.guessed_formats(&guessed);
+        }
+        TimeManCommand::Completions { shell } => {
+            clap_complete::generate(shell, &mut TimeMan::command(), "tm", &mut std::io::stdout());
+        }
+        TimeManCommand::Man { dir } => {
+            let mut command = TimeMan::command().name("tm").bin_name("tm");
+            command.build();
 
-Is from RFC3339
-Same as format: "%FT%T%.9f%:z""#,
-            );
-            items.insert("%:z", r#"Timezone offset like: +03:00"#);
-            items.insert("%::z", r#"Timezone offset like: +03:00:00"#);
-            items.insert("%:::z", r#"Timezone offset like: +03"#);
-            items.insert("%.3f", r#"Nanoseconds 3 digits like: .467"#);
-            items.insert("%.6f", r#"Nanoseconds 6 digits like: .467312"#);
-            items.insert("%.9f", r#"Nanoseconds 9 digits like: .432467312"#);
-            items.insert("%3f", r#"Nanoseconds 3 digits like: 467"#);
-            items.insert("%6f", r#"Nanoseconds 6 digits like: 467312"#);
-            items.insert("%9f", r#"Nanoseconds 9 digits like: 432467312"#);
-            items.insert("%%", r#"% like: %"#);
+            let result = if let Some(dir) = dir {
+                write_man_pages(&command, &dir)
+            } else {
+                render_man_page(&command, &mut std::io::stdout())
+            };
+            if let Err(err) = result {
+                eprintln!("Cannot write the man page: {err}");
+                std::process::exit(15)
+            }
+        }
+        TimeManCommand::HelpFormat { get_or_search } => {
+            let items = format_items();
 
             if let Some(get_or_search) = get_or_search {
                 let get_or_search = get_or_search.trim();
-                if let Some(item) = items.get(get_or_search) {
+                if let Some(preset) = timeman::find_preset(get_or_search) {
+                    println!(
+                        "{get_or_search} : {}\n\nIs a preset, same as format: \"{}\"",
+                        preset.description, preset.format
+                    );
+                } else if let Some(item) = items.get(get_or_search) {
                     println!("{get_or_search} : {item}");
                 } else {
                     let items = items
@@ -368,11 +4755,141 @@ Same as format: "%FT%T%.9f%:z""#,
             }
         }
         TimeManCommand::HelpDuration => {
-            println!(
-                r#"This is only for the content of the duration
+            println!("{DURATION_HELP}");
+        }
+    }
+}
+
+/// Every format item and preset with its description, the first line is the summary
+pub fn format_items() -> BTreeMap<&'static str, &'static str> {
+    let mut items = BTreeMap::new();
+
+    items.insert(
+        "%A",
+        r#"Full day of the week names.
+
+Prints a full name in the title case, reads either a short or full name in any case."#,
+    );
+
+    items.insert(
+        "%B",
+        r#"Full month names.
+
+Prints a full name in the title case, reads either a short or full name in any case."#,
+    );
+
+    items.insert(
+        "%C",
+        r#"Gregorian year divided by 100. Implies the non-negative year."#,
+    );
+
+    items.insert(
+        "%D",
+        r#"Date in format: 4/22/2024
+
+Same as format: "%m/%d/%Y""#,
+    );
+    items.insert(
+        "%F",
+        r#"Date in format: 2024-4-22
+
+Same as format: "%Y-%m-%d""#,
+    );
+    items.insert("%G", r#"IsoYear 2024 or 100BCE"#);
+    items.insert("%H", r#"Hour 0-24"#);
+    items.insert("%I", r#"Hour 0-12 zero pad like: 06"#);
+    items.insert("%M", r#"Minute 0-59"#);
+    items.insert("%P", r#"pm/am"#);
+    items.insert(
+        "%R",
+        r#"Hour 0-24 and minute like: 17:00
+
+Same as format: "%H:%M""#,
+    );
+    items.insert("%S", r#"Second 0-59 zero pad like: 06"#);
+    items.insert(
+        "%T",
+        r#"Time like: 17:46:05
+
+Same as format: "%H:%M:%S""#,
+    );
+    items.insert("%U", r#"Week of the year like: 16"#);
+    items.insert("%V", r#"ISO Week of the year like: 17"#);
+    items.insert("%Y", r#"Year like: 2024"#);
+    items.insert(
+        "%Z",
+        r#"Time zone name like: +03:00, don't use!
+
+This cannot be parsed use "%:z""#,
+    );
+    items.insert("%a", r#"Short name of the day of the week, is 3 letters"#);
+    items.insert("%b", r#"Short name of month"#);
+    items.insert("%h", r#"Short name of month"#);
+    items.insert("%d", r#"Day of the month zero pad like: 07"#);
+    items.insert("%e", r#"Day of the month space pad like:  7"#);
+    items.insert("%f", r#"Nanoseconds zero pad like: 000000007"#);
+    items.insert("%g", r#"Year mod 100 like: 24"#);
+    items.insert("%j", r#"Day of the year zero pad like: 013"#);
+    items.insert("%k", r#"Hour 24 space pad"#);
+    items.insert("%l", r#"Hour 12 space pad"#);
+    items.insert("%m", r#"Month space pad"#);
+    items.insert("%n", r#"New line like "\n""#);
+    items.insert("%p", r#"AM/PM"#);
+    items.insert("%r", r#"Time like: 07:08:29 PM and fallback to 18:08:29"#);
+    items.insert(
+        "%s",
+        r#"Timestamp
+
+The number of non-leap seconds since the midnight UTC on January 1, 1970.
+For formatting, it assumes UTC upon the absence of time zone offset."#,
+    );
+    items.insert("%t", r#"tab like: \t"#);
+    items.insert(
+        "%u",
+        r#"Day of the week, where Monday = 1 as Sunday = 7 like: 1"#,
+    );
+    items.insert(
+        "%v",
+        r#"Date like: 22-Apr-2024
+
+Same as format: "%d-%b-%Y""#,
+    );
+    items.insert(
+        "%w",
+        r#"Day of the week, where Sunday = 0 and Saturday = 6 like: 1"#,
+    );
+    items.insert("%y", r#"Year mod 100"#);
+    items.insert(
+        "%+",
+        r#"Date and time like: 2024-04-22T18:20:29.306665267+03:00
+
+Is from RFC3339
+Same as format: "%FT%T%.9f%:z""#,
+    );
+    items.insert("%:z", r#"Timezone offset like: +03:00"#);
+    items.insert("%::z", r#"Timezone offset like: +03:00:00"#);
+    items.insert("%:::z", r#"Timezone offset like: +03"#);
+    items.insert("%.3f", r#"Nanoseconds 3 digits like: .467"#);
+    items.insert("%.6f", r#"Nanoseconds 6 digits like: .467312"#);
+    items.insert("%.9f", r#"Nanoseconds 9 digits like: .432467312"#);
+    items.insert("%3f", r#"Nanoseconds 3 digits like: 467"#);
+    items.insert("%6f", r#"Nanoseconds 6 digits like: 467312"#);
+    items.insert("%9f", r#"Nanoseconds 9 digits like: 432467312"#);
+    items.insert("%%", r#"% like: %"#);
+
+    for preset in timeman::FORMAT_PRESETS {
+        items.insert(preset.name, preset.description);
+    }
+
+    items
+}
+
+/// The text of `help-duration`
+pub const DURATION_HELP: &str = r#"This is only for the content of the duration
 Valid flags are:
 Y : Year
 M : Month
+W : Week
 D : Day
 h : Hour
 m : minute
@@ -380,351 +4897,1119 @@ s : second
 n : nanosecond
 
 They are used like:
-"YMDhmsn" this means that everything is included in duration
+"YMDhmsn" this means that everything is included in duration, same as "all"
 "sn" this means only the seconds and nanoseconds are included but everything is stored in seconds and nanoseconds
+"D..s" this means every unit from the days to the seconds, like "Dhms"
+"all-n" this means every unit but the nanoseconds, after the "-" are the flags that are left out
 
-The recommended duration flags are "sn"
-                "#
-            );
+A duration is ISO 8601 like "P1Y2M3DT4H5M6S", "P2W" or "-PT1.5H", the units are in that order,
+the weeks are alone and only the last unit can have a fraction like "P0.5Y" or "PT1,5H"
+
+The years and months of `since`, `until` and `sub` are walked in the calendar,
+so they have the real length, in a duration a year is 365 days and a month is a twelfth of it
+
+`add-duration` and `sub-duration` move the years and months in the calendar,
+when the day doesn't exist in the new month it is the last day of the month
+so "2024-01-31" + "P1M" is "2024-02-29", use `--fixed` to not use the calendar,
+with `--wall-clock` the days are moved in the wall clock of `-o` so with DST a day can be 23 or 25 hours,
+this is the default when `-o` is a time zone name, use `--absolute` for days of 24 hours
+
+With `--dur-format` the duration is printed with a template like "{h:02}:{m:02}:{s:02}" or "{D}d {h}h",
+the units are the same letters as the flags and the duration is split only in to the units of the template,
+"{h:2}" is padded with spaces, "{h:02}" with zeros, "{{" and "}}" are "{" and "}"
+
+The recommended duration flags are "sn""#;
+
+/// The page of `command` with the format and duration help at the end
+pub fn render_man_page(command: &clap::Command, w: &mut dyn Write) -> std::io::Result<()> {
+    use clap_mangen::roff::{bold, Roff};
+
+    clap_mangen::Man::new(command.clone()).render(w)?;
+
+    let mut roff = Roff::default();
+    roff.control("SH", ["FORMAT"]);
+    for (item, description) in format_items() {
+        roff.control("TP", []);
+        roff.text([bold(item)]);
+        man_paragraphs(&mut roff, description);
+    }
+    roff.control("SH", ["DURATION"]);
+    man_paragraphs(&mut roff, DURATION_HELP);
+    roff.to_writer(w)?;
+
+    Ok(())
+}
+
+/// Writes `tm.1` and `tm-<subcommand>.1` for every subcommand in `dir`, the nested ones
+/// like `tm-dur-add.1` too
+pub fn write_man_pages(command: &clap::Command, dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let mut file = std::fs::File::create(dir.join("tm.1"))?;
+    render_man_page(command, &mut file)?;
+    write_subcommand_pages(command, dir)
+}
+
+fn write_subcommand_pages(command: &clap::Command, dir: &std::path::Path) -> std::io::Result<()> {
+    for subcommand in command
+        .get_subcommands()
+        .filter(|s| !s.is_hide_set() && s.get_name() != "help")
+    {
+        clap_mangen::Man::new(subcommand.clone()).generate_to(dir)?;
+        write_subcommand_pages(subcommand, dir)?;
+    }
+
+    Ok(())
+}
+
+/// Every empty line starts a new paragraph, the other lines are kept
+fn man_paragraphs(roff: &mut clap_mangen::roff::Roff, text: &str) {
+    for (i, paragraph) in text.split("\n\n").enumerate() {
+        if i > 0 {
+            roff.control("PP", []);
         }
+        let mut inlines = Vec::new();
+        for line in paragraph.lines() {
+            if !inlines.is_empty() {
+                inlines.push(clap_mangen::roff::line_break());
+            }
+            inlines.push(clap_mangen::roff::roman(line));
+        }
+        roff.text(inlines);
     }
 }
 
-pub fn parse_date(
-    format: &[chrono::format::Item],
-    format_str: &str,
-    date: &str,
-    field: &str,
-) -> chrono::DateTime<chrono::FixedOffset> {
-    let mut parsed = chrono::format::Parsed::new();
-    let Ok(_) = chrono::format::parse(&mut parsed, date, format.iter()) else {
-        eprintln!("Cannot parse `{field}` the date should be in this format: `{format_str}` ");
-        std::process::exit(5)
-    };
-    let Ok(offset) = parsed.to_fixed_offset() else {
-        eprintln!("Cannot parse the timeoffset for `{field}` or you don't have a format with `%:z` in it!");
-        std::process::exit(6)
-    };
+/// The json of a date with the formatted date and the structured fields
+pub fn date_json(
+    date: &chrono::DateTime<chrono::FixedOffset>,
+    formatted: String,
+) -> serde_json::Value {
+    let offset = date.offset();
+    serde_json::json!({
+        "formatted": formatted,
+        "epoch": date.timestamp(),
+        "nanoseconds": date.timestamp_subsec_nanos(),
+        "offset": offset.to_string(),
+        "offset_seconds": offset.local_minus_utc(),
+    })
+}
 
-    let Ok(time) = parsed.to_naive_datetime_with_offset(0) else {
-        eprintln!("`{field}` has a invalid date!");
-        std::process::exit(7);
-    };
+/// The json of a duration with the formatted duration and the structured fields
+pub fn duration_json(
+    timedelta: chrono::TimeDelta,
+    breakdown: &DurationBreakdown,
+    formatted: String,
+) -> serde_json::Value {
+    let components = breakdown
+        .components()
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), serde_json::Value::from(value)))
+        .collect::<serde_json::Map<_, _>>();
+    serde_json::json!({
+        "formatted": formatted,
+        "iso": breakdown.to_string(),
+        "negative": breakdown.negative,
+        "seconds": timedelta.num_seconds(),
+        "nanoseconds": timedelta.subsec_nanos(),
+        "components": components,
+    })
+}
+
+/// A bar of `#` that is `width` long for `max`, a count that is not 0 has at least one
+pub fn bar(count: usize, max: usize, width: usize) -> String {
+    "#".repeat((count * width).div_ceil(max))
+}
+
+/// Prints the rows with every column padded to its widest cell
+pub fn print_table(rows: &[Vec<String>]) {
+    let mut widths = Vec::<usize>::new();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(i) {
+                Some(max) => *max = (*max).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
 
-    let chrono::LocalResult::Single(time) = offset.from_local_datetime(&time) else {
-        eprintln!("`{field}` has a invalid date or ambiguous time!");
-        std::process::exit(8);
+    for row in rows {
+        let mut line = String::new();
+        for (i, cell) in row.iter().enumerate() {
+            if i > 0 {
+                line.push_str("  ");
+            }
+            line.push_str(cell);
+            if i + 1 < row.len() {
+                for _ in cell.chars().count()..widths[i] {
+                    line.push(' ');
+                }
+            }
+        }
+        println!("{line}");
+    }
+}
+
+/// Runs the program that is the first of the `command` with the rest as its arguments,
+/// exits when it cannot be started
+pub fn run_command(command: &[String]) -> std::process::ExitStatus {
+    std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .status()
+        .unwrap_or_else(|err| {
+            eprintln!("Cannot run `{}`: {err}", command[0]);
+            std::process::exit(14)
+        })
+}
+
+/// When the file was modified and the duration before `now`, exits when they cannot be found
+pub fn modified_and_limit(
+    path: &std::path::Path,
+    duration: &str,
+    now: chrono::DateTime<chrono::Utc>,
+    zone: Zone,
+) -> (
+    chrono::DateTime<chrono::Utc>,
+    chrono::DateTime<chrono::FixedOffset>,
+) {
+    let modified = timeman::file_mtime(path).unwrap_or_else(|err| {
+        eprintln!("Cannot read when `{}` was modified: {err}", path.display());
+        std::process::exit(14)
+    });
+    let now = zone.from_utc_datetime(&now.naive_utc());
+    let Some(limit) = (-parse_duration(duration, false)).add_to_zoned(now, zone) else {
+        eprintln!("The result is out of range!");
+        std::process::exit(13)
     };
+    (modified, limit)
+}
+
+/// Exits when there is no stopwatch with the `name`
+pub fn stopwatch<'a>(
+    stopwatches: &'a mut BTreeMap<String, timeman::Stopwatch>,
+    name: &str,
+) -> &'a mut timeman::Stopwatch {
+    stopwatches.get_mut(name).unwrap_or_else(|| {
+        eprintln!("There is no stopwatch `{name}`, use `stopwatch start {name}`!");
+        std::process::exit(2)
+    })
+}
 
-    time
+/// Like "0d 00h 24m 59s", rounded up so the zero is shown only when nothing is left
+pub fn countdown_str(left: chrono::TimeDelta) -> String {
+    let left = left.max(chrono::TimeDelta::zero());
+    let shown = left.num_seconds() + (left.subsec_nanos() > 0) as i64;
+    let breakdown = DurationBreakdown::new(
+        chrono::TimeDelta::seconds(shown),
+        TimedeltaFlags::DAY
+            | TimedeltaFlags::HOUR
+            | TimedeltaFlags::MINUTE
+            | TimedeltaFlags::SECOND,
+    );
+    format!(
+        "{}d {:02}h {:02}m {:02}s",
+        breakdown.days, breakdown.hours, breakdown.minutes, breakdown.seconds
+    )
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TimedeltaFlags(u8);
+/// Prints the time that is `left` after the `label` over the same line every second,
+/// returns false when it is stopped before nothing is left
+pub fn live_countdown(
+    label: &str,
+    left: impl Fn() -> chrono::TimeDelta,
+    stop: &std::sync::atomic::AtomicBool,
+) -> bool {
+    let mut stdout = std::io::stdout();
+    loop {
+        let left = left().max(chrono::TimeDelta::zero());
+        // the spaces clear what is left from a longer line
+        _ = write!(stdout, "\r{label}{}  ", countdown_str(left));
+        _ = stdout.flush();
+
+        if left.is_zero() {
+            println!();
+            return true;
+        }
 
-impl core::ops::BitOr<Self> for TimedeltaFlags {
-    type Output = Self;
+        let nanos = left.subsec_nanos();
+        let sleep = if nanos == 0 { 1_000_000_000 } else { nanos };
+        std::thread::sleep(std::time::Duration::from_nanos(sleep as u64));
 
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self(self.0 | rhs.0)
+        if stop.load(std::sync::atomic::Ordering::Relaxed) {
+            println!();
+            return false;
+        }
     }
 }
 
-impl Default for TimedeltaFlags {
-    fn default() -> Self {
-        Self::SECOND | Self::NANOS
+/// Rings the terminal bell and shows a desktop notification with the `message`
+#[cfg_attr(not(feature = "notify"), allow(unused_variables))]
+pub fn alert(bell: bool, notify: bool, message: &str) {
+    if bell {
+        let mut stdout = std::io::stdout();
+        _ = write!(stdout, "\x07");
+        _ = stdout.flush();
+    }
+    #[cfg(feature = "notify")]
+    if notify {
+        let shown = notify_rust::Notification::new()
+            .summary("tm")
+            .body(message)
+            .show();
+        if let Err(err) = shown {
+            eprintln!("Cannot show the notification: {err}");
+        }
     }
 }
 
-impl TimedeltaFlags {
-    const YEAR: Self = Self(1 << 0);
-    const MONTH: Self = Self(1 << 1);
-    const WEEK: Self = Self(1 << 2);
-    const DAY: Self = Self(1 << 3);
-    const HOUR: Self = Self(1 << 4);
-    const MINUTE: Self = Self(1 << 5);
-    const SECOND: Self = Self(1 << 6);
-    const NANOS: Self = Self(1 << 7);
+/// Sleeps in steps of at most a second until nothing is `left`,
+/// on SIGUSR1 or SIGINFO the time that is left is printed to stderr
+pub fn sleep_while(mut left: impl FnMut() -> chrono::TimeDelta) {
+    let report = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        let _ = signal_hook::flag::register(signal_hook::consts::SIGUSR1, report.clone());
+        #[cfg(any(
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "macos"
+        ))]
+        let _ = signal_hook::flag::register(signal_hook::consts::SIGINFO, report.clone());
+    }
+
+    loop {
+        let left = left();
+        if left <= chrono::TimeDelta::zero() {
+            return;
+        }
+
+        if report.swap(false, std::sync::atomic::Ordering::Relaxed) {
+            eprintln!("{}", timedelta_to_str(left, TimedeltaFlags::all()));
+        }
 
-    pub fn empty() -> Self {
-        Self(0)
+        let step = left
+            .to_std()
+            .unwrap_or_default()
+            .min(std::time::Duration::from_secs(1));
+        std::thread::sleep(step);
     }
+}
 
-    pub fn all() -> Self {
-        Self::YEAR
-            | Self::MONTH
-            | Self::WEEK
-            | Self::DAY
-            | Self::HOUR
-            | Self::MINUTE
-            | Self::SECOND
-            | Self::NANOS
+/// Calls `f` with the `operand`, or with every line of stdin when `stdin` is true,
+/// the error of a line is printed with its number and the next line is read
+pub fn for_each_date(
+    stdin: bool,
+    operand: Option<String>,
+    field: &str,
+    mut f: impl FnMut(&str) -> Result<(), DateError>,
+) {
+    if !stdin {
+        let Some(operand) = operand else {
+            eprintln!("`{field}` is required, or use `--stdin` to read it from stdin");
+            std::process::exit(2)
+        };
+        f(&operand).unwrap_or_else(|err| err.exit());
+        return;
     }
 
-    pub fn contains(self, rhs: Self) -> bool {
-        self.0 & rhs.0 == rhs.0
+    if operand.is_some() {
+        eprintln!("`{field}` is read from stdin, don't give it as an argument with `--stdin`");
+        std::process::exit(2)
     }
 
-    pub fn new(str: &str) -> Self {
-        let mut out = Self::empty();
-        for char in str.chars() {
-            match char {
-                'Y' => out = out | Self::YEAR,
-                'M' => out = out | Self::MONTH,
-                'D' => out = out | Self::DAY,
-                'h' => out = out | Self::HOUR,
-                'm' => out = out | Self::MINUTE,
-                's' => out = out | Self::SECOND,
-                'n' => out = out | Self::NANOS,
-                _ => {}
-            }
+    // a bad line is skipped, the code of the first one is the exit code at the end
+    let mut failed = None;
+    for (i, line) in std::io::stdin().lock().lines().enumerate() {
+        let Ok(line) = line else {
+            eprintln!("Cannot read from stdin!");
+            std::process::exit(14)
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
         }
-        out
+        if let Err(err) = f(line) {
+            eprintln!("line {}: {}", i + 1, err.message);
+            failed.get_or_insert(err.code);
+        }
+    }
+    if let Some(code) = failed {
+        std::process::exit(code)
     }
 }
 
-const YEAR_IN_SECONDS: i64 = 31_536_000;
-const MONTH_IN_SECONDS: i64 = YEAR_IN_SECONDS / 12;
-const WEAK_IN_SECONDS: i64 = 604800;
-const DAY_IN_SECONDS: i64 = 86400;
-const HOUR_IN_SECONDS: i64 = 3600;
-const MINUTE_IN_SECONDS: i64 = 60;
+/// Every date from stdin, one on a line, exits when there is none
+pub fn read_dates(parser: &DateParser) -> Vec<chrono::DateTime<chrono::FixedOffset>> {
+    let mut dates = Vec::new();
+    for_each_date(true, None, "date", |date| {
+        dates.push(parser.try_parse(date, "date")?);
+        Ok(())
+    });
+    if dates.is_empty() {
+        eprintln!("There are no dates in stdin!");
+        std::process::exit(2)
+    }
+    dates
+}
 
-fn timedelta_to_str(timedelta: chrono::TimeDelta, flags: TimedeltaFlags) -> String {
-    let mut out = String::default();
-    if timedelta.num_seconds().is_negative() {
-        out.push('-');
+/// With `--stdin` the date is left out, so the first argument is the next one
+pub fn shift_stdin_operand(
+    stdin: bool,
+    operand: Option<String>,
+    next: Option<String>,
+) -> (Option<String>, Option<String>) {
+    if stdin && next.is_none() {
+        (None, operand)
+    } else {
+        (operand, next)
     }
-    out.push('P');
+}
 
-    let mut seconds = unsafe { (&timedelta as *const _ as *const i64).offset(0).read() }.abs();
+/// Like `timedelta_from_str`, exits when the duration is invalid
+pub fn parse_timedelta(duration: &str) -> chrono::TimeDelta {
+    timedelta_from_str(duration).unwrap_or_else(|err| invalid_duration(err))
+}
 
-    if flags.contains(TimedeltaFlags::YEAR) {
-        let years = seconds / YEAR_IN_SECONDS;
-        if years > 0 {
-            seconds -= years * YEAR_IN_SECONDS;
-            out.write_fmt(format_args!("{years}Y")).unwrap();
-        }
+/// Prints why the duration is invalid and exits
+pub fn invalid_duration(err: timeman::Error) -> ! {
+    match err {
+        timeman::Error::MalformedDuration(err) => eprintln!("Invalid duration {err}!"),
+        _ => eprintln!("Invalid duration!"),
     }
+    std::process::exit(10)
+}
 
-    if flags.contains(TimedeltaFlags::MONTH) {
-        let months = seconds / MONTH_IN_SECONDS;
-        if months > 0 {
-            seconds -= months * MONTH_IN_SECONDS;
-            out.write_fmt(format_args!("{months}M")).unwrap();
-        }
-    }
+/// Exits when the cron expression is invalid
+pub fn parse_cron(expression: &str) -> timeman::Cron {
+    timeman::Cron::from_str(expression).unwrap_or_else(|err| {
+        eprintln!(
+            "Invalid cron expression{}!",
+            match err {
+                timeman::Error::InvalidCron(err) => format!(", {err}"),
+                _ => String::new(),
+            }
+        );
+        std::process::exit(2)
+    })
+}
 
-    if flags.contains(TimedeltaFlags::WEEK) {
-        let weeks = seconds / WEAK_IN_SECONDS;
-        if weeks > 0 {
-            seconds -= weeks * WEAK_IN_SECONDS;
-            out.write_fmt(format_args!("{weeks}W")).unwrap();
+/// Exits when the calendar event is invalid
+pub fn parse_calendar(calendar: &str) -> timeman::OnCalendar {
+    timeman::OnCalendar::from_str(calendar).unwrap_or_else(|err| {
+        match err {
+            timeman::Error::InvalidCalendar(err) => eprintln!("Invalid calendar event, {err}!"),
+            _ => eprintln!("Invalid calendar event!"),
         }
-    }
+        std::process::exit(2)
+    })
+}
 
-    if flags.contains(TimedeltaFlags::DAY) {
-        let days = seconds / DAY_IN_SECONDS;
-        if days > 0 {
-            seconds -= days * DAY_IN_SECONDS;
-            out.write_fmt(format_args!("{days}D")).unwrap();
+/// The events of the iCalendar file, exits when it cannot be read or is not valid
+pub fn read_events(path: &str) -> Vec<timeman::Event> {
+    let file = match std::fs::read_to_string(path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Cannot read the calendar from `{path}`: {err}");
+            std::process::exit(21)
         }
-    }
+    };
+    timeman::parse_ics(&file).unwrap_or_else(|err| {
+        eprintln!("`{path}` is an {err}!");
+        std::process::exit(21)
+    })
+}
 
-    if flags.contains(TimedeltaFlags::HOUR)
-        || flags.contains(TimedeltaFlags::MINUTE)
-        || flags.contains(TimedeltaFlags::SECOND)
-    {
-        out.push('T');
+/// The working hours of the flag, or of the config, or 9-17,
+/// exits when the ones of the config are invalid
+pub fn working_hours(
+    hours: Option<timeman::WorkingHours>,
+    config: &timeman::Config,
+) -> timeman::WorkingHours {
+    if let Some(hours) = hours {
+        return hours;
     }
+    let Some(hours) = &config.working_hours else {
+        return Default::default();
+    };
+    hours.parse().unwrap_or_else(|err| {
+        eprintln!("`working_hours` of the config is invalid, {err}!");
+        std::process::exit(17)
+    })
+}
 
-    if flags.contains(TimedeltaFlags::HOUR) {
-        let hours = seconds / HOUR_IN_SECONDS;
-        if hours > 0 {
-            seconds -= hours * HOUR_IN_SECONDS;
-            out.write_fmt(format_args!("{hours}H")).unwrap();
+/// Exits when the interval is invalid
+pub fn parse_interval(interval: &str, zone: Zone) -> timeman::Interval {
+    timeman::Interval::parse(interval, zone).unwrap_or_else(|err| {
+        match err {
+            timeman::Error::MalformedDuration(err) => {
+                eprintln!("`{interval}` has an invalid duration {err}!")
+            }
+            err => eprintln!("`{interval}` is not an interval, {err}!"),
         }
-    }
+        std::process::exit(2)
+    })
+}
 
-    if flags.contains(TimedeltaFlags::MINUTE) {
-        let minutes = seconds / MINUTE_IN_SECONDS;
-        if minutes > 0 {
-            seconds -= minutes * MINUTE_IN_SECONDS;
-            out.write_fmt(format_args!("{minutes}M")).unwrap();
+/// Exits when the recurrence rule is invalid
+pub fn parse_rrule(rule: &str) -> timeman::RRule {
+    timeman::RRule::from_str(rule).unwrap_or_else(|err| {
+        match err {
+            timeman::Error::InvalidRRule(err) => eprintln!("Invalid recurrence rule, {err}!"),
+            _ => eprintln!("Invalid recurrence rule!"),
         }
+        std::process::exit(2)
+    })
+}
+
+/// Like `timeman::parse_factor`, exits when it is not a number
+pub fn parse_number(number: &str) -> (i128, i128) {
+    let Some(number) = timeman::parse_factor(number) else {
+        eprintln!("`{number}` is not a number like \"3\" or \"1.5\"!");
+        std::process::exit(2)
+    };
+    number
+}
+
+/// Reverse video around the highlighted days of `cal` when stdout is a terminal and
+/// `NO_COLOR` is not set
+pub fn highlight_codes() -> (&'static str, &'static str) {
+    use std::io::IsTerminal;
+
+    if std::io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none_or(|var| var.is_empty())
+    {
+        ("\x1b[7m", "\x1b[27m")
+    } else {
+        ("", "")
     }
+}
 
-    if flags.contains(TimedeltaFlags::SECOND) {
-        let nanos = unsafe { (&timedelta as *const _ as *const i32).offset(2).read() }.abs();
-        if nanos != 0 && flags.contains(TimedeltaFlags::NANOS) {
-            out.write_fmt(format_args!("{seconds}.{}S", nanos)).unwrap();
-        } else {
-            out.write_fmt(format_args!("{seconds}S")).unwrap();
+/// Prints the result of `eval`, false when it is out of range
+pub fn print_value(
+    value: timeman::Value,
+    format: &[chrono::format::Item],
+    zone: Option<Zone>,
+    print: &DurPrintArgs,
+    config: &timeman::Config,
+    output: &Output,
+) -> bool {
+    match value {
+        timeman::Value::Date(date) => {
+            let date = zone.map_or(date, |zone| zone.from_utc_datetime(&date.naive_utc()));
+            output.date(&date, format);
+        }
+        timeman::Value::Duration(duration) => {
+            let Some(timedelta) = duration.to_timedelta() else {
+                return false;
+            };
+            print.clone().print(timedelta, config, output);
+        }
+        timeman::Value::Number(numerator, denominator) => {
+            let Some(value) = timeman::decimal_to_str(numerator, denominator) else {
+                return false;
+            };
+            match output.mode {
+                OutputMode::Text => println!("{value}"),
+                OutputMode::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "value": value.parse::<f64>().ok(),
+                        "formatted": value,
+                    })
+                ),
+            }
         }
     }
+    true
+}
 
-    out
+/// Why `eval` failed, like "Invalid expression at 5: expected `)`"
+pub fn expression_err(err: timeman::Error) -> String {
+    match err {
+        timeman::Error::InvalidExpression(err) => format!("Invalid expression {err}"),
+        err => format!("Invalid expression, {err}"),
+    }
 }
 
-fn timedelta_str_to_preety(str: &str) -> String {
-    let mut out = String::default();
+pub const REPL_HELP: &str = r#"Every line is an expression like `expr`, its result is `$1`, `$2` and so on
+and can be used in the next lines, like `$1 + P1D`
 
-    let mut num1 = 0u64;
-    let mut num2 = 0u32;
-    let mut dec = false;
+:format [FORMAT]  print or change the format of the dates, like `-f`
+:offset [OFFSET]  print or change the offset of the dates, like `-o`
+:results          print all the results
+:help             print this
+:quit             exit, like Ctrl-D"#;
 
-    let mut time = false;
+/// The loop of `repl`, the settings last until the end and the lines are kept in
+/// `Config::history_path` for the next time
+pub fn repl(
+    config: &timeman::Config,
+    format_str: &str,
+    mut zone: Zone,
+    print: &DurPrintArgs,
+    output: &Output,
+) {
+    let Ok(mut editor) = rustyline::DefaultEditor::new() else {
+        eprintln!("Cannot read from stdin!");
+        std::process::exit(14)
+    };
+    let history = timeman::Config::history_path();
+    if let Some(history) = &history {
+        _ = editor.load_history(history);
+    }
 
-    for char in str.chars() {
-        let s = if num1 > 1 { "s" } else { "" };
-        match char {
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                let num = char as u32 - b'0' as u32;
-                if dec {
-                    num2 = (num2 * 10) + num;
-                } else {
-                    num1 = (num1 * 10) + num as u64;
-                }
-            }
-            '.' => {
-                dec = true;
-            }
-            '-' => {
-                out.push('-');
-            }
-            'P' => {}
-            'T' => time = true,
-            'Y' => {
-                out.push_str(&format!("{num1} Year{s}, "));
-                num1 = 0;
+    let mut format_str = format_str.to_owned();
+    let mut results = Vec::new();
+    let show = |n: usize, value, format_str: &str, zone| {
+        if output.mode == OutputMode::Text {
+            print!("${n} = ");
+        }
+        let format = chrono::format::strftime::StrftimeItems::new(format_str)
+            .parse()
+            .unwrap();
+        if !print_value(value, &format, Some(zone), print, config, output) {
+            eprintln!("The result is out of range!");
+        }
+    };
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(
+                rustyline::error::ReadlineError::Interrupted | rustyline::error::ReadlineError::Eof,
+            ) => break,
+            Err(_) => {
+                eprintln!("Cannot read from stdin!");
+                std::process::exit(14)
             }
-            'M' => {
-                if !time {
-                    out.push_str(&format!("{num1} Month{s}, "));
-                } else {
-                    out.push_str(&format!("{num1} Minute{s}, "));
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        _ = editor.add_history_entry(line);
+
+        let (command, arg) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let arg = arg.trim();
+        match command {
+            ":quit" | ":q" => break,
+            ":help" => println!("{REPL_HELP}"),
+            ":format" if arg.is_empty() => println!("{format_str}"),
+            ":format" => {
+                let Ok(format) = config.resolve_format(arg) else {
+                    eprintln!("There is no format profile named \"{arg}\"!");
+                    continue;
+                };
+                let preset = timeman::find_preset(format);
+                let format = preset.map_or(format, |preset| preset.format);
+                if chrono::format::strftime::StrftimeItems::new(format)
+                    .parse()
+                    .is_err()
+                {
+                    eprintln!("Invalid format, run command `help-format`");
+                    continue;
+                }
+                format_str = format.to_owned();
+                if preset.is_some_and(|preset| preset.utc) {
+                    zone = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
                 }
-                num1 = 0;
-            }
-            'W' => {
-                out.push_str(&format!("{num1} Weak{s}, "));
-                num1 = 0;
             }
-            'D' => {
-                out.push_str(&format!("{num1} Day{s}, "));
-                num1 = 0;
+            ":offset" if arg.is_empty() => println!("{zone}"),
+            ":offset" => match Zone::from_str(arg) {
+                Ok(new) => zone = new,
+                Err(_) => eprintln!(
+                    "The offset should look like \"+00:00\" or a time zone name like \"Europe/Bucharest\""
+                ),
+            },
+            ":results" => {
+                for (i, value) in results.iter().enumerate() {
+                    show(i + 1, *value, &format_str, zone);
+                }
             }
-            'H' => {
-                out.push_str(&format!("{num1} Hour{s}, "));
-                num1 = 0;
+            _ if command.starts_with(':') => {
+                eprintln!("Unknown command `{command}`, see `:help`");
             }
-            'S' => {
-                out.push_str(&format!("{num1} Second{s}, "));
-                if num2 > 0 {
-                    out.push_str(&format!("{num2} Nanoseconds"));
+            _ => {
+                let format = chrono::format::strftime::StrftimeItems::new(&format_str)
+                    .parse()
+                    .unwrap();
+                let now = chrono::Utc::now();
+                let value = timeman::eval(line, zone, |literal| {
+                    if let Some(n) = literal.strip_prefix('$') {
+                        let n = n.parse::<usize>().ok()?;
+                        return results.get(n.checked_sub(1)?).copied();
+                    }
+                    timeman::parse_date_auto(&format, literal, zone)
+                        .map(|(date, _)| date)
+                        .or_else(|_| timeman::parse_relative(literal, now, zone))
+                        .ok()
+                        .map(timeman::Value::Date)
+                });
+                match value {
+                    Ok(value) => {
+                        results.push(value);
+                        show(results.len(), value, &format_str, zone);
+                    }
+                    Err(err) => eprintln!("{}!", expression_err(err)),
                 }
             }
-            _ => {}
         }
     }
 
-    out
+    if let Some(history) = &history {
+        if let Some(dir) = history.parent() {
+            _ = std::fs::create_dir_all(dir);
+        }
+        _ = editor.save_history(history);
+    }
 }
 
-fn timedelta_from_str(str: &str) -> Option<chrono::TimeDelta> {
-    let mut seconds = 0i64;
-    let mut nanos = 0u32;
+/// With `fixed` the years and months are in the time, like `timedelta_from_str`
+pub fn parse_duration(duration: &str, fixed: bool) -> CalendarDuration {
+    let mut duration =
+        CalendarDuration::from_str(duration).unwrap_or_else(|err| invalid_duration(err));
 
-    let mut num1 = 0i64;
-    let mut num2 = 0u32;
-    let mut dec = false;
+    if fixed {
+        let Some(time) = duration.to_timedelta() else {
+            eprintln!("Invalid duration!");
+            std::process::exit(10)
+        };
+        duration = CalendarDuration {
+            months: 0,
+            days: 0,
+            time,
+        };
+    }
 
-    let mut sign = 1;
+    duration
+}
 
-    let mut chars = str.chars();
+/// The items of a `-F` format that can be a profile or a preset,
+/// and if it is a preset that is always in UTC
+pub fn parse_to_format<'a>(
+    config: &'a timeman::Config,
+    to_format: &'a str,
+) -> (Vec<chrono::format::Item<'a>>, bool) {
+    let to_format = resolve_profile(config, to_format);
+    let to_preset = timeman::find_preset(to_format);
+    let to_format = to_preset.map_or(to_format, |preset| preset.format);
+    let Ok(items) = chrono::format::strftime::StrftimeItems::new(to_format).parse() else {
+        eprintln!("Invalid to_format, look at `format-help`");
+        std::process::exit(11);
+    };
+    (items, to_preset.is_some_and(|preset| preset.utc))
+}
 
-    loop {
-        match chars.next()? {
-            '-' => sign = -1,
-            'P' => break,
-            _ => return None,
+/// Turns a `@name` format in to the format of the profile from the config
+pub fn resolve_profile<'a>(config: &'a timeman::Config, format: &'a str) -> &'a str {
+    match config.resolve_format(format) {
+        Ok(format) => format,
+        Err(err) => {
+            eprintln!("Invalid format, {err}, add it to `[profiles]` in the config");
+            std::process::exit(2)
         }
     }
+}
+
+/// A weekday like "friday" or "fri" for clap
+fn parse_weekday(weekday: &str) -> Result<chrono::Weekday, String> {
+    timeman::relative::parse_weekday(&weekday.to_lowercase())
+        .ok_or_else(|| format!("\"{weekday}\" is not a weekday like \"friday\" or \"fri\""))
+}
 
-    let mut time = false;
+/// A month like "4", "april" or "apr" for clap
+fn parse_month(month: &str) -> Result<u32, String> {
+    month
+        .parse()
+        .ok()
+        .filter(|month| (1..=12).contains(month))
+        .or_else(|| timeman::nth::parse_month(&month.to_lowercase()))
+        .ok_or_else(|| format!("\"{month}\" is not a month like \"4\", \"april\" or \"apr\""))
+}
 
-    for char in chars {
-        match char {
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                let num = char as u32 - b'0' as u32;
+/// A month like "2024-02" for clap
+fn parse_year_month(month: &str) -> Result<(i32, u32), String> {
+    chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map(|date| (date.year(), date.month()))
+        .map_err(|_| format!("\"{month}\" is not a month like \"2024-02\""))
+}
 
-                if !dec {
-                    num1 = (num1 * 10) + num as i64;
-                } else {
-                    num2 = (num2 * 10) + num;
+/// A time of the day like "14:00" for clap
+fn parse_working_hours(hours: &str) -> Result<timeman::WorkingHours, String> {
+    hours.parse().map_err(|err: timeman::Error| err.to_string())
+}
+
+fn parse_time_of_day(time: &str) -> Result<chrono::NaiveTime, String> {
+    timeman::relative::parse_time_of_day(time)
+        .ok_or_else(|| format!("\"{time}\" is not a time like \"14:00\" or \"9:30:15\""))
+}
+
+pub fn parse_zone(zone: &str) -> Zone {
+    let Ok(zone) = Zone::from_str(zone) else {
+        eprintln!(
+            "The offset should look like \"+00:00\" or a time zone name like \"Europe/Bucharest\""
+        );
+        std::process::exit(2)
+    };
+    zone
+}
+
+/// The start of the argument that `-r FILE` is turned in to, it cannot be typed
+const REFERENCE: &str = "\0reference:";
+
+/// Turns every `-r FILE`, `--reference FILE` and `--reference=FILE` before `--` in to one
+/// date argument that is the time the file was modified
+///
+/// The subcommands with their own `-r` like `sort -r` are left as they are, and so is the
+/// command of `measure`, `at` and `periodic` after their first argument
+pub fn expand_references(
+    args: impl IntoIterator<Item = std::ffi::OsString>,
+) -> Vec<std::ffi::OsString> {
+    let mut command = TimeMan::command();
+    command.build();
+    let mut current = &command;
+    let mut positionals = 0;
+
+    let mut args = args.into_iter();
+    let mut expanded = args.next().into_iter().collect::<Vec<_>>();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let trailing = current
+            .get_arguments()
+            .any(|arg| arg.is_trailing_var_arg_set());
+        if arg == "--" || (trailing && positionals > 0) {
+            expanded.push(arg);
+            expanded.extend(args);
+            break;
+        }
+
+        let str = arg.to_string_lossy().into_owned();
+        let own = current
+            .get_arguments()
+            .any(|arg| arg.get_short() == Some('r'));
+        let path = if own {
+            None
+        } else if str == "-r" || str == "--reference" {
+            args.next()
+        } else {
+            str.strip_prefix("--reference=").map(Into::into)
+        };
+        if let Some(path) = path {
+            let mut reference = std::ffi::OsString::from(REFERENCE);
+            reference.push(path);
+            expanded.push(reference);
+            positionals += 1;
+            continue;
+        }
+
+        // the value of an option is not a subcommand or a date
+        let takes_value = |arg: Option<&clap::Arg>| {
+            arg.is_some_and(|arg| {
+                arg.get_action().takes_values()
+                    && arg.get_num_args().is_none_or(|num| num.min_values() > 0)
+            })
+        };
+        let value = if let Some(long) = str.strip_prefix("--") {
+            !long.contains('=')
+                && takes_value(
+                    current
+                        .get_arguments()
+                        .find(|arg| arg.get_long() == Some(long)),
+                )
+        } else if let Some(shorts) = str.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+            // like `-af rfc3339`, the value is the next argument when it is the last letter
+            let mut value = false;
+            for (i, short) in shorts.char_indices() {
+                let arg = current
+                    .get_arguments()
+                    .find(|arg| arg.get_short() == Some(short));
+                if arg.is_none() {
+                    break;
                 }
-            }
-            'Y' => {
-                seconds += num1 * YEAR_IN_SECONDS;
-                num1 = 0;
-            }
-            'M' => {
-                if time {
-                    seconds += num1 * MINUTE_IN_SECONDS;
-                } else {
-                    seconds += num1 * MONTH_IN_SECONDS;
+                if takes_value(arg) {
+                    value = i + short.len_utf8() == shorts.len();
+                    break;
                 }
-                num1 = 0;
-            }
-            'W' => {
-                seconds += num1 * WEAK_IN_SECONDS;
-                num1 = 0;
-            }
-            'D' => {
-                seconds += num1 * DAY_IN_SECONDS;
-                num1 = 0;
-            }
-            'T' => {
-                num1 = 0;
-                time = true;
             }
-            'H' => {
-                seconds += num1 * HOUR_IN_SECONDS;
-                num1 = 0;
+            value
+        } else if let Some(subcommand) = current.find_subcommand(&arg) {
+            current = subcommand;
+            positionals = 0;
+            false
+        } else {
+            positionals += 1;
+            false
+        };
+
+        expanded.push(arg);
+        if value {
+            expanded.extend(args.next());
+        }
+    }
+    expanded
+}
+
+/// Why a date cannot be used, with the code to exit with
+#[derive(Debug)]
+pub struct DateError {
+    pub message: String,
+    pub code: i32,
+}
+
+impl DateError {
+    pub fn new(message: impl Into<String>, code: i32) -> Self {
+        Self {
+            message: message.into(),
+            code,
+        }
+    }
+
+    /// Like "The result is out of range!"
+    pub fn out_of_range(what: &str) -> Self {
+        Self::new(format!("The {what} is out of range!"), 13)
+    }
+
+    pub fn exit(self) -> ! {
+        eprintln!("{}", self.message);
+        std::process::exit(self.code)
+    }
+}
+
+/// Everything that is needed to turn a date argument in to a date
+pub struct DateParser<'a> {
+    pub format: &'a [chrono::format::Item<'a>],
+    pub format_str: &'a str,
+    /// Used when the date has no offset in it
+    pub zone: Zone,
+    pub auto: bool,
+    /// The format is a preset, so a missing offset is taken from `zone`
+    pub lenient: bool,
+    /// Relative dates like `2 hours ago` are from this
+    pub now: chrono::DateTime<chrono::Utc>,
+    /// A date argument was `-` and stdin was read
+    pub stdin_used: std::cell::Cell<bool>,
+}
+
+impl DateParser<'_> {
+    /// When `date` is `-` the date is read from stdin, when it is `-r FILE` it is the time
+    /// the file was modified
+    pub fn parse(&self, date: &str, field: &str) -> chrono::DateTime<chrono::FixedOffset> {
+        self.try_parse(date, field).unwrap_or_else(|err| err.exit())
+    }
+
+    /// Like `parse` but the error is returned, so a line of `--stdin` can be skipped
+    pub fn try_parse(
+        &self,
+        date: &str,
+        field: &str,
+    ) -> Result<chrono::DateTime<chrono::FixedOffset>, DateError> {
+        if let Some(path) = date.strip_prefix(REFERENCE) {
+            let modified = timeman::file_mtime(path.as_ref()).map_err(|err| {
+                DateError::new(
+                    format!("Cannot read when `{path}` of `{field}` was modified: {err}"),
+                    14,
+                )
+            })?;
+            return Ok(self.zone.from_utc_datetime(&modified.naive_utc()));
+        }
+
+        let stdin_date;
+        let date = if date == "-" {
+            stdin_date = self.read_stdin(field);
+            stdin_date.as_str()
+        } else {
+            date
+        };
+
+        let result = if self.auto {
+            timeman::parse_date_auto(self.format, date, self.zone).map(|(date, matched)| {
+                if let Some((name, format)) = matched {
+                    eprintln!("`{field}` was parsed as {name}: `{format}`");
+                }
+                date
+            })
+        } else if self.lenient {
+            timeman::parse_date_lenient(self.format, date, self.zone)
+        } else {
+            timeman::parse_date(self.format, date)
+        };
+
+        let err = match result {
+            Ok(date) => return self.check_leap_second(date, field),
+            Err(err) => err,
+        };
+
+        if let Ok(date) = timeman::parse_relative(date, self.now, self.zone) {
+            return self.check_leap_second(date, field);
+        }
+
+        Err(match err {
+            timeman::Error::NoOffset => DateError::new(
+                format!("Cannot parse the timeoffset for `{field}` or you don't have a format with `%:z` in it!"),
+                6,
+            ),
+            timeman::Error::InvalidDate => {
+                DateError::new(format!("`{field}` has a invalid date!"), 7)
             }
-            '.' => dec = true,
-            'S' => {
-                seconds += num1;
-                nanos += num2;
+            timeman::Error::AmbiguousDate => {
+                DateError::new(format!("`{field}` has a invalid date or ambiguous time!"), 8)
             }
+            timeman::Error::NoAutoFormat => DateError::new(
+                format!(
+                    "Cannot parse `{field}` the date should be in this format: `{}` or in one of the auto formats",
+                    self.format_str
+                ),
+                5,
+            ),
+            _ => DateError::new(
+                format!(
+                    "Cannot parse `{field}` the date should be in this format: `{}` ",
+                    self.format_str
+                ),
+                5,
+            ),
+        })
+    }
 
-            _ => return None,
+    /// With `--leap-seconds` a second like `23:59:60` should be a leap second of the list
+    fn check_leap_second(
+        &self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        field: &str,
+    ) -> Result<chrono::DateTime<chrono::FixedOffset>, DateError> {
+        let Some(leap_seconds) = LEAP_SECONDS.get() else {
+            return Ok(date);
+        };
+        if date.timestamp_subsec_nanos() >= 1_000_000_000
+            && !leap_seconds.is_leap_second(date.to_utc())
+        {
+            return Err(DateError::new(
+                format!("`{field}` is not a leap second!"),
+                7,
+            ));
         }
+        Ok(date)
     }
 
-    chrono::TimeDelta::new(seconds * sign, nanos)
+    fn read_stdin(&self, field: &str) -> String {
+        if self.stdin_used.replace(true) {
+            eprintln!("`{field}` is `-` but stdin was already read for another date!");
+            std::process::exit(2)
+        }
+
+        let mut date = String::new();
+        if std::io::stdin().read_to_string(&mut date).is_err() {
+            eprintln!("Cannot read from stdin!");
+            std::process::exit(14)
+        }
+        date.trim().to_owned()
+    }
 }
 
 #[cfg(test)]
 #[test]
-fn timedelta() {
-    use chrono::NaiveDateTime;
-    use chrono::TimeDelta;
-    use chrono::Utc;
+fn child_arguments() {
+    let command = |args: &[&str]| match TimeMan::try_parse_from(expand_references(
+        args.iter().map(Into::into),
+    ))
+    .unwrap()
+    .command
+    {
+        TimeManCommand::Measure { command, .. }
+        | TimeManCommand::At { command, .. }
+        | TimeManCommand::Periodic { command, .. } => command,
+        _ => unreachable!(),
+    };
 
-    let time_delta = TimeDelta::new(1, 32).unwrap();
+    // the `-r` of the command is not a date
+    assert_eq!(
+        command(&["tm", "measure", "ls", "-r", "/tmp"]),
+        ["ls", "-r", "/tmp"]
+    );
     assert_eq!(
-        timedelta_to_str(time_delta, TimedeltaFlags::all()),
-        "PT1.32S".to_owned()
+        command(&["tm", "at", "tomorrow 9:00", "--", "ls", "-r", "/tmp"]),
+        ["ls", "-r", "/tmp"]
     );
     assert_eq!(
-        time_delta,
-        timedelta_from_str(&timedelta_to_str(time_delta, TimedeltaFlags::all())).unwrap()
+        command(&[
+            "tm",
+            "periodic",
+            "PT1M",
+            "--",
+            "sort",
+            "-r",
+            "--reference=x"
+        ]),
+        ["sort", "-r", "--reference=x"]
     );
+}
+
+#[cfg(test)]
+#[test]
+fn references() {
+    let parse = |args: &[&str]| {
+        TimeMan::try_parse_from(expand_references(args.iter().map(Into::into))).unwrap()
+    };
+    let reference = |path: &str| format!("{REFERENCE}{path}");
+
+    match parse(&[
+        "tm",
+        "-f",
+        "rfc3339",
+        "sub",
+        "-r",
+        "a.log",
+        "--reference=b.log",
+    ])
+    .command
+    {
+        TimeManCommand::Sub {
+            from_date, date, ..
+        } => assert_eq!((from_date, date), (reference("a.log"), reference("b.log"))),
+        _ => unreachable!(),
+    }
+    match parse(&["tm", "since", "--reference", "build/output", "sn"]).command {
+        TimeManCommand::Since {
+            date,
+            duration_flags,
+            ..
+        } => {
+            assert_eq!(date, Some(reference("build/output")));
+            assert_eq!(duration_flags.as_deref(), Some("sn"));
+        }
+        _ => unreachable!(),
+    }
+    match parse(&["tm", "unix", "to", "-r", "a.log"]).command {
+        TimeManCommand::Unix {
+            command: UnixCommand::To { date },
+            ..
+        } => assert_eq!(date, Some(reference("a.log"))),
+        _ => unreachable!(),
+    }
+    // the `-r` of `sort` is `--reverse`
+    match parse(&["tm", "sort", "-r"]).command {
+        TimeManCommand::Sort { reverse, .. } => assert!(reverse),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn negative_durations() {
+    let durations = |args: &[&str]| match TimeMan::try_parse_from(args).unwrap().command {
+        TimeManCommand::Dur {
+            command: DurCommand::Add { durations, .. } | DurCommand::Sub { durations, .. },
+        } => durations,
+        _ => unreachable!(),
+    };
 
-    let since = Utc::now().naive_utc() - NaiveDateTime::UNIX_EPOCH;
     assert_eq!(
-        since,
-        timedelta_from_str(&timedelta_to_str(since, TimedeltaFlags::default())).unwrap()
+        durations(&["tm", "dur", "add", "-PT1H", "PT30M"]),
+        ["-PT1H", "PT30M"]
+    );
+    assert_eq!(
+        durations(&["tm", "dur", "sub", "-p", "-PT1H", "-PT30M"]),
+        ["-PT1H", "-PT30M"]
     );
 }
@@ -0,0 +1,178 @@
+use chrono::Datelike;
+
+use crate::relative::{parse_time_of_day, parse_weekday};
+use crate::{Error, Zone};
+
+/// The `n`th `weekday` of a month, a negative `n` is from the end so `-1` is the last one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NthWeekday {
+    pub n: i8,
+    pub weekday: chrono::Weekday,
+}
+
+impl NthWeekday {
+    /// The day in `month` of `year`, none when the month doesn't have it like the 5th Monday
+    pub fn in_month(self, year: i32, month: u32) -> Option<chrono::NaiveDate> {
+        if self.n > 0 {
+            return chrono::NaiveDate::from_weekday_of_month_opt(
+                year,
+                month,
+                self.weekday,
+                self.n as u8,
+            );
+        }
+
+        let first = chrono::NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = first
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?;
+        let last = last - chrono::Days::new(last.weekday().days_since(self.weekday) as u64);
+        let date = last.checked_sub_days(chrono::Days::new(7 * (-self.n as u64 - 1)))?;
+        (date.month() == month).then_some(date)
+    }
+}
+
+/// Parses `first`, `2nd`, `3` ... `fifth` and `last`
+fn parse_nth(str: &str) -> Option<i8> {
+    match str {
+        "first" | "1st" | "1" => Some(1),
+        "second" | "2nd" | "2" => Some(2),
+        "third" | "3rd" | "3" => Some(3),
+        "fourth" | "4th" | "4" => Some(4),
+        "fifth" | "5th" | "5" => Some(5),
+        "last" => Some(-1),
+        _ => None,
+    }
+}
+
+/// Parses `january` or `jan` to `1`
+pub fn parse_month(str: &str) -> Option<u32> {
+    let month = match str {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// Parses a day like:
+///
+/// - `3rd thursday of november 2025`, `first monday of sep` this year
+/// - `last friday of the month 18:00`, `2nd tuesday of next month`
+///
+/// The month without a year and `the month`, `next month` and `last month` are from `now` in `zone`
+pub fn parse_nth_weekday(
+    input: &str,
+    now: chrono::DateTime<chrono::Utc>,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let input = input.trim().to_lowercase();
+    let mut tokens = input.split_whitespace().collect::<Vec<_>>();
+    let today = zone.from_utc_datetime(&now.naive_utc()).date_naive();
+
+    let time = match tokens.last().and_then(|time| parse_time_of_day(time)) {
+        Some(time) => {
+            tokens.pop();
+            time
+        }
+        None => chrono::NaiveTime::MIN,
+    };
+
+    let [nth, weekday, "of", month @ ..] = tokens.as_slice() else {
+        return Err(Error::InvalidNthWeekday);
+    };
+    let nth = NthWeekday {
+        n: parse_nth(nth).ok_or(Error::InvalidNthWeekday)?,
+        weekday: parse_weekday(weekday).ok_or(Error::InvalidNthWeekday)?,
+    };
+
+    let this_month = today.with_day(1).ok_or(Error::InvalidDate)?;
+    let month = match month {
+        ["the" | "this", "month"] => Some(this_month),
+        ["next", "month"] => this_month.checked_add_months(chrono::Months::new(1)),
+        ["last", "month"] => this_month.checked_sub_months(chrono::Months::new(1)),
+        [month] => {
+            let month = parse_month(month).ok_or(Error::InvalidNthWeekday)?;
+            chrono::NaiveDate::from_ymd_opt(today.year(), month, 1)
+        }
+        [month, year] => {
+            let month = parse_month(month).ok_or(Error::InvalidNthWeekday)?;
+            let year = year.parse().map_err(|_| Error::InvalidNthWeekday)?;
+            chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        }
+        _ => return Err(Error::InvalidNthWeekday),
+    }
+    .ok_or(Error::InvalidDate)?;
+
+    let date = nth
+        .in_month(month.year(), month.month())
+        .ok_or(Error::InvalidDate)?;
+    zone.resolve_local(&date.and_time(time))
+        .ok_or(Error::InvalidDate)
+}
+
+#[cfg(test)]
+#[test]
+fn nth_weekday() {
+    use chrono::TimeZone;
+
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let nth = |n, weekday| NthWeekday { n, weekday };
+
+    // Thanksgiving
+    assert_eq!(
+        nth(4, chrono::Weekday::Thu).in_month(2025, 11),
+        Some(date(2025, 11, 27))
+    );
+    // Memorial Day
+    assert_eq!(
+        nth(-1, chrono::Weekday::Mon).in_month(2025, 5),
+        Some(date(2025, 5, 26))
+    );
+    assert_eq!(
+        nth(-5, chrono::Weekday::Sat).in_month(2025, 5),
+        Some(date(2025, 5, 3))
+    );
+    assert_eq!(nth(5, chrono::Weekday::Mon).in_month(2025, 2), None);
+    assert_eq!(nth(-5, chrono::Weekday::Mon).in_month(2025, 2), None);
+
+    let now = chrono::Utc.with_ymd_and_hms(2024, 4, 26, 12, 0, 0).unwrap();
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(3 * 3600).unwrap());
+    let at = |y, m, d, h| {
+        chrono::FixedOffset::east_opt(3 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(y, m, d, h, 0, 0)
+            .unwrap()
+    };
+
+    assert_eq!(
+        parse_nth_weekday("3rd Thursday of November 2025", now, zone),
+        Ok(at(2025, 11, 20, 0))
+    );
+    assert_eq!(
+        parse_nth_weekday("last friday of the month 18:00", now, zone),
+        Ok(at(2024, 4, 26, 18))
+    );
+    assert_eq!(
+        parse_nth_weekday("first monday of next month", now, zone),
+        Ok(at(2024, 5, 6, 0))
+    );
+    assert_eq!(
+        parse_nth_weekday("fifth monday of feb 2025", now, zone),
+        Err(Error::InvalidDate)
+    );
+    assert_eq!(
+        parse_nth_weekday("3rd thursday in november", now, zone),
+        Err(Error::InvalidNthWeekday)
+    );
+}
@@ -0,0 +1,149 @@
+use crate::Error;
+
+type Utc = chrono::DateTime<chrono::Utc>;
+
+/// The seconds from 1900-01-01, the NTP epoch, to 1970-01-01
+pub(crate) const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+
+/// The answer of an SNTP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NtpTime {
+    /// The time of the server when the answer was received
+    pub time: Utc,
+    /// How much the local clock is behind the server, it is negative when it is ahead
+    pub offset: chrono::TimeDelta,
+    /// The time the request and the answer were on the network
+    pub delay: chrono::TimeDelta,
+    /// How far the server is from a reference clock, 1 is next to it
+    pub stratum: u8,
+}
+
+/// Asks the SNTP `server`, like "pool.ntp.org" or "time.google.com:123", for its time (RFC 4330)
+pub fn query_ntp(server: &str, timeout: std::time::Duration) -> Result<NtpTime, Error> {
+    use std::net::ToSocketAddrs;
+
+    let failed = |err: &dyn std::fmt::Display| Error::NtpFailed(format!("{server}: {err}"));
+    // the port is 123 when it is not in the `server`
+    let address = server
+        .to_socket_addrs()
+        .or_else(|_| (server.trim_matches(['[', ']']), 123).to_socket_addrs())
+        .map_err(|err| failed(&err))?
+        .next()
+        .ok_or_else(|| failed(&"no address"))?;
+
+    let bind = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = std::net::UdpSocket::bind(bind).map_err(|err| failed(&err))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|err| failed(&err))?;
+    socket.connect(address).map_err(|err| failed(&err))?;
+
+    // version 4, client mode, with the local time as the transmit time
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+    let sent = chrono::Utc::now();
+    request[40..48].copy_from_slice(&to_ntp(sent).to_be_bytes());
+    socket.send(&request).map_err(|err| failed(&err))?;
+
+    let mut answer = [0u8; 48];
+    let len = socket.recv(&mut answer).map_err(|err| failed(&err))?;
+    let received = chrono::Utc::now();
+    parse_ntp(&answer[..len], sent, received).map_err(|err| failed(&err))
+}
+
+/// The time of the `answer` to a request that was sent at `sent` and answered at `received`
+fn parse_ntp(answer: &[u8], sent: Utc, received: Utc) -> Result<NtpTime, String> {
+    let Ok(answer) = <&[u8; 48]>::try_from(answer) else {
+        return Err(format!("the answer is {} bytes, not 48", answer.len()));
+    };
+    let timestamp =
+        |at: usize| u64::from_be_bytes(answer[at..at + 8].try_into().unwrap_or_default());
+
+    let mode = answer[0] & 0x7;
+    if mode != 4 {
+        return Err(format!(
+            "the answer is in the mode {mode}, not 4 of a server"
+        ));
+    }
+    let stratum = answer[1];
+    if stratum == 0 {
+        let code = String::from_utf8_lossy(&answer[12..16]);
+        return Err(format!("the server refused with `{code}`"));
+    }
+    if timestamp(24) != to_ntp(sent) {
+        return Err("the answer is not for the request".to_owned());
+    }
+
+    let (Some(receive), Some(transmit)) = (from_ntp(timestamp(32)), from_ntp(timestamp(40))) else {
+        return Err("the time of the server is invalid".to_owned());
+    };
+    let offset = ((receive - sent) + (transmit - received)) / 2;
+    let delay = ((received - sent) - (transmit - receive)).max(chrono::TimeDelta::zero());
+    Ok(NtpTime {
+        time: received + offset,
+        offset,
+        delay,
+        stratum,
+    })
+}
+
+/// The 32 bits of seconds from the NTP epoch and the 32 bits of the fraction of a second
+fn to_ntp(date: Utc) -> u64 {
+    let seconds = (date.timestamp() + NTP_UNIX_OFFSET) as u64 & 0xFFFF_FFFF;
+    let fraction = (u64::from(date.timestamp_subsec_nanos()) << 32) / 1_000_000_000;
+    seconds << 32 | fraction
+}
+
+/// The seconds that are less than 2^31 are after 2036, in the next era of NTP
+fn from_ntp(timestamp: u64) -> Option<Utc> {
+    let seconds = (timestamp >> 32) as i64;
+    let seconds = if seconds < 1 << 31 {
+        seconds + (1 << 32)
+    } else {
+        seconds
+    };
+    let nanos = ((timestamp & 0xFFFF_FFFF) * 1_000_000_000) >> 32;
+    chrono::DateTime::from_timestamp(seconds - NTP_UNIX_OFFSET, nanos as u32)
+}
+
+#[cfg(test)]
+#[test]
+fn ntp() {
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap().to_utc();
+
+    let sent = date("2025-01-01T00:00:00.5Z");
+    assert_eq!(from_ntp(to_ntp(sent)), Some(sent));
+    let after_2036 = date("2040-01-01T00:00:00Z");
+    assert_eq!(from_ntp(to_ntp(after_2036)), Some(after_2036));
+
+    // the server is 10s ahead and the network takes 20ms each way
+    let mut answer = [0u8; 48];
+    answer[0] = 0x24;
+    answer[1] = 2;
+    answer[24..32].copy_from_slice(&to_ntp(sent).to_be_bytes());
+    answer[32..40].copy_from_slice(&to_ntp(date("2025-01-01T00:00:10.52Z")).to_be_bytes());
+    answer[40..48].copy_from_slice(&to_ntp(date("2025-01-01T00:00:10.53Z")).to_be_bytes());
+    let received = date("2025-01-01T00:00:00.55Z");
+    let ntp = parse_ntp(&answer, sent, received).unwrap();
+    assert_eq!(ntp.stratum, 2);
+    assert!(
+        (ntp.offset - chrono::TimeDelta::seconds(10)).abs() < chrono::TimeDelta::microseconds(1)
+    );
+    assert!(
+        (ntp.delay - chrono::TimeDelta::milliseconds(40)).abs()
+            < chrono::TimeDelta::microseconds(1)
+    );
+
+    assert!(parse_ntp(&answer, date("2025-01-01T00:00:01Z"), received).is_err());
+    assert!(parse_ntp(&answer[..40], sent, received).is_err());
+    answer[1] = 0;
+    answer[12..16].copy_from_slice(b"RATE");
+    assert_eq!(
+        parse_ntp(&answer, sent, received),
+        Err("the server refused with `RATE`".to_owned())
+    );
+}
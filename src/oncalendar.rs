@@ -0,0 +1,492 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, Timelike};
+
+use crate::{days_in_month, relative::parse_weekday, Error, Zone};
+
+/// A value, a range like `1..5` or a repetition like `0/15` of a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    start: u32,
+    end: u32,
+    step: u32,
+    /// It was `*`
+    all: bool,
+    /// It was a repetition without an end like `0/15`
+    open: bool,
+}
+
+impl Item {
+    fn matches(&self, value: u32) -> bool {
+        (self.start..=self.end).contains(&value) && (value - self.start).is_multiple_of(self.step)
+    }
+}
+
+/// A component of the date or the time, like the `*` of the year or the `0/15` of the minutes
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Component {
+    items: Vec<Item>,
+    /// The digits of a value in the normalized form, 4 for the years and 2 for the rest
+    width: usize,
+}
+
+impl Component {
+    fn all(min: u32, max: u32, width: usize) -> Self {
+        Self {
+            items: vec![Item {
+                start: min,
+                end: max,
+                step: 1,
+                all: true,
+                open: false,
+            }],
+            width,
+        }
+    }
+
+    fn parse(str: &str, name: &str, min: u32, max: u32, width: usize) -> Result<Self, Error> {
+        let value = |str: &str| {
+            str.parse::<u32>()
+                .ok()
+                .filter(|value| (min..=max).contains(value))
+                .ok_or_else(|| {
+                    Error::InvalidCalendar(format!(
+                        "`{str}` is not a value of the {name} from {min} to {max}"
+                    ))
+                })
+        };
+
+        let mut items = Vec::new();
+        for item in str.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step = step.parse::<u32>().ok().filter(|step| *step > 0);
+                    let step = step.ok_or_else(|| {
+                        Error::InvalidCalendar(format!("`{item}` has an invalid repetition"))
+                    })?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+            let (start, end, all) = if range == "*" {
+                (min, max, true)
+            } else if let Some((start, end)) = range.split_once("..") {
+                (value(start)?, value(end)?, false)
+            } else {
+                let start = value(range)?;
+                // `0/15` repeats until the end
+                (start, if step > 1 { max } else { start }, false)
+            };
+            let open = !all && step > 1 && !range.contains("..");
+            if start > end {
+                return Err(Error::InvalidCalendar(format!(
+                    "`{range}` is a reversed range of the {name}"
+                )));
+            }
+            items.push(Item {
+                start,
+                end,
+                step,
+                all,
+                open,
+            });
+        }
+        Ok(Self { items, width })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.items.iter().any(|item| item.matches(value))
+    }
+}
+
+impl std::fmt::Display for Component {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.width;
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            if item.all && item.step == 1 {
+                write!(f, "*")?;
+                continue;
+            }
+            write!(f, "{:0width$}", item.start)?;
+            if !item.all && !item.open && item.start != item.end {
+                write!(f, "..{:0width$}", item.end)?;
+            }
+            if item.step > 1 {
+                write!(f, "/{}", item.step)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A systemd calendar event like `Mon..Fri *-*-* 02:00`, see `systemd.time(7)`,
+/// with the weekdays, the date, the time and a time zone name that are all optional
+///
+/// The date can be `*-02~01` for the last day of February, and the shorthands
+/// `minutely`, `hourly`, `daily`, `weekly`, `monthly`, `quarterly`, `semiannually`
+/// and `yearly` are there too
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnCalendar {
+    /// Bit `n` is the weekday `n` from Monday
+    weekdays: u8,
+    years: Component,
+    months: Component,
+    days: Component,
+    /// The days are from the end of the month, `~01` is the last day and one that is
+    /// longer than the month is its first day
+    last: bool,
+    hours: Component,
+    minutes: Component,
+    seconds: Component,
+    zone: Option<chrono_tz::Tz>,
+}
+
+fn parse_weekdays(str: &str) -> Result<u8, Error> {
+    let weekday = |str: &str| {
+        parse_weekday(&str.to_lowercase())
+            .map(|weekday| weekday.num_days_from_monday())
+            .ok_or_else(|| Error::InvalidCalendar(format!("`{str}` is not a weekday")))
+    };
+    let mut weekdays = 0;
+    for item in str.split(',') {
+        let (start, end) = match item.split_once("..") {
+            Some((start, end)) => (weekday(start)?, weekday(end)?),
+            None => (weekday(item)?, weekday(item)?),
+        };
+        // `Sat..Mon` wraps around the week
+        let mut day = start;
+        loop {
+            weekdays |= 1 << day;
+            if day == end {
+                break;
+            }
+            day = (day + 1) % 7;
+        }
+    }
+    Ok(weekdays)
+}
+
+impl FromStr for OnCalendar {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens: Vec<&str> = s.split_whitespace().collect();
+        let shorthand = match tokens.first().map(|token| token.to_lowercase()).as_deref() {
+            Some("minutely") => Some("*-*-* *:*:00"),
+            Some("hourly") => Some("*-*-* *:00:00"),
+            Some("daily") => Some("*-*-* 00:00:00"),
+            Some("weekly") => Some("Mon *-*-* 00:00:00"),
+            Some("monthly") => Some("*-*-01 00:00:00"),
+            Some("quarterly") => Some("*-01,04,07,10-01 00:00:00"),
+            Some("semiannually") => Some("*-01,07-01 00:00:00"),
+            Some("yearly" | "annually") => Some("*-01-01 00:00:00"),
+            _ => None,
+        };
+        if let Some(shorthand) = shorthand {
+            tokens.splice(..1, shorthand.split_whitespace());
+        }
+
+        let zone = match tokens.last().map(|token| chrono_tz::Tz::from_str(token)) {
+            Some(Ok(tz)) => {
+                tokens.pop();
+                Some(tz)
+            }
+            _ => None,
+        };
+
+        let mut calendar = Self {
+            weekdays: 0x7f,
+            years: Component::all(1970, 9999, 4),
+            months: Component::all(1, 12, 2),
+            days: Component::all(1, 31, 2),
+            last: false,
+            hours: Component::parse("00", "hour", 0, 23, 2)?,
+            minutes: Component::parse("00", "minute", 0, 59, 2)?,
+            seconds: Component::parse("00", "second", 0, 59, 2)?,
+            zone,
+        };
+
+        let mut tokens = tokens.into_iter().peekable();
+        if let Some(weekdays) = tokens.next_if(|token| token.starts_with(char::is_alphabetic)) {
+            calendar.weekdays = parse_weekdays(weekdays)?;
+        }
+        if let Some(date) = tokens.next_if(|token| !token.contains(':')) {
+            calendar.parse_date(date)?;
+        }
+        if let Some(time) = tokens.next() {
+            calendar.parse_time(time)?;
+        }
+        if let Some(token) = tokens.next() {
+            return Err(Error::InvalidCalendar(format!(
+                "`{token}` is not a time zone name like `Europe/Berlin`"
+            )));
+        }
+        Ok(calendar)
+    }
+}
+
+impl OnCalendar {
+    fn parse_date(&mut self, date: &str) -> Result<(), Error> {
+        let (rest, days) = match date.rsplit_once('~') {
+            Some(split) => {
+                self.last = true;
+                split
+            }
+            None => date.rsplit_once('-').ok_or_else(|| {
+                Error::InvalidCalendar(format!("`{date}` is not a date like `*-*-01`"))
+            })?,
+        };
+        let (years, months) = match rest.split_once('-') {
+            Some((years, months)) => (Some(years), months),
+            None => (None, rest),
+        };
+
+        if let Some(years) = years {
+            self.years = Component::parse(years, "year", 1970, 9999, 4)?;
+        }
+        self.months = Component::parse(months, "month", 1, 12, 2)?;
+        self.days = Component::parse(days, "day", 1, 31, 2)?;
+        Ok(())
+    }
+
+    fn parse_time(&mut self, time: &str) -> Result<(), Error> {
+        let parts: Vec<&str> = time.split(':').collect();
+        let (hours, minutes, seconds) = match parts.as_slice() {
+            [hours, minutes] => (*hours, *minutes, "00"),
+            [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+            _ => {
+                return Err(Error::InvalidCalendar(format!(
+                    "`{time}` is not a time like `02:00` or `*:0/15:00`"
+                )))
+            }
+        };
+        self.hours = Component::parse(hours, "hour", 0, 23, 2)?;
+        self.minutes = Component::parse(minutes, "minute", 0, 59, 2)?;
+        self.seconds = Component::parse(seconds, "second", 0, 59, 2)?;
+        Ok(())
+    }
+
+    /// The time zone at the end of the expression, the times are in it instead of the zone
+    /// of `times`
+    pub fn zone(&self) -> Option<Zone> {
+        self.zone.map(Zone::Named)
+    }
+
+    fn matches_day(&self, date: chrono::NaiveDate) -> bool {
+        let day = if self.last {
+            let length = days_in_month(date.year(), date.month()).unwrap_or(31);
+            // `~31` of a month with 30 days is clamped to the first day
+            let clamped = date.day() == 1 && (length + 1..=31).any(|day| self.days.matches(day));
+            clamped || self.days.matches(length - date.day() + 1)
+        } else {
+            self.days.matches(date.day())
+        };
+        self.weekdays & (1 << date.weekday().num_days_from_monday()) != 0
+            && self.years.matches(date.year() as u32)
+            && self.months.matches(date.month())
+            && day
+    }
+
+    /// The wall clock time after `local` that matches, none after the years of the expression
+    /// or after 400 years
+    fn step(&self, local: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+        let second = chrono::TimeDelta::seconds(1);
+        let mut time = local.checked_add_signed(second)?;
+        let limit = time.year() + 400;
+        let last_year = self.years.items.iter().map(|item| item.end).max()?;
+
+        while time.year() < limit && time.year() as u32 <= last_year {
+            let date = time.date();
+            let midnight = chrono::NaiveTime::MIN;
+            if time.year() < 1970 || !self.years.matches(time.year() as u32) {
+                time = chrono::NaiveDate::from_ymd_opt(time.year() + 1, 1, 1)?.and_time(midnight);
+            } else if !self.months.matches(date.month()) {
+                time = date
+                    .with_day(1)?
+                    .checked_add_months(chrono::Months::new(1))?
+                    .and_time(midnight);
+            } else if !self.matches_day(date) {
+                time = date.succ_opt()?.and_time(midnight);
+            } else if !self.hours.matches(time.hour()) {
+                time = time.with_minute(0)?.with_second(0)? + chrono::TimeDelta::hours(1);
+            } else if !self.minutes.matches(time.minute()) {
+                time = time.with_second(0)? + chrono::TimeDelta::minutes(1);
+            } else if !self.seconds.matches(time.second()) {
+                time += second;
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    /// The times after `from` in the wall clock of the zone of the expression or of `zone`,
+    /// a wall clock time skipped by DST doesn't elapse and one that is repeated elapses once
+    pub fn times(
+        &self,
+        from: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+    ) -> OnCalendarTimes<'_> {
+        let zone = self.zone().unwrap_or(zone);
+        let local = zone.from_utc_datetime(&from.naive_utc()).naive_local();
+        OnCalendarTimes {
+            calendar: self,
+            zone,
+            from,
+            local: local.with_nanosecond(0),
+        }
+    }
+}
+
+/// Like `systemd-analyze calendar`, `Mon..Fri *-*-* 02:00:00`
+impl std::fmt::Display for OnCalendar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.weekdays != 0x7f {
+            let names = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+            let mut runs: Vec<(usize, usize)> = Vec::new();
+            for day in (0..7).filter(|day| self.weekdays & (1 << day) != 0) {
+                match runs.last_mut() {
+                    Some((_, end)) if *end + 1 == day => *end = day,
+                    _ => runs.push((day, day)),
+                }
+            }
+            let runs: Vec<String> = runs
+                .into_iter()
+                .flat_map(|(start, end)| match end - start {
+                    0 => vec![names[start].to_owned()],
+                    1 => vec![names[start].to_owned(), names[end].to_owned()],
+                    _ => vec![format!("{}..{}", names[start], names[end])],
+                })
+                .collect();
+            write!(f, "{} ", runs.join(","))?;
+        }
+        let separator = if self.last { '~' } else { '-' };
+        write!(
+            f,
+            "{}-{}{separator}{} {}:{}:{}",
+            self.years, self.months, self.days, self.hours, self.minutes, self.seconds
+        )?;
+        if let Some(zone) = self.zone {
+            write!(f, " {}", zone.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// The times of `OnCalendar::times`
+#[derive(Debug, Clone)]
+pub struct OnCalendarTimes<'a> {
+    calendar: &'a OnCalendar,
+    zone: Zone,
+    from: chrono::DateTime<chrono::FixedOffset>,
+    local: Option<chrono::NaiveDateTime>,
+}
+
+impl Iterator for OnCalendarTimes<'_> {
+    type Item = chrono::DateTime<chrono::FixedOffset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let local = self.calendar.step(self.local?);
+            self.local = local;
+            let Some(date) = self.zone.from_local_datetime(&local?).earliest() else {
+                continue;
+            };
+            if date > self.from {
+                self.from = date;
+                return Some(date);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn oncalendar() {
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+    let times = |calendar: &str, from, n| {
+        OnCalendar::from_str(calendar)
+            .unwrap()
+            .times(date(from), utc)
+            .take(n)
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+    let normalized = |calendar: &str| OnCalendar::from_str(calendar).unwrap().to_string();
+
+    // 2025-01-03 is a Friday
+    assert_eq!(
+        times("Mon..Fri *-*-* 02:00", "2025-01-03T02:00:00+00:00", 2),
+        ["2025-01-06T02:00:00+00:00", "2025-01-07T02:00:00+00:00"]
+    );
+    assert_eq!(
+        times("*:0/20", "2025-01-03T02:00:00+00:00", 3),
+        [
+            "2025-01-03T02:20:00+00:00",
+            "2025-01-03T02:40:00+00:00",
+            "2025-01-03T03:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("*-02~01", "2025-01-03T00:00:00+00:00", 2),
+        ["2025-02-28T00:00:00+00:00", "2026-02-28T00:00:00+00:00"]
+    );
+    // January has 31 days, February 28 and April 30
+    assert_eq!(
+        times("*-*~07", "2025-01-01T00:00:00+00:00", 4),
+        [
+            "2025-01-25T00:00:00+00:00",
+            "2025-02-22T00:00:00+00:00",
+            "2025-03-25T00:00:00+00:00",
+            "2025-04-24T00:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("*-*~30", "2025-01-01T00:00:00+00:00", 4),
+        [
+            "2025-01-02T00:00:00+00:00",
+            "2025-02-01T00:00:00+00:00",
+            "2025-03-02T00:00:00+00:00",
+            "2025-04-01T00:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("*-02~31", "2025-01-01T00:00:00+00:00", 1),
+        ["2025-02-01T00:00:00+00:00"]
+    );
+    assert_eq!(
+        times("quarterly", "2025-01-03T00:00:00+00:00", 1),
+        ["2025-04-01T00:00:00+00:00"]
+    );
+    assert_eq!(
+        times(
+            "Sat..Sun 12-25 Europe/Berlin",
+            "2025-01-01T00:00:00+00:00",
+            1
+        ),
+        ["2027-12-25T00:00:00+01:00"]
+    );
+    assert_eq!(
+        times("2020-*-* 00:00", "2025-01-01T00:00:00+00:00", 1).len(),
+        0
+    );
+
+    assert_eq!(
+        normalized("Mon..Fri *-*-* 02:00"),
+        "Mon..Fri *-*-* 02:00:00"
+    );
+    assert_eq!(normalized("daily"), "*-*-* 00:00:00");
+    assert_eq!(
+        normalized("Mon,Tue,Thu 2025-1..3-1 *:0/15"),
+        "Mon,Tue,Thu 2025-01..03-01 *:00/15:00"
+    );
+    assert_eq!(normalized("*-02~01 UTC"), "*-02~01 00:00:00 UTC");
+
+    assert!(OnCalendar::from_str("Mon..Fri *-*-* 25:00").is_err());
+    assert!(OnCalendar::from_str("Someday").is_err());
+    assert!(OnCalendar::from_str("*-*-* 02:00 Mars/Base").is_err());
+}
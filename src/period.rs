@@ -0,0 +1,177 @@
+use chrono::Datelike;
+
+use crate::{WeekStart, Zone};
+
+/// A calendar period that a date is in, like the month of `2024-04-22`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// The midnight that starts the period of the wall clock time `local`
+    pub fn start_of_local(
+        self,
+        local: chrono::NaiveDateTime,
+        week_start: WeekStart,
+    ) -> Option<chrono::NaiveDateTime> {
+        let date = local.date();
+        let date = match self {
+            Period::Day => date,
+            Period::Week => week_start.start_of_week(date),
+            Period::Month => date.with_day(1)?,
+            Period::Quarter => date.with_day(1)?.with_month(date.month0() / 3 * 3 + 1)?,
+            Period::Year => date.with_ordinal(1)?,
+        };
+        Some(date.and_time(chrono::NaiveTime::MIN))
+    }
+
+    /// The midnight that starts the next period
+    pub fn next_local(self, start: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+        match self {
+            Period::Day => start.checked_add_days(chrono::Days::new(1)),
+            Period::Week => start.checked_add_days(chrono::Days::new(7)),
+            Period::Month => start.checked_add_months(chrono::Months::new(1)),
+            Period::Quarter => start.checked_add_months(chrono::Months::new(3)),
+            Period::Year => start.checked_add_months(chrono::Months::new(12)),
+        }
+    }
+
+    /// The first instant of the period of `date` in the wall clock of `zone`
+    pub fn start_of(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        week_start: WeekStart,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let local = zone.from_utc_datetime(&date.naive_utc()).naive_local();
+        zone.resolve_local(&self.start_of_local(local, week_start)?)
+    }
+
+    /// The last nanosecond of the period of `date` in the wall clock of `zone`
+    pub fn end_of(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        week_start: WeekStart,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let (_, next) = self.bounds(date, zone, week_start)?;
+        let end = next - chrono::TimeDelta::nanoseconds(1);
+        Some(zone.from_utc_datetime(&end.naive_utc()))
+    }
+
+    /// The first instant of the period of `date` and the first instant of the next one
+    pub fn bounds(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        week_start: WeekStart,
+    ) -> Option<(
+        chrono::DateTime<chrono::FixedOffset>,
+        chrono::DateTime<chrono::FixedOffset>,
+    )> {
+        let local = zone.from_utc_datetime(&date.naive_utc()).naive_local();
+        let start = self.start_of_local(local, week_start)?;
+        let next = self.next_local(start)?;
+        Some((zone.resolve_local(&start)?, zone.resolve_local(&next)?))
+    }
+
+    /// The part of the period of `date` that is before it, from 0 to 1,
+    /// a day with a DST change is 23 or 25 hours
+    pub fn progress(
+        self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        zone: Zone,
+        week_start: WeekStart,
+    ) -> Option<f64> {
+        let (start, next) = self.bounds(date, zone, week_start)?;
+        let seconds = |timedelta: chrono::TimeDelta| {
+            timedelta.num_seconds() as f64 + timedelta.subsec_nanos() as f64 / 1e9
+        };
+        Some(seconds(date - start) / seconds(next - start))
+    }
+}
+
+/// If `year` has February 29
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// The days of `month` in `year`, none when `month` is not from 1 to 12
+pub fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 if is_leap_year(year) => Some(29),
+        2 => Some(28),
+        _ => None,
+    }
+}
+
+pub fn days_in_year(year: i32) -> u32 {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn period() {
+    use chrono::TimeZone;
+
+    let zone = Zone::Named(chrono_tz::Europe::Bucharest);
+    let at = |offset: i32, y, m, d, h| {
+        chrono::FixedOffset::east_opt(offset * 3600)
+            .unwrap()
+            .with_ymd_and_hms(y, m, d, h, 0, 0)
+            .unwrap()
+    };
+    let date = at(3, 2024, 5, 15, 12);
+
+    assert_eq!(
+        Period::Quarter.start_of(date, zone, WeekStart::Mon),
+        Some(at(3, 2024, 4, 1, 0))
+    );
+    assert_eq!(
+        Period::Week.start_of(date, zone, WeekStart::Mon),
+        Some(at(3, 2024, 5, 13, 0))
+    );
+    assert_eq!(
+        Period::Week.start_of(date, zone, WeekStart::Sun),
+        Some(at(3, 2024, 5, 12, 0))
+    );
+
+    let end = Period::Quarter.end_of(date, zone, WeekStart::Mon).unwrap();
+    assert_eq!(
+        end.to_rfc3339_opts(chrono::SecondsFormat::Nanos, false),
+        "2024-06-30T23:59:59.999999999+03:00"
+    );
+    assert_eq!(
+        Period::Year.start_of(date, zone, WeekStart::Mon),
+        Some(at(2, 2024, 1, 1, 0))
+    );
+
+    assert_eq!(
+        Period::Day.progress(at(3, 2024, 5, 15, 6), zone, WeekStart::Mon),
+        Some(0.25)
+    );
+    // the day of the DST change has 23 hours
+    assert_eq!(
+        Period::Day.progress(at(3, 2024, 3, 31, 12), zone, WeekStart::Mon),
+        Some(11.0 / 23.0)
+    );
+
+    assert!(is_leap_year(2024));
+    assert!(is_leap_year(2000));
+    assert!(!is_leap_year(1900));
+    assert_eq!(days_in_month(2024, 2), Some(29));
+    assert_eq!(days_in_month(2023, 2), Some(28));
+    assert_eq!(days_in_month(2023, 13), None);
+    assert_eq!(days_in_year(2100), 365);
+}
@@ -0,0 +1,176 @@
+use chrono::Datelike;
+use ratatui::{
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind},
+        execute,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Alignment, Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::{month_weeks, weekdays, Lang, WeekStart};
+
+const HELP: &str = "arrows day/week  PgUp/PgDn month  t today  Enter pick  q cancel";
+
+/// Moves a day, a week or a month with the keys, `None` when the key doesn't move
+fn step(
+    day: chrono::NaiveDate,
+    today: chrono::NaiveDate,
+    key: KeyCode,
+) -> Option<chrono::NaiveDate> {
+    let month = chrono::Months::new(1);
+    match key {
+        KeyCode::Left | KeyCode::Char('h') => day.pred_opt(),
+        KeyCode::Right | KeyCode::Char('l') => day.succ_opt(),
+        KeyCode::Up | KeyCode::Char('k') => day.checked_sub_days(chrono::Days::new(7)),
+        KeyCode::Down | KeyCode::Char('j') => day.checked_add_days(chrono::Days::new(7)),
+        KeyCode::PageUp | KeyCode::Char('<') => day.checked_sub_months(month),
+        KeyCode::PageDown | KeyCode::Char('>') => day.checked_add_months(month),
+        KeyCode::Char('t') => Some(today),
+        _ => None,
+    }
+}
+
+/// The month of `selected` with its weekdays, `today` is underlined
+fn month_lines(
+    selected: chrono::NaiveDate,
+    today: chrono::NaiveDate,
+    week_start: WeekStart,
+    lang: Lang,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(format!(
+            "{} {}",
+            lang.month(selected.month()),
+            selected.year()
+        ))
+        .style(Style::new().add_modifier(Modifier::BOLD)),
+        Line::from(
+            weekdays(week_start)
+                .map(|weekday| {
+                    format!(
+                        "{:>3}",
+                        lang.weekday(weekday, true)
+                            .chars()
+                            .take(2)
+                            .collect::<String>()
+                    )
+                })
+                .concat(),
+        ),
+    ];
+
+    let weeks = month_weeks(selected.year(), selected.month(), week_start).unwrap_or_default();
+    for week in weeks {
+        let days = week.map(|day| {
+            if day.month() != selected.month() {
+                return Span::raw("   ");
+            }
+            let mut style = Style::new();
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if day == selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Span::styled(format!("{:>3}", day.day()), style)
+        });
+        lines.push(Line::from(days.to_vec()));
+    }
+    lines
+}
+
+/// Opens a month calendar on stderr, so stdout is free for the picked date,
+/// none when it is cancelled with `q` or `Esc`
+pub fn pick_date(
+    start: chrono::NaiveDate,
+    today: chrono::NaiveDate,
+    week_start: WeekStart,
+    lang: Lang,
+) -> std::io::Result<Option<chrono::NaiveDate>> {
+    terminal::enable_raw_mode()?;
+    execute!(std::io::stderr(), EnterAlternateScreen)?;
+    let res = run(start, today, week_start, lang);
+    _ = execute!(std::io::stderr(), LeaveAlternateScreen);
+    _ = terminal::disable_raw_mode();
+    res
+}
+
+fn run(
+    start: chrono::NaiveDate,
+    today: chrono::NaiveDate,
+    week_start: WeekStart,
+    lang: Lang,
+) -> std::io::Result<Option<chrono::NaiveDate>> {
+    let mut terminal =
+        ratatui::Terminal::new(ratatui::backend::CrosstermBackend::new(std::io::stderr()))?;
+    let mut selected = start;
+    loop {
+        terminal.draw(|frame| {
+            let lines = month_lines(selected, today, week_start, lang);
+            let [calendar, help] = Layout::vertical([
+                Constraint::Length(lines.len() as u16),
+                Constraint::Length(1),
+            ])
+            .flex(ratatui::layout::Flex::Center)
+            .spacing(1)
+            .areas(frame.area());
+            let [calendar] = Layout::horizontal([Constraint::Length(21)])
+                .flex(ratatui::layout::Flex::Center)
+                .areas(calendar);
+            frame.render_widget(Paragraph::new(lines).alignment(Alignment::Left), calendar);
+            frame.render_widget(
+                Paragraph::new(HELP)
+                    .alignment(Alignment::Center)
+                    .style(Style::new().add_modifier(Modifier::DIM)),
+                help,
+            );
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(Some(selected)),
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                return Ok(None)
+            }
+            code => selected = step(selected, today, code).unwrap_or(selected),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn pick() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let today = date(2025, 1, 31);
+
+    assert_eq!(step(today, today, KeyCode::Right), Some(date(2025, 2, 1)));
+    assert_eq!(
+        step(today, today, KeyCode::Char('k')),
+        Some(date(2025, 1, 24))
+    );
+    assert_eq!(
+        step(today, today, KeyCode::PageDown),
+        Some(date(2025, 2, 28))
+    );
+    assert_eq!(
+        step(date(2020, 5, 5), today, KeyCode::Char('t')),
+        Some(today)
+    );
+    assert_eq!(step(today, today, KeyCode::Char('x')), None);
+
+    let lines = month_lines(today, today, WeekStart::Mon, Lang::En);
+    assert_eq!(lines[0].to_string(), "January 2025");
+    assert_eq!(lines[1].to_string(), " Mo Tu We Th Fr Sa Su");
+    assert_eq!(lines[2].to_string(), "        1  2  3  4  5");
+    assert_eq!(lines.len(), 7);
+}
@@ -0,0 +1,51 @@
+use crate::duration::{from_nanos, to_nanos};
+
+/// A date between `start` and `end` with every nanosecond as likely,
+/// `end` is not in the range unless it is `start`
+pub fn random_date(
+    start: chrono::DateTime<chrono::FixedOffset>,
+    end: chrono::DateTime<chrono::FixedOffset>,
+    rng: &mut impl rand::Rng,
+) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let (start, end) = if end < start {
+        (end, start)
+    } else {
+        (start, end)
+    };
+    let range = to_nanos(end - start);
+    let offset = if range == 0 {
+        0
+    } else {
+        rng.random_range(0..range)
+    };
+
+    start.checked_add_signed(from_nanos(offset)?)
+}
+
+#[cfg(test)]
+#[test]
+fn random() {
+    use chrono::TimeZone;
+    use rand::SeedableRng;
+
+    let offset = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+    let start = offset.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let end = offset.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    for _ in 0..100 {
+        let date = random_date(start, end, &mut rng).unwrap();
+        assert!(start <= date && date < end);
+        let date = random_date(end, start, &mut rng).unwrap();
+        assert!(start <= date && date < end);
+    }
+    assert_eq!(random_date(start, start, &mut rng), Some(start));
+
+    let dates = |seed| {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        (0..3)
+            .map(|_| random_date(start, end, &mut rng))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(dates(1), dates(1));
+}
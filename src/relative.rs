@@ -0,0 +1,547 @@
+use chrono::Datelike;
+
+use crate::{Error, Zone};
+
+/// Parses a date that is relative to `now` like:
+///
+/// - `2 hours ago`, `an hour 30 minutes ago`
+/// - `in 3 days`, `in 1 week 2 days`
+/// - `next tuesday 14:00`, `last friday`, `this monday 9:30`, `sunday`
+/// - `now`, `today`, `yesterday`, `tomorrow 9:00` the days without a time are the midnight
+/// - `3rd thursday of november 2025`, `last friday of the month` like `parse_nth_weekday`
+/// - the relative items of GNU `date -d` like `next thursday + 3 days 2 hours`, `last month`,
+///   `fortnight ago` or `2025-01-01 +3 days`, see `parse_items`
+///
+/// Days, weeks, months and years are added in the wall clock of `zone`,
+/// hours, minutes and seconds are added to the instant
+pub fn parse_relative(
+    input: &str,
+    now: chrono::DateTime<chrono::Utc>,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let input = input.trim().to_lowercase();
+    let tokens = input.split_whitespace().collect::<Vec<_>>();
+    if let [_, _, "of", ..] = tokens.as_slice() {
+        return crate::parse_nth_weekday(&input, now, zone);
+    }
+    let now = zone.from_utc_datetime(&now.naive_utc());
+
+    match parse_phrase(&tokens, now, zone) {
+        Err(Error::InvalidRelative) => parse_items(&tokens, now, zone),
+        relative => relative,
+    }
+}
+
+/// The phrases of `parse_relative` that are not `parse_items`
+fn parse_phrase(
+    tokens: &[&str],
+    now: chrono::DateTime<chrono::FixedOffset>,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    match tokens {
+        ["now"] => Ok(now),
+        ["today", time @ ..] => resolve_day(now, 0, time, zone),
+        ["yesterday", time @ ..] => resolve_day(now, -1, time, zone),
+        ["tomorrow", time @ ..] => resolve_day(now, 1, time, zone),
+        ["in", amounts @ ..] => RelativeAmounts::parse(amounts)?.apply(now, 1, zone),
+        [amounts @ .., "ago"] => RelativeAmounts::parse(amounts)?.apply(now, -1, zone),
+        [direction @ ("next" | "last" | "this"), weekday, time @ ..] => {
+            let weekday = parse_weekday(weekday).ok_or(Error::InvalidRelative)?;
+            resolve_weekday(now, weekday, direction, time, zone)
+        }
+        [weekday, time @ ..] if parse_weekday(weekday).is_some() => {
+            let weekday = parse_weekday(weekday).ok_or(Error::InvalidRelative)?;
+            resolve_weekday(now, weekday, "this", time, zone)
+        }
+        _ => Err(Error::InvalidRelative),
+    }
+}
+
+/// The relative items of GNU `date -d` in any order:
+///
+/// - `3 days`, `+3 days`, `- 2 hours`, `a week`, `month` and `fortnight`, with `ago` after
+///   the one that is back
+/// - `next month`, `last year`, `this week` are 1, -1 and 0 of the unit
+/// - `thursday`, `next thursday`, `last thursday` like `parse_relative`
+/// - `today`, `yesterday`, `tomorrow`, `now` and a day like `2025-01-01`
+/// - a time like `14:00`, `9:30:15`, `9am` or `5:30pm`
+///
+/// The day is found first, a day without a time is the midnight, then the amounts are added
+fn parse_items(
+    tokens: &[&str],
+    now: chrono::DateTime<chrono::FixedOffset>,
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let mut amounts = RelativeAmounts::default();
+    let mut day = None;
+    let mut weekday = None;
+    let mut time = None;
+    // a day, a weekday or a time can only be once
+    fn set<T>(slot: &mut Option<T>, value: T) -> Result<(), Error> {
+        match slot.replace(value) {
+            Some(_) => Err(Error::InvalidRelative),
+            None => Ok(()),
+        }
+    }
+
+    let mut tokens = tokens
+        .iter()
+        .map(|token| token.trim_end_matches(','))
+        .filter(|token| !token.is_empty() && *token != "and")
+        .peekable();
+    while let Some(token) = tokens.next() {
+        let amount = match token {
+            "now" => continue,
+            "today" => {
+                set(&mut day, now.date_naive())?;
+                continue;
+            }
+            "yesterday" | "tomorrow" => {
+                let days = if token == "yesterday" { -1 } else { 1 };
+                set(&mut day, now.date_naive() + chrono::TimeDelta::days(days))?;
+                continue;
+            }
+            "next" | "last" | "this" => {
+                let next = tokens.next().ok_or(Error::InvalidRelative)?;
+                if let Some(next) = parse_weekday(next) {
+                    set(&mut weekday, (next, token))?;
+                    continue;
+                }
+                let amount = match token {
+                    "next" => 1,
+                    "last" => -1,
+                    _ => 0,
+                };
+                amounts.add(next, amount)?;
+                continue;
+            }
+            "+" | "-" => {
+                let number = tokens.next().ok_or(Error::InvalidRelative)?;
+                let number = number.parse::<i64>().map_err(|_| Error::InvalidRelative)?;
+                if token == "-" {
+                    number.checked_neg().ok_or(Error::InvalidRelative)?
+                } else {
+                    number
+                }
+            }
+            "a" | "an" => 1,
+            token => {
+                if let Ok(number) = token.parse::<i64>() {
+                    number
+                } else if let Some(next) = parse_weekday(token) {
+                    set(&mut weekday, (next, "this"))?;
+                    continue;
+                } else if let Some(next) = parse_clock_time(token) {
+                    set(&mut time, next)?;
+                    continue;
+                } else if let Ok(next) = chrono::NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+                    set(&mut day, next)?;
+                    continue;
+                } else {
+                    // a unit without a number is one of it, like `fortnight ago`
+                    let ago = tokens.next_if_eq(&"ago").is_some();
+                    amounts.add(token, if ago { -1 } else { 1 })?;
+                    continue;
+                }
+            }
+        };
+
+        let unit = tokens.next().ok_or(Error::InvalidRelative)?;
+        let ago = tokens.next_if_eq(&"ago").is_some();
+        let amount = if ago {
+            amount.checked_neg().ok_or(Error::InvalidRelative)?
+        } else {
+            amount
+        };
+        amounts.add(unit, amount)?;
+    }
+
+    let resets = day.is_some() || weekday.is_some();
+    let mut date = day.unwrap_or(now.date_naive());
+    if let Some((weekday, direction)) = weekday {
+        date += chrono::TimeDelta::days(weekday_days(date.weekday(), weekday, direction));
+    }
+    let time = time.unwrap_or(if resets {
+        chrono::NaiveTime::MIN
+    } else {
+        now.time()
+    });
+    let date = zone
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .ok_or(Error::AmbiguousDate)?;
+    amounts.apply(date, 1, zone)
+}
+
+/// Parses a time like `parse_time_of_day` or with `am` or `pm` like `9am` or `5:30pm`
+fn parse_clock_time(str: &str) -> Option<chrono::NaiveTime> {
+    let (time, pm) = match (str.strip_suffix("am"), str.strip_suffix("pm")) {
+        (Some(time), _) => (time, false),
+        (_, Some(time)) => (time, true),
+        _ => return parse_time_of_day(str),
+    };
+    let (hour, minute) = time.split_once(':').unwrap_or((time, "0"));
+    let hour = hour
+        .parse::<u32>()
+        .ok()
+        .filter(|hour| (1..=12).contains(hour))?;
+    let hour = hour % 12 + if pm { 12 } else { 0 };
+    chrono::NaiveTime::from_hms_opt(hour, minute.parse().ok()?, 0)
+}
+
+/// The amount of every unit in an expression like `2 hours 30 minutes`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RelativeAmounts {
+    pub years: i64,
+    pub months: i64,
+    pub weeks: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+}
+
+impl RelativeAmounts {
+    /// From pairs of number and unit, `a` and `an` are the same as `1`
+    pub fn parse(tokens: &[&str]) -> Result<Self, Error> {
+        if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+            return Err(Error::InvalidRelative);
+        }
+
+        let mut out = Self::default();
+        for pair in tokens.chunks(2) {
+            let amount = match pair[0] {
+                "a" | "an" => 1,
+                amount => amount.parse::<i64>().map_err(|_| Error::InvalidRelative)?,
+            };
+
+            out.add(pair[1].trim_end_matches(','), amount)?;
+        }
+
+        Ok(out)
+    }
+
+    /// Adds `amount` of the unit, an amount that doesn't fit is invalid
+    fn add(&mut self, unit: &str, amount: i64) -> Result<(), Error> {
+        let (value, factor) = self.unit(unit).ok_or(Error::InvalidRelative)?;
+        *value = amount
+            .checked_mul(factor)
+            .and_then(|amount| value.checked_add(amount))
+            .ok_or(Error::InvalidRelative)?;
+        Ok(())
+    }
+
+    /// The amount of the unit and how many of it the unit is, a fortnight is 14 days
+    fn unit(&mut self, unit: &str) -> Option<(&mut i64, i64)> {
+        match unit {
+            "year" | "years" | "y" => Some((&mut self.years, 1)),
+            "month" | "months" => Some((&mut self.months, 1)),
+            "fortnight" | "fortnights" => Some((&mut self.days, 14)),
+            "week" | "weeks" | "w" => Some((&mut self.weeks, 1)),
+            "day" | "days" | "d" => Some((&mut self.days, 1)),
+            "hour" | "hours" | "h" => Some((&mut self.hours, 1)),
+            "minute" | "minutes" | "min" | "mins" | "m" => Some((&mut self.minutes, 1)),
+            "second" | "seconds" | "sec" | "secs" | "s" => Some((&mut self.seconds, 1)),
+            _ => None,
+        }
+    }
+
+    /// Moves `date` forward when `sign` is `1` or backward when `sign` is `-1`
+    pub fn apply(
+        &self,
+        date: chrono::DateTime<chrono::FixedOffset>,
+        sign: i64,
+        zone: Zone,
+    ) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+        let mut local = date.naive_local();
+
+        // the amounts times their factors and the sign, an invalid date when it does not fit
+        let sum = |amounts: &[(i64, i64)]| {
+            amounts
+                .iter()
+                .try_fold(0i64, |sum, (amount, factor)| {
+                    sum.checked_add(amount.checked_mul(*factor)?)
+                })
+                .and_then(|sum| sum.checked_mul(sign))
+                .ok_or(Error::InvalidDate)
+        };
+
+        let months = sum(&[(self.years, 12), (self.months, 1)])?;
+        let forward = months >= 0;
+        let months = u32::try_from(months.unsigned_abs()).map_err(|_| Error::InvalidDate)?;
+        let months = chrono::Months::new(months);
+        local = if forward {
+            local.checked_add_months(months)
+        } else {
+            local.checked_sub_months(months)
+        }
+        .ok_or(Error::InvalidDate)?;
+
+        let days = sum(&[(self.weeks, 7), (self.days, 1)])?;
+        let days = chrono::TimeDelta::try_days(days).ok_or(Error::InvalidDate)?;
+        local = local.checked_add_signed(days).ok_or(Error::InvalidDate)?;
+
+        let date = zone
+            .from_local_datetime(&local)
+            .earliest()
+            .ok_or(Error::AmbiguousDate)?;
+
+        let seconds = sum(&[(self.hours, 3600), (self.minutes, 60), (self.seconds, 1)])?;
+        let seconds = chrono::TimeDelta::try_seconds(seconds).ok_or(Error::InvalidDate)?;
+        let date = date.checked_add_signed(seconds).ok_or(Error::InvalidDate)?;
+
+        Ok(zone.from_utc_datetime(&date.naive_utc()))
+    }
+}
+
+pub fn parse_weekday(str: &str) -> Option<chrono::Weekday> {
+    match str {
+        "monday" | "mon" => Some(chrono::Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(chrono::Weekday::Tue),
+        "wednesday" | "wed" => Some(chrono::Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(chrono::Weekday::Thu),
+        "friday" | "fri" => Some(chrono::Weekday::Fri),
+        "saturday" | "sat" => Some(chrono::Weekday::Sat),
+        "sunday" | "sun" => Some(chrono::Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Parses `14:00` or `9:30:15`
+pub fn parse_time_of_day(str: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(str, "%H:%M:%S")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(str, "%H:%M"))
+        .ok()
+}
+
+/// `next` is after today, `last` is before today and `this` is today or after
+fn resolve_weekday(
+    now: chrono::DateTime<chrono::FixedOffset>,
+    weekday: chrono::Weekday,
+    direction: &str,
+    time: &[&str],
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let days = weekday_days(now.weekday(), weekday, direction);
+    resolve_day(now, days, time, zone)
+}
+
+/// The days from `from` to the `next`, `last` or `this` `weekday`
+fn weekday_days(from: chrono::Weekday, weekday: chrono::Weekday, direction: &str) -> i64 {
+    let from = from.num_days_from_monday() as i64;
+    let to = weekday.num_days_from_monday() as i64;
+    match direction {
+        "next" => (to - from - 1).rem_euclid(7) + 1,
+        "last" => -((from - to - 1).rem_euclid(7) + 1),
+        _ => (to - from).rem_euclid(7),
+    }
+}
+
+/// The first `weekday` after the day of `date` in the wall clock of `zone`,
+/// or with `forward` false the last one before it, at `time` or the midnight
+pub fn weekday_occurrence(
+    date: chrono::DateTime<chrono::FixedOffset>,
+    weekday: chrono::Weekday,
+    forward: bool,
+    time: Option<chrono::NaiveTime>,
+    zone: Zone,
+) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let local = zone.from_utc_datetime(&date.naive_utc()).date_naive();
+    let days = if forward {
+        weekday.days_since(local.weekday()) as u64
+    } else {
+        local.weekday().days_since(weekday) as u64
+    };
+    // the same weekday is a whole week away
+    let days = chrono::Days::new(if days == 0 { 7 } else { days });
+    let local = if forward {
+        local.checked_add_days(days)?
+    } else {
+        local.checked_sub_days(days)?
+    };
+    zone.resolve_local(&local.and_time(time.unwrap_or(chrono::NaiveTime::MIN)))
+}
+
+/// The day that is `days` from the day of `now` at `time` or the midnight
+fn resolve_day(
+    now: chrono::DateTime<chrono::FixedOffset>,
+    days: i64,
+    time: &[&str],
+    zone: Zone,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, Error> {
+    let time = match time {
+        [] => chrono::NaiveTime::MIN,
+        [time] => parse_time_of_day(time).ok_or(Error::InvalidRelative)?,
+        _ => return Err(Error::InvalidRelative),
+    };
+
+    let date = now.date_naive() + chrono::TimeDelta::days(days);
+    let local = date.and_time(time);
+    zone.from_local_datetime(&local)
+        .earliest()
+        .ok_or(Error::AmbiguousDate)
+}
+
+#[cfg(test)]
+#[test]
+fn relative() {
+    use chrono::TimeZone;
+
+    // Friday
+    let now = chrono::Utc.with_ymd_and_hms(2024, 4, 26, 12, 0, 0).unwrap();
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(3 * 3600).unwrap());
+    let at = |y, m, d, h, min| {
+        chrono::FixedOffset::east_opt(3 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+    };
+
+    assert_eq!(
+        parse_relative("2 hours ago", now, zone),
+        Ok(at(2024, 4, 26, 13, 0))
+    );
+    assert_eq!(
+        parse_relative("in 1 month 3 days", now, zone),
+        Ok(at(2024, 5, 29, 15, 0))
+    );
+    assert_eq!(
+        parse_relative("next friday 9:30", now, zone),
+        Ok(at(2024, 5, 3, 9, 30))
+    );
+    assert_eq!(
+        parse_relative("this friday", now, zone),
+        Ok(at(2024, 4, 26, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("last monday", now, zone),
+        Ok(at(2024, 4, 22, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("2 potatoes", now, zone),
+        Err(Error::InvalidRelative)
+    );
+
+    // the days are from midnight in the zone
+    assert_eq!(parse_relative("now", now, zone), Ok(at(2024, 4, 26, 15, 0)));
+    assert_eq!(
+        parse_relative("today", now, zone),
+        Ok(at(2024, 4, 26, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("yesterday", now, zone),
+        Ok(at(2024, 4, 25, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("tomorrow", now, zone),
+        Ok(at(2024, 4, 27, 0, 0))
+    );
+    // it is already Saturday in the zone
+    let late = chrono::Utc
+        .with_ymd_and_hms(2024, 4, 26, 22, 30, 0)
+        .unwrap();
+    assert_eq!(
+        parse_relative("today", late, zone),
+        Ok(at(2024, 4, 27, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("yesterday", late, zone),
+        Ok(at(2024, 4, 26, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("tomorrow", late, zone),
+        Ok(at(2024, 4, 28, 0, 0))
+    );
+
+    // the items of GNU `date -d`
+    assert_eq!(
+        parse_relative("2 hours", now, zone),
+        Ok(at(2024, 4, 26, 17, 0))
+    );
+    assert_eq!(
+        parse_relative("next thursday + 3 days 2 hours", now, zone),
+        Ok(at(2024, 5, 5, 2, 0))
+    );
+    assert_eq!(
+        parse_relative("last month", now, zone),
+        Ok(at(2024, 3, 26, 15, 0))
+    );
+    assert_eq!(
+        parse_relative("fortnight ago", now, zone),
+        Ok(at(2024, 4, 12, 15, 0))
+    );
+    assert_eq!(
+        parse_relative("2024-01-01 +3 days", now, zone),
+        Ok(at(2024, 1, 4, 0, 0))
+    );
+    assert_eq!(
+        parse_relative("yesterday 5:30pm", now, zone),
+        Ok(at(2024, 4, 25, 17, 30))
+    );
+    assert_eq!(
+        parse_relative("1 day ago -1 hour", now, zone),
+        Ok(at(2024, 4, 25, 14, 0))
+    );
+    // the amounts that don't fit are errors
+    assert_eq!(
+        parse_relative("9999999999999999 hours ago", now, zone),
+        Err(Error::InvalidDate)
+    );
+    assert_eq!(
+        parse_relative("in 4294967296 months", now, zone),
+        Err(Error::InvalidDate)
+    );
+    assert_eq!(
+        parse_relative("in 9223372036854775807 years", now, zone),
+        Err(Error::InvalidDate)
+    );
+    assert_eq!(
+        parse_relative("700000000000000000 fortnights", now, zone),
+        Err(Error::InvalidRelative)
+    );
+    assert_eq!(
+        parse_relative("9223372036854775807 days 1 day", now, zone),
+        Err(Error::InvalidRelative)
+    );
+    assert_eq!(
+        parse_relative("- -9223372036854775808 days", now, zone),
+        Err(Error::InvalidRelative)
+    );
+    assert!(parse_relative("next", now, zone).is_err());
+    assert!(parse_relative("today tomorrow", now, zone).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn weekday_occurrences() {
+    use chrono::TimeZone;
+
+    let zone = Zone::Named(chrono_tz::Europe::Bucharest);
+    let at = |offset: i32, m, d, h, min| {
+        chrono::FixedOffset::east_opt(offset * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, m, d, h, min, 0)
+            .unwrap()
+    };
+    // Friday
+    let date = at(3, 4, 26, 12, 0);
+
+    assert_eq!(
+        weekday_occurrence(date, chrono::Weekday::Fri, true, None, zone),
+        Some(at(3, 5, 3, 0, 0))
+    );
+    assert_eq!(
+        weekday_occurrence(date, chrono::Weekday::Mon, false, None, zone),
+        Some(at(3, 4, 22, 0, 0))
+    );
+    let time = parse_time_of_day("9:30");
+    assert_eq!(
+        weekday_occurrence(date, chrono::Weekday::Sun, true, time, zone),
+        Some(at(3, 4, 28, 9, 30))
+    );
+    // across the start of the DST
+    assert_eq!(
+        weekday_occurrence(at(2, 3, 30, 12, 0), chrono::Weekday::Sun, true, time, zone),
+        Some(at(3, 3, 31, 9, 30))
+    );
+}
@@ -0,0 +1,119 @@
+use crate::{parse_date_auto, parse_date_prefix, Zone};
+
+/// A timestamp has to start and end at a word, so `2024` is not found in `12024`
+fn is_boundary(before: Option<char>, after: Option<char>) -> bool {
+    !(before.is_some_and(char::is_alphanumeric) && after.is_some_and(char::is_alphanumeric))
+}
+
+/// Replaces every timestamp in `line` that `format` parses with `f` of it,
+/// the rest of the line is left as it is
+///
+/// A timestamp without an offset is in `zone`, like `parse_date_lenient`
+pub fn rewrite_dates(
+    line: &str,
+    format: &[chrono::format::Item],
+    zone: Zone,
+    mut f: impl FnMut(chrono::DateTime<chrono::FixedOffset>) -> String,
+) -> String {
+    let mut res = String::with_capacity(line.len());
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        // the numbers of chrono can start with spaces, they are not part of the timestamp
+        if c.is_whitespace() || !is_boundary(line[..i].chars().next_back(), Some(c)) {
+            continue;
+        }
+        let Ok((date, rest)) = parse_date_prefix(format, &line[i..], zone) else {
+            continue;
+        };
+        let end = line.len() - rest.len();
+        if end == i || !is_boundary(line[..end].chars().next_back(), rest.chars().next()) {
+            continue;
+        }
+
+        res.push_str(&line[start..i]);
+        res.push_str(&f(date));
+        start = end;
+        while chars.next_if(|(i, _)| *i < end).is_some() {}
+    }
+    res.push_str(&line[start..]);
+    res
+}
+
+/// Like `rewrite_dates` but the timestamps are the matches of `regex`,
+/// a match is parsed with `format` or one of the `AUTO_FORMATS` and is left when none parse it
+pub fn rewrite_regex(
+    line: &str,
+    regex: &regex::Regex,
+    format: &[chrono::format::Item],
+    zone: Zone,
+    mut f: impl FnMut(chrono::DateTime<chrono::FixedOffset>) -> String,
+) -> String {
+    regex
+        .replace_all(line, |captures: &regex::Captures| {
+            let date = &captures[0];
+            match parse_date_auto(format, date, zone) {
+                Ok((date, _)) => f(date),
+                Err(_) => date.to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+#[test]
+fn rewrite() {
+    let items = |format| {
+        chrono::format::strftime::StrftimeItems::new(format)
+            .parse()
+            .unwrap()
+    };
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(3 * 3600).unwrap());
+    let utc = |date: chrono::DateTime<chrono::FixedOffset>| {
+        date.to_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    };
+
+    assert_eq!(
+        rewrite_dates(
+            "[2024-04-22 10:05:00] GET / took 2024ms",
+            &items("%Y-%m-%d %H:%M:%S"),
+            zone,
+            utc
+        ),
+        "[2024-04-22T07:05:00Z] GET / took 2024ms"
+    );
+    assert_eq!(
+        rewrite_dates(
+            "retry at 2024-04-22 11:00:00+00:00 or 2024-04-22 14:00:00+02:00.",
+            &items("%Y-%m-%d %H:%M:%S%:z"),
+            zone,
+            utc
+        ),
+        "retry at 2024-04-22T11:00:00Z or 2024-04-22T12:00:00Z."
+    );
+    assert_eq!(
+        rewrite_dates(
+            "id 12024-04-22 and 2024-04-22",
+            &items("%Y-%m-%d"),
+            zone,
+            utc
+        ),
+        "id 12024-04-22 and 2024-04-21T21:00:00Z"
+    );
+    assert_eq!(
+        rewrite_dates("no dates here: 2024-13-01", &items("%Y-%m-%d"), zone, utc),
+        "no dates here: 2024-13-01"
+    );
+
+    let regex = regex::Regex::new(r"\d{4}-\d\d-\d\dT[\d:]+").unwrap();
+    assert_eq!(
+        rewrite_regex(
+            "start=2024-04-22T10:05:00 end=2024-99-22T10:05:00",
+            &regex,
+            &items("%Y-%m-%dT%H:%M:%S"),
+            zone,
+            utc
+        ),
+        "start=2024-04-22T07:05:00Z end=2024-99-22T10:05:00"
+    );
+}
@@ -0,0 +1,745 @@
+use std::{collections::VecDeque, str::FromStr};
+
+use chrono::{Datelike, Timelike};
+
+use crate::{days_in_month, days_in_year, Error, Zone};
+
+/// How often the rule repeats, the `FREQ` of the rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Frequency {
+    Yearly,
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    Minutely,
+    Secondly,
+}
+
+/// The last occurrence, `UNTIL=20251231`, `UNTIL=20251231T235959` or `UNTIL=20251231T235959Z`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    Date(chrono::NaiveDate),
+    /// In the wall clock of the zone
+    Local(chrono::NaiveDateTime),
+    Utc(chrono::NaiveDateTime),
+}
+
+/// An RFC 5545 recurrence rule like `FREQ=MONTHLY;BYDAY=2TU`
+///
+/// Like the other calendars the occurrences are the ones that match the rule from the start,
+/// the start is not an occurrence when it doesn't match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<Until>,
+    seconds: Vec<u32>,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    /// The weekdays with the nth of the month or the year, 0 is every one
+    weekdays: Vec<(i32, chrono::Weekday)>,
+    month_days: Vec<i32>,
+    year_days: Vec<i32>,
+    weeks: Vec<i32>,
+    months: Vec<u32>,
+    positions: Vec<i32>,
+    week_start: chrono::Weekday,
+}
+
+fn parse_weekday(str: &str) -> Option<chrono::Weekday> {
+    match str {
+        "MO" => Some(chrono::Weekday::Mon),
+        "TU" => Some(chrono::Weekday::Tue),
+        "WE" => Some(chrono::Weekday::Wed),
+        "TH" => Some(chrono::Weekday::Thu),
+        "FR" => Some(chrono::Weekday::Fri),
+        "SA" => Some(chrono::Weekday::Sat),
+        "SU" => Some(chrono::Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The numbers of a `BY` part from `min` to `max`, or from `-max` to `-1` too when `signed`
+fn numbers(value: &str, name: &str, min: i32, max: i32, signed: bool) -> Result<Vec<i32>, Error> {
+    value
+        .split(',')
+        .map(|number| {
+            number
+                .parse::<i32>()
+                .ok()
+                .filter(|n| (min..=max).contains(n) || signed && (-max..=-1).contains(n))
+                .ok_or_else(|| {
+                    let range = if signed {
+                        format!("from {min} to {max} or from -{max} to -1")
+                    } else {
+                        format!("from {min} to {max}")
+                    };
+                    Error::InvalidRRule(format!("`{number}` is not a value of {name} {range}"))
+                })
+        })
+        .collect()
+}
+
+fn unsigned(value: &str, name: &str, min: i32, max: i32) -> Result<Vec<u32>, Error> {
+    let mut numbers: Vec<u32> = numbers(value, name, min, max, false)?
+        .into_iter()
+        .map(|n| n as u32)
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+impl FromStr for RRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = match s.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &s[6..],
+            _ => s,
+        };
+
+        let mut frequency = None;
+        let mut rule = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            seconds: Vec::new(),
+            minutes: Vec::new(),
+            hours: Vec::new(),
+            weekdays: Vec::new(),
+            month_days: Vec::new(),
+            year_days: Vec::new(),
+            weeks: Vec::new(),
+            months: Vec::new(),
+            positions: Vec::new(),
+            week_start: chrono::Weekday::Mon,
+        };
+        let mut seen = Vec::new();
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let Some((name, value)) = part.split_once('=') else {
+                return Err(Error::InvalidRRule(format!(
+                    "`{part}` is not like NAME=VALUE"
+                )));
+            };
+            let name = name.to_uppercase();
+            let value = value.to_uppercase();
+            if seen.contains(&name) {
+                return Err(Error::InvalidRRule(format!("{name} is there twice")));
+            }
+            match name.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "YEARLY" => Frequency::Yearly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "WEEKLY" => Frequency::Weekly,
+                        "DAILY" => Frequency::Daily,
+                        "HOURLY" => Frequency::Hourly,
+                        "MINUTELY" => Frequency::Minutely,
+                        "SECONDLY" => Frequency::Secondly,
+                        _ => {
+                            return Err(Error::InvalidRRule(format!(
+                                "`{value}` is not a frequency like YEARLY, MONTHLY, WEEKLY or DAILY"
+                            )))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| {
+                            Error::InvalidRRule(format!("`{value}` is not a positive interval"))
+                        })?
+                }
+                "COUNT" => {
+                    rule.count =
+                        Some(value.parse().map_err(|_| {
+                            Error::InvalidRRule(format!("`{value}` is not a count"))
+                        })?)
+                }
+                "UNTIL" => {
+                    let until = if let Some(utc) = value.strip_suffix('Z') {
+                        chrono::NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                            .ok()
+                            .map(Until::Utc)
+                    } else if value.contains('T') {
+                        chrono::NaiveDateTime::parse_from_str(&value, "%Y%m%dT%H%M%S")
+                            .ok()
+                            .map(Until::Local)
+                    } else {
+                        chrono::NaiveDate::parse_from_str(&value, "%Y%m%d")
+                            .ok()
+                            .map(Until::Date)
+                    };
+                    rule.until = Some(until.ok_or_else(|| {
+                        Error::InvalidRRule(format!(
+                            "`{value}` is not like 20251231, 20251231T235959 or 20251231T235959Z"
+                        ))
+                    })?)
+                }
+                "BYSECOND" => rule.seconds = unsigned(&value, &name, 0, 59)?,
+                "BYMINUTE" => rule.minutes = unsigned(&value, &name, 0, 59)?,
+                "BYHOUR" => rule.hours = unsigned(&value, &name, 0, 23)?,
+                "BYMONTH" => rule.months = unsigned(&value, &name, 1, 12)?,
+                "BYMONTHDAY" => rule.month_days = numbers(&value, &name, 1, 31, true)?,
+                "BYYEARDAY" => rule.year_days = numbers(&value, &name, 1, 366, true)?,
+                "BYWEEKNO" => rule.weeks = numbers(&value, &name, 1, 53, true)?,
+                "BYSETPOS" => rule.positions = numbers(&value, &name, 1, 366, true)?,
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let split = day.len().saturating_sub(2);
+                        let weekday = day.get(split..).and_then(parse_weekday);
+                        let nth = match &day[..split] {
+                            "" => Some(0),
+                            nth => nth
+                                .parse::<i32>()
+                                .ok()
+                                .filter(|nth| (1..=53).contains(&nth.abs())),
+                        };
+                        let (Some(nth), Some(weekday)) = (nth, weekday) else {
+                            return Err(Error::InvalidRRule(format!(
+                                "`{day}` is not a weekday like MO, 2TU or -1FR"
+                            )));
+                        };
+                        rule.weekdays.push((nth, weekday));
+                    }
+                }
+                "WKST" => {
+                    rule.week_start = parse_weekday(&value).ok_or_else(|| {
+                        Error::InvalidRRule(format!("`{value}` is not a weekday like MO or SU"))
+                    })?
+                }
+                _ => return Err(Error::InvalidRRule(format!("{name} is not a rule part"))),
+            }
+            seen.push(name);
+        }
+
+        let Some(frequency) = frequency else {
+            return Err(Error::InvalidRRule("it has no FREQ".to_owned()));
+        };
+        rule.frequency = frequency;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(Error::InvalidRRule(
+                "it has both COUNT and UNTIL".to_owned(),
+            ));
+        }
+        let nth = rule.weekdays.iter().any(|(nth, _)| *nth != 0);
+        if nth && !matches!(frequency, Frequency::Monthly | Frequency::Yearly) {
+            return Err(Error::InvalidRRule(
+                "a BYDAY like 2TU is only for MONTHLY or YEARLY".to_owned(),
+            ));
+        }
+        if !rule.month_days.is_empty() && frequency == Frequency::Weekly {
+            return Err(Error::InvalidRRule(
+                "BYMONTHDAY is not for WEEKLY".to_owned(),
+            ));
+        }
+        if !rule.year_days.is_empty()
+            && matches!(
+                frequency,
+                Frequency::Monthly | Frequency::Weekly | Frequency::Daily
+            )
+        {
+            return Err(Error::InvalidRRule(
+                "BYYEARDAY is not for MONTHLY, WEEKLY or DAILY".to_owned(),
+            ));
+        }
+        if !rule.weeks.is_empty() && frequency != Frequency::Yearly {
+            return Err(Error::InvalidRRule(
+                "BYWEEKNO is only for YEARLY".to_owned(),
+            ));
+        }
+        let by = seen
+            .iter()
+            .any(|name| name.starts_with("BY") && name != "BYSETPOS");
+        if !rule.positions.is_empty() && !by {
+            return Err(Error::InvalidRRule(
+                "BYSETPOS needs another BY part".to_owned(),
+            ));
+        }
+        Ok(rule)
+    }
+}
+
+/// The first day of the week 1 of `year`, the first week with 4 days of the year in it
+fn first_week(year: i32, week_start: chrono::Weekday) -> Option<chrono::NaiveDate> {
+    let fourth = chrono::NaiveDate::from_ymd_opt(year, 1, 4)?;
+    fourth.checked_sub_days(chrono::Days::new(
+        fourth.weekday().days_since(week_start) as u64
+    ))
+}
+
+/// The week of `date` and the number of weeks of its year, the year of a week
+/// is the one of its 4th day, so the end of December can be in the week 1
+fn week_of(date: chrono::NaiveDate, week_start: chrono::Weekday) -> Option<(i32, i32)> {
+    let start = date.checked_sub_days(chrono::Days::new(
+        date.weekday().days_since(week_start) as u64
+    ))?;
+    let year = start.checked_add_days(chrono::Days::new(3))?.year();
+    let first = first_week(year, week_start)?;
+    let next = first_week(year + 1, week_start)?;
+    let week = (start - first).num_days() / 7 + 1;
+    Some((week as i32, ((next - first).num_days() / 7) as i32))
+}
+
+/// `n` matches the 1 based `value` of `len` values, or `-n` from the end
+fn matches_nth(n: i32, value: u32, len: u32) -> bool {
+    if n > 0 {
+        n as u32 == value
+    } else {
+        (len + 1).checked_sub(n.unsigned_abs()) == Some(value)
+    }
+}
+
+impl RRule {
+    /// The rule with the parts that come from the start, like the day of the month of `MONTHLY`
+    fn with_start(&self, start: chrono::NaiveDateTime) -> Self {
+        let mut rule = self.clone();
+        let days = self.weeks.is_empty()
+            && self.year_days.is_empty()
+            && self.month_days.is_empty()
+            && self.weekdays.is_empty();
+        if days {
+            match self.frequency {
+                Frequency::Yearly => {
+                    if rule.months.is_empty() {
+                        rule.months = vec![start.month()];
+                    }
+                    rule.month_days = vec![start.day() as i32];
+                }
+                Frequency::Monthly => rule.month_days = vec![start.day() as i32],
+                Frequency::Weekly => rule.weekdays = vec![(0, start.weekday())],
+                _ => {}
+            }
+        }
+        if rule.hours.is_empty() && self.frequency < Frequency::Hourly {
+            rule.hours = vec![start.hour()];
+        }
+        if rule.minutes.is_empty() && self.frequency < Frequency::Minutely {
+            rule.minutes = vec![start.minute()];
+        }
+        if rule.seconds.is_empty() && self.frequency < Frequency::Secondly {
+            rule.seconds = vec![start.second()];
+        }
+        rule
+    }
+
+    fn matches_day(&self, date: chrono::NaiveDate) -> bool {
+        let year_len = days_in_year(date.year());
+        let month_len = days_in_month(date.year(), date.month()).unwrap_or(31);
+
+        if !self.months.is_empty() && !self.months.contains(&date.month()) {
+            return false;
+        }
+        if !self.weeks.is_empty() {
+            let Some((week, weeks)) = week_of(date, self.week_start) else {
+                return false;
+            };
+            if !self
+                .weeks
+                .iter()
+                .any(|n| matches_nth(*n, week as u32, weeks as u32))
+            {
+                return false;
+            }
+        }
+        if !self.year_days.is_empty()
+            && !self
+                .year_days
+                .iter()
+                .any(|n| matches_nth(*n, date.ordinal(), year_len))
+        {
+            return false;
+        }
+        if !self.month_days.is_empty()
+            && !self
+                .month_days
+                .iter()
+                .any(|n| matches_nth(*n, date.day(), month_len))
+        {
+            return false;
+        }
+        if !self.weekdays.is_empty() {
+            // the nth weekday of the month, or of the year for `YEARLY` without `BYMONTH`
+            let (index, len) = if self.frequency == Frequency::Yearly && self.months.is_empty() {
+                (date.ordinal0(), year_len)
+            } else {
+                (date.day0(), month_len)
+            };
+            let nth = index / 7 + 1;
+            let count = nth + (len - 1 - index) / 7;
+            let matches = self.weekdays.iter().any(|(n, weekday)| {
+                *weekday == date.weekday() && (*n == 0 || matches_nth(*n, nth, count))
+            });
+            if !matches {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The days of the period that starts at `period`
+    fn days(&self, period: chrono::NaiveDate) -> Vec<chrono::NaiveDate> {
+        let len = match self.frequency {
+            Frequency::Yearly => days_in_year(period.year()),
+            Frequency::Monthly => days_in_month(period.year(), period.month()).unwrap_or(31),
+            Frequency::Weekly => 7,
+            _ => 1,
+        };
+        period
+            .iter_days()
+            .take(len as usize)
+            .filter(|date| self.matches_day(*date))
+            .collect()
+    }
+
+    /// The times of a day of the period that starts at `period`
+    fn day_times(&self, period: chrono::NaiveDateTime) -> Vec<chrono::NaiveTime> {
+        let values = |by: &Vec<u32>, value: u32, unit: Frequency| {
+            if self.frequency < unit {
+                by.clone()
+            } else if by.is_empty() || by.contains(&value) {
+                vec![value]
+            } else {
+                Vec::new()
+            }
+        };
+        let hours = values(&self.hours, period.hour(), Frequency::Hourly);
+        let minutes = values(&self.minutes, period.minute(), Frequency::Minutely);
+        let seconds = values(&self.seconds, period.second(), Frequency::Secondly);
+
+        let mut times = Vec::new();
+        for hour in &hours {
+            for minute in &minutes {
+                for second in &seconds {
+                    times.extend(chrono::NaiveTime::from_hms_opt(*hour, *minute, *second));
+                }
+            }
+        }
+        times
+    }
+
+    /// The start of the period of `start`, like the first day of its month for `MONTHLY`
+    fn period(&self, start: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+        let date = start.date();
+        Some(match self.frequency {
+            Frequency::Yearly => date.with_ordinal(1)?.and_time(Default::default()),
+            Frequency::Monthly => date.with_day(1)?.and_time(Default::default()),
+            Frequency::Weekly => date
+                .checked_sub_days(chrono::Days::new(
+                    date.weekday().days_since(self.week_start) as u64,
+                ))?
+                .and_time(Default::default()),
+            Frequency::Daily => date.and_time(Default::default()),
+            Frequency::Hourly => start.with_minute(0)?.with_second(0)?,
+            Frequency::Minutely => start.with_second(0)?,
+            Frequency::Secondly => start,
+        })
+    }
+
+    /// The period after `period`, skipping the ones before `at_least`
+    fn next_period(
+        &self,
+        period: chrono::NaiveDateTime,
+        at_least: Option<chrono::NaiveDateTime>,
+    ) -> Option<chrono::NaiveDateTime> {
+        let step = match self.frequency {
+            Frequency::Yearly => {
+                return period.checked_add_months(chrono::Months::new(12 * self.interval))
+            }
+            Frequency::Monthly => {
+                return period.checked_add_months(chrono::Months::new(self.interval))
+            }
+            Frequency::Weekly => 7 * 86400,
+            Frequency::Daily => 86400,
+            Frequency::Hourly => 3600,
+            Frequency::Minutely => 60,
+            Frequency::Secondly => 1,
+        } * self.interval as i64;
+        let steps = match at_least {
+            Some(at_least) => ((at_least - period).num_seconds() + step - 1) / step,
+            None => 1,
+        };
+        period.checked_add_signed(chrono::TimeDelta::seconds(steps.max(1) * step))
+    }
+
+    /// The occurrences after `start` (or at it) in the wall clock of `zone`,
+    /// a wall clock time skipped by DST is moved an hour later like RFC 5545 says
+    pub fn times(&self, start: chrono::DateTime<chrono::FixedOffset>, zone: Zone) -> RRuleTimes {
+        let local = zone.from_utc_datetime(&start.naive_utc()).naive_local();
+        let local = local.with_nanosecond(0).unwrap_or(local);
+        RRuleTimes {
+            rule: self.with_start(local),
+            zone,
+            start: local,
+            period: self.period(local),
+            pending: VecDeque::new(),
+            found: 0,
+            last: None,
+            limit: local.year() + 400,
+        }
+    }
+}
+
+/// The occurrences of `RRule::times`
+#[derive(Debug, Clone)]
+pub struct RRuleTimes {
+    rule: RRule,
+    zone: Zone,
+    start: chrono::NaiveDateTime,
+    period: Option<chrono::NaiveDateTime>,
+    /// The occurrences of the last period that are not out yet
+    pending: VecDeque<chrono::NaiveDateTime>,
+    found: u32,
+    last: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// A rule like `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30` never matches,
+    /// so it ends after 400 years without occurrences
+    limit: i32,
+}
+
+impl RRuleTimes {
+    fn end(&mut self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.period = None;
+        self.pending.clear();
+        None
+    }
+
+    /// The occurrences of the period, or where the next period with occurrences can be
+    fn expand(
+        &self,
+        period: chrono::NaiveDateTime,
+    ) -> (Vec<chrono::NaiveDateTime>, Option<chrono::NaiveDateTime>) {
+        let rule = &self.rule;
+        let days = rule.days(period.date());
+        if days.is_empty() {
+            let next_day = (rule.frequency > Frequency::Daily)
+                .then(|| period.date().succ_opt())
+                .flatten()
+                .map(|day| day.and_time(Default::default()));
+            return (Vec::new(), next_day);
+        }
+        let times = rule.day_times(period);
+        if times.is_empty() {
+            let next = if rule.frequency > Frequency::Hourly
+                && !rule.hours.is_empty()
+                && !rule.hours.contains(&period.hour())
+            {
+                period.with_minute(0).and_then(|hour| {
+                    hour.with_second(0)?
+                        .checked_add_signed(chrono::TimeDelta::hours(1))
+                })
+            } else {
+                None
+            };
+            return (Vec::new(), next);
+        }
+
+        let mut occurrences: Vec<_> = days
+            .iter()
+            .flat_map(|day| times.iter().map(|time| day.and_time(*time)))
+            .collect();
+        if !rule.positions.is_empty() {
+            let len = occurrences.len() as i32;
+            let mut positioned: Vec<_> = rule
+                .positions
+                .iter()
+                .filter_map(|position| {
+                    let i = if *position > 0 {
+                        position - 1
+                    } else {
+                        len + position
+                    };
+                    occurrences.get(usize::try_from(i).ok()?).copied()
+                })
+                .collect();
+            positioned.sort_unstable();
+            positioned.dedup();
+            occurrences = positioned;
+        }
+        (occurrences, None)
+    }
+}
+
+impl Iterator for RRuleTimes {
+    type Item = chrono::DateTime<chrono::FixedOffset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(local) = self.pending.pop_front() {
+                if local < self.start {
+                    continue;
+                }
+                let ended = match self.rule.until {
+                    Some(Until::Date(until)) => local.date() > until,
+                    Some(Until::Local(until)) => local > until,
+                    _ => false,
+                };
+                if ended || self.rule.count.is_some_and(|count| self.found >= count) {
+                    return self.end();
+                }
+                let Some(date) = self.zone.resolve_local(&local) else {
+                    continue;
+                };
+                if let Some(Until::Utc(until)) = self.rule.until {
+                    if date.naive_utc() > until {
+                        return self.end();
+                    }
+                }
+                // a time moved by DST can be at the next occurrence
+                if self.last.is_some_and(|last| date <= last) {
+                    continue;
+                }
+                self.found += 1;
+                self.last = Some(date);
+                self.limit = local.year() + 400;
+                return Some(date);
+            }
+
+            let period = self.period?;
+            if period.year() > self.limit {
+                return self.end();
+            }
+            let (occurrences, skip) = self.expand(period);
+            self.pending.extend(occurrences);
+            self.period = self.rule.next_period(period, skip);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn rrule() {
+    let utc = Zone::Fixed(chrono::FixedOffset::east_opt(0).unwrap());
+    let date = |date: &str| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+    let times = |rule: &str, from, zone, n| {
+        RRule::from_str(rule)
+            .unwrap()
+            .times(date(from), zone)
+            .take(n)
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        times(
+            "FREQ=MONTHLY;BYDAY=2TU",
+            "2025-01-01T09:00:00+00:00",
+            utc,
+            3
+        ),
+        [
+            "2025-01-14T09:00:00+00:00",
+            "2025-02-11T09:00:00+00:00",
+            "2025-03-11T09:00:00+00:00"
+        ]
+    );
+    // the last workday of the month
+    assert_eq!(
+        times(
+            "RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            "2025-01-01T00:00:00+00:00",
+            utc,
+            3
+        ),
+        [
+            "2025-01-31T00:00:00+00:00",
+            "2025-02-28T00:00:00+00:00",
+            "2025-03-31T00:00:00+00:00"
+        ]
+    );
+    // the months without a 31st are skipped
+    assert_eq!(
+        times("FREQ=MONTHLY", "2025-01-31T08:00:00+00:00", utc, 3),
+        [
+            "2025-01-31T08:00:00+00:00",
+            "2025-03-31T08:00:00+00:00",
+            "2025-05-31T08:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times("FREQ=YEARLY", "2024-02-29T00:00:00+00:00", utc, 2),
+        ["2024-02-29T00:00:00+00:00", "2028-02-29T00:00:00+00:00"]
+    );
+    // the Monday of the week of the start is before it and the count ends it
+    assert_eq!(
+        times(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=4",
+            "2025-01-01T10:00:00+00:00",
+            utc,
+            10
+        ),
+        [
+            "2025-01-03T10:00:00+00:00",
+            "2025-01-13T10:00:00+00:00",
+            "2025-01-17T10:00:00+00:00",
+            "2025-01-27T10:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        times(
+            "FREQ=DAILY;UNTIL=20250103",
+            "2025-01-01T09:00:00+00:00",
+            utc,
+            10
+        )
+        .len(),
+        3
+    );
+    assert_eq!(
+        times(
+            "FREQ=HOURLY;INTERVAL=6;BYHOUR=0,12",
+            "2025-01-01T00:00:00+00:00",
+            utc,
+            3
+        ),
+        [
+            "2025-01-01T00:00:00+00:00",
+            "2025-01-01T12:00:00+00:00",
+            "2025-01-02T00:00:00+00:00"
+        ]
+    );
+    // the week 1 of 2026 starts on 2025-12-29 and the one of 2027 on 2027-01-04
+    assert_eq!(
+        times(
+            "FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO",
+            "2025-06-01T00:00:00+00:00",
+            utc,
+            2
+        ),
+        ["2025-12-29T00:00:00+00:00", "2027-01-04T00:00:00+00:00"]
+    );
+    assert_eq!(
+        times(
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+            "2025-01-01T00:00:00+00:00",
+            utc,
+            1
+        )
+        .len(),
+        0
+    );
+
+    // 03:30 is skipped on 2025-03-30 in Bucharest so it is at 04:30
+    let bucharest = Zone::Named(chrono_tz::Europe::Bucharest);
+    assert_eq!(
+        times("FREQ=DAILY", "2025-03-29T01:30:00+00:00", bucharest, 3),
+        [
+            "2025-03-29T03:30:00+02:00",
+            "2025-03-30T04:30:00+03:00",
+            "2025-03-31T03:30:00+03:00"
+        ]
+    );
+
+    assert!(RRule::from_str("BYDAY=MO").is_err());
+    assert!(RRule::from_str("FREQ=WEEKLY;BYDAY=2TU").is_err());
+    assert!(RRule::from_str("FREQ=DAILY;COUNT=2;UNTIL=20250101").is_err());
+    assert!(RRule::from_str("FREQ=YEARLY;BYMONTH=13").is_err());
+    assert!(RRule::from_str("FREQ=DAILY;BYSETPOS=1").is_err());
+    assert!(RRule::from_str("FREQ=DAILY;FREQ=WEEKLY").is_err());
+}
@@ -0,0 +1,110 @@
+use crate::{CalendarDuration, Zone};
+
+/// The dates from `start` to `end` every `step`, both ends are in it,
+/// it is empty when `step` moves away from `end`
+///
+/// The nth date is `start + n * step` and not the date before it + `step`,
+/// so the steps of `P1M` from January 31 are February 29 and March 31
+#[derive(Debug, Clone)]
+pub struct DateSeq {
+    start: chrono::DateTime<chrono::FixedOffset>,
+    end: chrono::DateTime<chrono::FixedOffset>,
+    step: CalendarDuration,
+    zone: Zone,
+    n: i64,
+    forward: bool,
+}
+
+impl DateSeq {
+    /// The steps are added in the wall clock of `zone` like `CalendarDuration::add_to_zoned`,
+    /// none when `step` doesn't move the date
+    pub fn new(
+        start: chrono::DateTime<chrono::FixedOffset>,
+        end: chrono::DateTime<chrono::FixedOffset>,
+        step: CalendarDuration,
+        zone: Zone,
+    ) -> Option<Self> {
+        let next = step.add_to_zoned(start, zone)?;
+        if next == start {
+            return None;
+        }
+
+        Some(Self {
+            start,
+            end,
+            step,
+            zone,
+            n: 0,
+            forward: next > start,
+        })
+    }
+}
+
+impl Iterator for DateSeq {
+    type Item = chrono::DateTime<chrono::FixedOffset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self
+            .step
+            .checked_mul(self.n)?
+            .add_to_zoned(self.start, self.zone)?;
+        let past_end = if self.forward {
+            date > self.end
+        } else {
+            date < self.end
+        };
+        if past_end {
+            return None;
+        }
+
+        self.n = self.n.checked_add(1)?;
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn date_seq() {
+    use chrono::TimeZone;
+    use std::str::FromStr;
+
+    let offset = chrono::FixedOffset::east_opt(0).unwrap();
+    let zone = Zone::Fixed(offset);
+    let at = |m, d, h| offset.with_ymd_and_hms(2024, m, d, h, 0, 0).unwrap();
+    let step = |step| CalendarDuration::from_str(step).unwrap();
+
+    let dates = DateSeq::new(at(1, 1, 0), at(1, 2, 0), step("PT6H"), zone)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        dates,
+        [
+            at(1, 1, 0),
+            at(1, 1, 6),
+            at(1, 1, 12),
+            at(1, 1, 18),
+            at(1, 2, 0)
+        ]
+    );
+
+    let dates = DateSeq::new(at(1, 31, 0), at(4, 30, 0), step("P1M"), zone)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        dates,
+        [at(1, 31, 0), at(2, 29, 0), at(3, 31, 0), at(4, 30, 0)]
+    );
+
+    let dates = DateSeq::new(at(1, 3, 0), at(1, 1, 12), step("-P1D"), zone)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(dates, [at(1, 3, 0), at(1, 2, 0)]);
+
+    assert_eq!(
+        DateSeq::new(at(1, 1, 0), at(1, 2, 0), step("-PT1H"), zone)
+            .unwrap()
+            .count(),
+        0
+    );
+    assert!(DateSeq::new(at(1, 1, 0), at(1, 2, 0), step("PT0S"), zone).is_none());
+}
@@ -0,0 +1,111 @@
+use crate::Error;
+
+type Utc = chrono::DateTime<chrono::Utc>;
+
+/// The times a stopwatch was started and stopped by the wall clock,
+/// so it keeps going when `tm` is not running and after a reboot
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Stopwatch {
+    /// The last one has no stop when it is running
+    pub runs: Vec<Run>,
+    pub laps: Vec<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Run {
+    pub start: Utc,
+    pub stop: Option<Utc>,
+}
+
+impl Stopwatch {
+    pub fn running(&self) -> bool {
+        self.runs.last().is_some_and(|run| run.stop.is_none())
+    }
+
+    /// Starts it, or continues it when it was stopped
+    pub fn start(&mut self, now: Utc) -> Result<(), Error> {
+        if self.running() {
+            return Err(Error::StopwatchRunning);
+        }
+        self.runs.push(Run {
+            start: now,
+            stop: None,
+        });
+        Ok(())
+    }
+
+    pub fn stop(&mut self, now: Utc) -> Result<(), Error> {
+        match self.runs.last_mut() {
+            Some(run) if run.stop.is_none() => {
+                run.stop = Some(now.max(run.start));
+                Ok(())
+            }
+            _ => Err(Error::StopwatchStopped),
+        }
+    }
+
+    pub fn lap(&mut self, now: Utc) -> Result<(), Error> {
+        if !self.running() {
+            return Err(Error::StopwatchStopped);
+        }
+        self.laps.push(now);
+        Ok(())
+    }
+
+    /// The time it was running until `at`
+    pub fn elapsed(&self, at: Utc) -> chrono::TimeDelta {
+        self.runs
+            .iter()
+            .map(|run| {
+                let stop = run.stop.map_or(at, |stop| stop.min(at));
+                (stop - run.start).max(chrono::TimeDelta::zero())
+            })
+            .sum()
+    }
+
+    /// The time of every lap, from the start or from the lap before it
+    pub fn splits(&self) -> Vec<chrono::TimeDelta> {
+        let mut last = chrono::TimeDelta::zero();
+        self.laps
+            .iter()
+            .map(|lap| {
+                let elapsed = self.elapsed(*lap);
+                let split = elapsed - last;
+                last = elapsed;
+                split
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn stopwatch() {
+    let at = |minutes| chrono::DateTime::UNIX_EPOCH + chrono::TimeDelta::minutes(minutes);
+
+    let mut stopwatch = Stopwatch::default();
+    assert_eq!(stopwatch.stop(at(0)), Err(Error::StopwatchStopped));
+    stopwatch.start(at(0)).unwrap();
+    assert_eq!(stopwatch.start(at(1)), Err(Error::StopwatchRunning));
+    stopwatch.lap(at(10)).unwrap();
+    stopwatch.stop(at(15)).unwrap();
+    assert_eq!(stopwatch.lap(at(20)), Err(Error::StopwatchStopped));
+
+    // the time while it was stopped is left out
+    stopwatch.start(at(30)).unwrap();
+    stopwatch.lap(at(40)).unwrap();
+    assert!(stopwatch.running());
+    assert_eq!(stopwatch.elapsed(at(45)), chrono::TimeDelta::minutes(30));
+    assert_eq!(stopwatch.elapsed(at(12)), chrono::TimeDelta::minutes(12));
+    assert_eq!(
+        stopwatch.splits(),
+        [
+            chrono::TimeDelta::minutes(10),
+            chrono::TimeDelta::minutes(15)
+        ]
+    );
+
+    let json = serde_json::to_string(&stopwatch).unwrap();
+    assert_eq!(serde_json::from_str::<Stopwatch>(&json).unwrap(), stopwatch);
+}
@@ -0,0 +1,25 @@
+use crate::Error;
+
+/// Reads the json file at `path`, when there is no file it is the default
+pub fn load_json<T: Default + serde::de::DeserializeOwned>(
+    path: &std::path::Path,
+) -> Result<T, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|err| invalid(path, &err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(invalid(path, &err)),
+    }
+}
+
+/// Writes `value` to the json file at `path`, the directories of it are created
+pub fn save_json<T: serde::Serialize>(path: &std::path::Path, value: &T) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| invalid(path, &err))?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|err| invalid(path, &err))?;
+    std::fs::write(path, json).map_err(|err| invalid(path, &err))
+}
+
+fn invalid(path: &std::path::Path, err: &dyn std::fmt::Display) -> Error {
+    Error::InvalidDataFile(format!("{}: {err}", path.display()))
+}
@@ -0,0 +1,314 @@
+use chrono::{Datelike, Timelike};
+
+use crate::{
+    duration::{from_nanos, to_nanos},
+    round_date, Error, Rounding, WeekStart, Zone,
+};
+
+/// The most buckets of `histogram`, so two dates years apart with a bucket of a second
+/// don't fill the memory
+pub const MAX_BUCKETS: usize = 100_000;
+
+/// How many of `dates` are in every bucket of `unit`, from the earliest bucket to the latest,
+/// the buckets without dates are there with 0
+///
+/// The buckets start at the multiples of `unit` in the wall clock of `zone` like `round_date`,
+/// so the buckets of `P1D` start at the midnight, a bucket that is out of range is
+/// `Error::InvalidDate`
+pub fn histogram(
+    dates: &[chrono::DateTime<chrono::FixedOffset>],
+    unit: chrono::TimeDelta,
+    zone: Zone,
+) -> Result<Vec<(chrono::DateTime<chrono::FixedOffset>, usize)>, Error> {
+    let bucket = |date| round_date(date, unit, Rounding::Floor, zone).ok_or(Error::InvalidDate);
+
+    let mut counts = std::collections::BTreeMap::new();
+    for date in dates {
+        *counts.entry(bucket(*date)?).or_insert(0) += 1;
+    }
+    let (Some(first), Some(last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Ok(Vec::new());
+    };
+    if to_nanos(*last - *first) / to_nanos(unit).max(1) >= MAX_BUCKETS as i128 {
+        return Err(Error::TooManyBuckets);
+    }
+
+    let mut buckets = Vec::new();
+    let mut at = *first;
+    while at <= *last {
+        buckets.push((at, counts.get(&at).copied().unwrap_or(0)));
+        let next = at.checked_add_signed(unit).ok_or(Error::InvalidDate)?;
+        // with DST the next multiple in the wall clock can be closer or farther
+        at = bucket(next)
+            .ok()
+            .filter(|bucket| *bucket > at)
+            .unwrap_or(next);
+    }
+    Ok(buckets)
+}
+
+/// The first and the last of the dates and the gaps between them, from the earliest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub count: usize,
+    pub first: chrono::DateTime<chrono::FixedOffset>,
+    pub last: chrono::DateTime<chrono::FixedOffset>,
+    pub span: chrono::TimeDelta,
+    /// None when there is only one date
+    pub gaps: Option<GapStats>,
+}
+
+/// The gaps between the dates that are next to each other when they are sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapStats {
+    pub min: chrono::TimeDelta,
+    pub mean: chrono::TimeDelta,
+    /// The mean of the two in the middle when there is an even number of gaps
+    pub median: chrono::TimeDelta,
+    pub max: chrono::TimeDelta,
+}
+
+impl StreamStats {
+    /// None when there are no dates
+    pub fn new(dates: &[chrono::DateTime<chrono::FixedOffset>]) -> Option<Self> {
+        let mut dates = dates.to_vec();
+        dates.sort();
+        let (first, last) = (*dates.first()?, *dates.last()?);
+
+        let mut gaps = dates
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect::<Vec<_>>();
+        gaps.sort();
+
+        Some(Self {
+            count: dates.len(),
+            first,
+            last,
+            span: last - first,
+            gaps: GapStats::new(&gaps),
+        })
+    }
+}
+
+impl GapStats {
+    /// From the sorted `gaps`
+    fn new(gaps: &[chrono::TimeDelta]) -> Option<Self> {
+        let (min, max) = (*gaps.first()?, *gaps.last()?);
+        let mean =
+            from_nanos(gaps.iter().copied().map(to_nanos).sum::<i128>() / gaps.len() as i128)?;
+        let middle = gaps.len() / 2;
+        let median = if gaps.len().is_multiple_of(2) {
+            from_nanos((to_nanos(gaps[middle - 1]) + to_nanos(gaps[middle])) / 2)?
+        } else {
+            gaps[middle]
+        };
+
+        Some(Self {
+            min,
+            mean,
+            median,
+            max,
+        })
+    }
+}
+
+/// Dates that are close to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    pub start: chrono::DateTime<chrono::FixedOffset>,
+    pub end: chrono::DateTime<chrono::FixedOffset>,
+    pub count: usize,
+}
+
+/// Splits the sorted dates in to sessions where a gap longer than `idle` starts a new one
+pub fn sessions(
+    dates: &[chrono::DateTime<chrono::FixedOffset>],
+    idle: chrono::TimeDelta,
+) -> Vec<Session> {
+    let mut dates = dates.to_vec();
+    dates.sort();
+
+    let mut sessions = Vec::<Session>::new();
+    for date in dates {
+        match sessions.last_mut() {
+            Some(session) if date - session.end <= idle => {
+                session.end = date;
+                session.count += 1;
+            }
+            _ => sessions.push(Session {
+                start: date,
+                end: date,
+                count: 1,
+            }),
+        }
+    }
+    sessions
+}
+
+/// What the dates are counted by in `group_by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// the hour of the day, from 0 to 23
+    Hour,
+    /// the day of the week, the ISO number from 1 for Monday to 7 for Sunday
+    Weekday,
+}
+
+/// How many of `dates` are in every hour of the day or every weekday in the wall clock of `zone`,
+/// every hour or weekday is there even with 0, the weekdays are from `week_start`
+pub fn group_by(
+    dates: &[chrono::DateTime<chrono::FixedOffset>],
+    by: GroupBy,
+    zone: Zone,
+    week_start: WeekStart,
+) -> Vec<(u32, usize)> {
+    let mut groups = match by {
+        GroupBy::Hour => (0..24).map(|hour| (hour, 0)).collect::<Vec<_>>(),
+        GroupBy::Weekday => {
+            let mut weekday = week_start.weekday();
+            let mut groups = Vec::new();
+            for _ in 0..7 {
+                groups.push((weekday.number_from_monday(), 0));
+                weekday = weekday.succ();
+            }
+            groups
+        }
+    };
+
+    for date in dates {
+        let date = zone.from_utc_datetime(&date.naive_utc());
+        let key = match by {
+            GroupBy::Hour => date.hour(),
+            GroupBy::Weekday => date.weekday().number_from_monday(),
+        };
+        if let Some((_, count)) = groups.iter_mut().find(|(group, _)| *group == key) {
+            *count += 1;
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+#[test]
+fn histogram_buckets() {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+    let at = |h, m| offset.with_ymd_and_hms(2024, 4, 22, h, m, 0).unwrap();
+    let zone = Zone::Fixed(offset);
+
+    let dates = [at(10, 5), at(10, 59), at(13, 0), at(10, 30)];
+    assert_eq!(
+        histogram(&dates, chrono::TimeDelta::hours(1), zone),
+        Ok(vec![
+            (at(10, 0), 3),
+            (at(11, 0), 0),
+            (at(12, 0), 0),
+            (at(13, 0), 1)
+        ])
+    );
+    assert_eq!(
+        histogram(&[], chrono::TimeDelta::hours(1), zone),
+        Ok(Vec::new())
+    );
+    let far = [
+        offset.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap(),
+        at(10, 0),
+    ];
+    assert_eq!(
+        histogram(&far, chrono::TimeDelta::seconds(1), zone),
+        Err(Error::TooManyBuckets)
+    );
+    assert_eq!(
+        histogram(&far, chrono::TimeDelta::days(1), zone).map(|buckets| buckets.len()),
+        Ok(19836)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn stream_stats() {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(0).unwrap();
+    let at = |m, s| offset.with_ymd_and_hms(2024, 4, 22, 10, m, s).unwrap();
+    let minutes = chrono::TimeDelta::minutes;
+
+    let stats = StreamStats::new(&[at(10, 0), at(0, 0), at(1, 0), at(4, 0)]).unwrap();
+    assert_eq!(stats.count, 4);
+    assert_eq!((stats.first, stats.last), (at(0, 0), at(10, 0)));
+    assert_eq!(stats.span, minutes(10));
+    assert_eq!(
+        stats.gaps,
+        Some(GapStats {
+            min: minutes(1),
+            mean: chrono::TimeDelta::seconds(200),
+            median: minutes(3),
+            max: minutes(6),
+        })
+    );
+
+    let stats = StreamStats::new(&[at(0, 0), at(0, 1), at(0, 4)]).unwrap();
+    assert_eq!(stats.gaps.unwrap().median, chrono::TimeDelta::seconds(2));
+    assert_eq!(stats.gaps.unwrap().mean, chrono::TimeDelta::seconds(2));
+
+    assert_eq!(StreamStats::new(&[at(0, 0)]).unwrap().gaps, None);
+    assert_eq!(StreamStats::new(&[]), None);
+}
+
+#[cfg(test)]
+#[test]
+fn idle_sessions() {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(0).unwrap();
+    let at = |h, m| offset.with_ymd_and_hms(2024, 4, 22, h, m, 0).unwrap();
+    let session = |start, end, count| Session { start, end, count };
+
+    let dates = [at(10, 0), at(12, 0), at(10, 20), at(10, 50), at(12, 31)];
+    assert_eq!(
+        sessions(&dates, chrono::TimeDelta::minutes(30)),
+        [
+            session(at(10, 0), at(10, 50), 3),
+            session(at(12, 0), at(12, 0), 1),
+            session(at(12, 31), at(12, 31), 1),
+        ]
+    );
+    assert_eq!(sessions(&[], chrono::TimeDelta::minutes(30)), []);
+}
+
+#[cfg(test)]
+#[test]
+fn group_by_hour_and_weekday() {
+    use chrono::TimeZone;
+
+    let utc = chrono::FixedOffset::east_opt(0).unwrap();
+    let zone = Zone::Fixed(chrono::FixedOffset::east_opt(3 * 3600).unwrap());
+    // Monday April 22 and Sunday April 28 2024
+    let dates = [
+        utc.with_ymd_and_hms(2024, 4, 22, 7, 5, 0).unwrap(),
+        utc.with_ymd_and_hms(2024, 4, 22, 7, 55, 0).unwrap(),
+        utc.with_ymd_and_hms(2024, 4, 28, 22, 0, 0).unwrap(),
+    ];
+
+    let hours = group_by(&dates, GroupBy::Hour, zone, WeekStart::Mon);
+    assert_eq!(hours.len(), 24);
+    assert_eq!(hours[10], (10, 2));
+    assert_eq!(hours[1], (1, 1));
+    assert_eq!(hours.iter().map(|(_, count)| count).sum::<usize>(), 3);
+
+    assert_eq!(
+        group_by(&dates, GroupBy::Weekday, zone, WeekStart::Sun),
+        [(7, 0), (1, 3), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0)]
+    );
+    assert_eq!(
+        group_by(
+            &dates[..2],
+            GroupBy::Weekday,
+            Zone::Fixed(utc),
+            WeekStart::Mon
+        )[0],
+        (1, 2)
+    );
+}
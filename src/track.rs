@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::Error;
+
+type Utc = chrono::DateTime<chrono::Utc>;
+
+/// The time that was spent on a project, the last one has no stop when it is tracked
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TrackEntry {
+    pub project: String,
+    pub start: Utc,
+    pub stop: Option<Utc>,
+}
+
+impl TrackEntry {
+    /// The time of it until `at`, the one that is tracked is until `at`
+    pub fn duration(&self, at: Utc) -> chrono::TimeDelta {
+        let stop = self.stop.map_or(at, |stop| stop.min(at));
+        (stop - self.start).max(chrono::TimeDelta::zero())
+    }
+}
+
+/// The entries of `track` in the order they were started
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TimeLog {
+    pub entries: Vec<TrackEntry>,
+}
+
+impl TimeLog {
+    /// The entry that is tracked now
+    pub fn current(&self) -> Option<&TrackEntry> {
+        self.entries.last().filter(|entry| entry.stop.is_none())
+    }
+
+    /// Starts tracking the `project`, the one that is tracked is stopped and returned
+    pub fn start(&mut self, project: &str, now: Utc) -> Result<Option<TrackEntry>, Error> {
+        let stopped = match self.current() {
+            Some(entry) if entry.project == project => {
+                return Err(Error::AlreadyTracking(project.to_owned()))
+            }
+            Some(_) => Some(self.stop(now)?.clone()),
+            None => None,
+        };
+        self.entries.push(TrackEntry {
+            project: project.to_owned(),
+            start: now,
+            stop: None,
+        });
+        Ok(stopped)
+    }
+
+    pub fn stop(&mut self, now: Utc) -> Result<&TrackEntry, Error> {
+        match self.entries.last_mut() {
+            Some(entry) if entry.stop.is_none() => {
+                entry.stop = Some(now.max(entry.start));
+                Ok(entry)
+            }
+            _ => Err(Error::NotTracking),
+        }
+    }
+
+    /// The time of every project from `since` to `until`, the entries are cut to them
+    pub fn totals(&self, since: Option<Utc>, until: Utc) -> BTreeMap<&str, chrono::TimeDelta> {
+        let mut totals = BTreeMap::new();
+        for entry in &self.entries {
+            let start = since.map_or(entry.start, |since| entry.start.max(since));
+            let stop = entry.stop.map_or(until, |stop| stop.min(until));
+            if start < stop {
+                *totals
+                    .entry(entry.project.as_str())
+                    .or_insert(chrono::TimeDelta::zero()) += stop - start;
+            }
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn track() {
+    let at = |minutes| chrono::DateTime::UNIX_EPOCH + chrono::TimeDelta::minutes(minutes);
+
+    let mut log = TimeLog::default();
+    assert_eq!(log.stop(at(0)), Err(Error::NotTracking));
+    assert_eq!(log.start("timeman", at(0)), Ok(None));
+    assert_eq!(
+        log.start("timeman", at(5)),
+        Err(Error::AlreadyTracking("timeman".to_owned()))
+    );
+
+    // starting an other project stops the one that is tracked
+    let stopped = log.start("docs", at(30)).unwrap().unwrap();
+    assert_eq!(stopped.project, "timeman");
+    assert_eq!(stopped.duration(at(60)), chrono::TimeDelta::minutes(30));
+    assert_eq!(log.current().unwrap().project, "docs");
+    log.stop(at(45)).unwrap();
+    assert_eq!(log.current(), None);
+
+    log.start("timeman", at(60)).unwrap();
+    let totals = log.totals(None, at(90));
+    assert_eq!(totals["timeman"], chrono::TimeDelta::minutes(60));
+    assert_eq!(totals["docs"], chrono::TimeDelta::minutes(15));
+
+    let totals = log.totals(Some(at(40)), at(70));
+    assert_eq!(totals["timeman"], chrono::TimeDelta::minutes(10));
+    assert_eq!(totals["docs"], chrono::TimeDelta::minutes(5));
+    assert_eq!(log.totals(Some(at(100)), at(90)).len(), 0);
+}
@@ -0,0 +1,56 @@
+use chrono::Datelike;
+
+/// The first day of the week, ISO 8601 weeks start on Monday and the US weeks on Sunday
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Mon,
+    Sun,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> chrono::Weekday {
+        match self {
+            WeekStart::Mon => chrono::Weekday::Mon,
+            WeekStart::Sun => chrono::Weekday::Sun,
+        }
+    }
+
+    /// The first day of the week of `date`
+    pub fn start_of_week(self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        date - chrono::Days::new(date.weekday().days_since(self.weekday()) as u64)
+    }
+
+    /// The week of the year where the week 1 has January 1,
+    /// with `Sun` it is the US week number
+    pub fn week_of_year(self, date: chrono::NaiveDate) -> u32 {
+        let first = date.with_ordinal0(0).unwrap_or(date);
+        (date.ordinal0() + first.weekday().days_since(self.weekday())) / 7 + 1
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn week() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    // Monday
+    assert_eq!(WeekStart::Mon.week_of_year(date(2024, 4, 22)), 17);
+    assert_eq!(WeekStart::Sun.week_of_year(date(2024, 4, 22)), 17);
+    // Sunday
+    assert_eq!(WeekStart::Mon.week_of_year(date(2024, 4, 21)), 16);
+    assert_eq!(WeekStart::Sun.week_of_year(date(2024, 4, 21)), 17);
+    assert_eq!(WeekStart::Sun.week_of_year(date(2023, 1, 1)), 1);
+    assert_eq!(WeekStart::Mon.week_of_year(date(2023, 1, 1)), 1);
+    assert_eq!(WeekStart::Mon.week_of_year(date(2023, 1, 2)), 2);
+
+    assert_eq!(
+        WeekStart::Mon.start_of_week(date(2024, 4, 21)),
+        date(2024, 4, 15)
+    );
+    assert_eq!(
+        WeekStart::Sun.start_of_week(date(2024, 4, 21)),
+        date(2024, 4, 21)
+    );
+}
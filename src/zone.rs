@@ -0,0 +1,268 @@
+use std::str::FromStr;
+
+use chrono::{Offset, TimeZone, Timelike};
+
+use crate::Error;
+
+/// Where a date should be shown, a fixed offset is always the same
+/// but a time zone can have a different offset for every instant (DST)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Local,
+    Fixed(chrono::FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+impl FromStr for Zone {
+    type Err = Error;
+
+    /// Accepts an offset like `+03:00` or a time zone name like `Europe/Bucharest`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(offset) = chrono::FixedOffset::from_str(s) {
+            return Ok(Self::Fixed(offset));
+        }
+
+        if let Ok(tz) = chrono_tz::Tz::from_str(s) {
+            return Ok(Self::Named(tz));
+        }
+
+        Err(Error::InvalidZone)
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zone::Local => write!(f, "local"),
+            Zone::Fixed(offset) => write!(f, "{offset}"),
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// A change of the offset of a zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// The first instant with the new offset
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub before: chrono::FixedOffset,
+    pub after: chrono::FixedOffset,
+}
+
+/// The transitions of a zone from an instant, see `Zone::transitions`
+#[derive(Debug, Clone)]
+pub struct Transitions {
+    zone: Zone,
+    at: chrono::NaiveDateTime,
+    end: chrono::NaiveDateTime,
+}
+
+impl Iterator for Transitions {
+    type Item = Transition;
+
+    /// Steps a day at a time until the offset changes and then bisects to the second
+    fn next(&mut self) -> Option<Self::Item> {
+        if matches!(self.zone, Zone::Fixed(_)) {
+            return None;
+        }
+
+        let offset = |at| self.zone.offset_from_utc_datetime(&at);
+        let before = offset(self.at);
+
+        while self.at < self.end {
+            let mut low = self.at;
+            let mut high = self.at.checked_add_signed(chrono::TimeDelta::days(1))?;
+            self.at = high;
+            if offset(high) == before {
+                continue;
+            }
+
+            while high - low > chrono::TimeDelta::seconds(1) {
+                let middle = low + chrono::TimeDelta::seconds((high - low).num_seconds() / 2);
+                if offset(middle) == before {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+
+            self.at = high;
+            return Some(Transition {
+                at: high.and_utc(),
+                before,
+                after: offset(high),
+            });
+        }
+
+        None
+    }
+}
+
+impl Zone {
+    /// The offset changes after `from` in the next `years`, a fixed offset has none
+    pub fn transitions(self, from: chrono::DateTime<chrono::Utc>, years: u32) -> Transitions {
+        // the transitions are on whole seconds
+        let from = from.naive_utc().with_nanosecond(0).unwrap_or_default();
+        Transitions {
+            zone: self,
+            at: from,
+            end: from
+                .checked_add_months(chrono::Months::new(years * 12))
+                .unwrap_or(chrono::NaiveDateTime::MAX),
+        }
+    }
+
+    /// If the daylight saving time is active at the `utc` instant
+    ///
+    /// The local zone doesn't know it, so it is when the offset is bigger
+    /// than the smallest one of January and July
+    pub fn is_dst(&self, utc: &chrono::NaiveDateTime) -> bool {
+        use chrono::Datelike;
+        use chrono_tz::OffsetComponents;
+
+        match self {
+            Zone::Local => {
+                let offset = |month| {
+                    let date = chrono::NaiveDate::from_ymd_opt(utc.year(), month, 1)
+                        .unwrap_or_default()
+                        .and_time(chrono::NaiveTime::MIN);
+                    self.offset_from_utc_datetime(&date).local_minus_utc()
+                };
+                let standard = offset(1).min(offset(7));
+                self.offset_from_utc_datetime(utc).local_minus_utc() > standard
+            }
+            Zone::Fixed(_) => false,
+            Zone::Named(tz) => !tz.offset_from_utc_datetime(utc).dst_offset().is_zero(),
+        }
+    }
+
+    /// The abbreviation like `EEST`, only the time zones have one
+    pub fn abbreviation(&self, utc: &chrono::NaiveDateTime) -> Option<String> {
+        use chrono_tz::OffsetName;
+
+        match self {
+            Zone::Named(tz) => Some(tz.offset_from_utc_datetime(utc).abbreviation().to_owned()),
+            _ => None,
+        }
+    }
+
+    /// The instant of the wall clock time `local`,
+    /// when it doesn't exist (skipped by DST) it is moved an hour later,
+    /// when it is there twice (repeated by DST) the earlier one is used
+    pub fn resolve_local(
+        &self,
+        local: &chrono::NaiveDateTime,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        match self.from_local_datetime(local) {
+            chrono::LocalResult::Single(date) => Some(date),
+            chrono::LocalResult::Ambiguous(earlier, _) => Some(earlier),
+            chrono::LocalResult::None => self
+                .from_local_datetime(&local.checked_add_signed(chrono::TimeDelta::hours(1))?)
+                .earliest(),
+        }
+    }
+
+    /// The offset that this zone has at the `utc` instant
+    pub fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> chrono::FixedOffset {
+        match self {
+            Zone::Local => chrono::Local.offset_from_utc_datetime(utc).fix(),
+            Zone::Fixed(offset) => *offset,
+            Zone::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+        }
+    }
+
+    pub fn from_utc_datetime(
+        &self,
+        utc: &chrono::NaiveDateTime,
+    ) -> chrono::DateTime<chrono::FixedOffset> {
+        self.offset_from_utc_datetime(utc).from_utc_datetime(utc)
+    }
+
+    pub fn from_local_datetime(
+        &self,
+        local: &chrono::NaiveDateTime,
+    ) -> chrono::LocalResult<chrono::DateTime<chrono::FixedOffset>> {
+        match self {
+            Zone::Local => chrono::Local
+                .from_local_datetime(local)
+                .map(|date| date.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(local),
+            Zone::Named(tz) => tz
+                .from_local_datetime(local)
+                .map(|date| date.fixed_offset()),
+        }
+    }
+}
+
+/// The time zone names that have `pattern` in them (in any case) with their offset at `utc`,
+/// when `pattern` is an offset like `+03:00` the zones that have that offset at `utc`
+pub fn search_zones(
+    pattern: &str,
+    utc: &chrono::NaiveDateTime,
+) -> Vec<(&'static str, chrono::FixedOffset)> {
+    let offset = chrono::FixedOffset::from_str(pattern).ok();
+    let pattern = pattern.to_lowercase();
+
+    chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| (tz.name(), Zone::Named(*tz).offset_from_utc_datetime(utc)))
+        .filter(|(name, zone_offset)| match offset {
+            Some(offset) => *zone_offset == offset,
+            None => name.to_lowercase().contains(&pattern),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn transitions() {
+    use chrono::TimeZone;
+
+    let zone = Zone::Named(chrono_tz::Europe::Bucharest);
+    let from = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let hours = |hours| chrono::FixedOffset::east_opt(hours * 3600).unwrap();
+
+    let transitions = zone.transitions(from, 1).collect::<Vec<_>>();
+    assert_eq!(
+        transitions,
+        [
+            Transition {
+                at: chrono::Utc.with_ymd_and_hms(2024, 3, 31, 1, 0, 0).unwrap(),
+                before: hours(2),
+                after: hours(3),
+            },
+            Transition {
+                at: chrono::Utc.with_ymd_and_hms(2024, 10, 27, 1, 0, 0).unwrap(),
+                before: hours(3),
+                after: hours(2),
+            },
+        ]
+    );
+    assert!(zone.is_dst(&transitions[0].at.naive_utc()));
+    assert!(!zone.is_dst(&from.naive_utc()));
+    assert_eq!(zone.abbreviation(&from.naive_utc()).as_deref(), Some("EET"));
+
+    assert_eq!(Zone::Fixed(hours(3)).transitions(from, 1).next(), None);
+}
+
+#[cfg(test)]
+#[test]
+fn search() {
+    use chrono::TimeZone;
+
+    let utc = chrono::Utc
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .unwrap()
+        .naive_utc();
+    let names = |pattern| {
+        search_zones(pattern, &utc)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names("bucharest"), ["Europe/Bucharest"]);
+    assert!(names("+02:00").contains(&"Europe/Bucharest"));
+    assert!(!names("+03:00").contains(&"Europe/Bucharest"));
+    assert!(names("nowhere").is_empty());
+}
@@ -0,0 +1,47 @@
+use std::process::Command;
+
+/// The exit code of `tm` with `args`
+fn exit_code(args: &[&str]) -> Option<i32> {
+    let output = Command::new(env!("CARGO_BIN_EXE_tm"))
+        .args(args)
+        .output()
+        .unwrap();
+    output.status.code()
+}
+
+#[test]
+fn answer_exit_codes() {
+    // "yes" is 0, "no" is 1 and an error is 2 or more
+    assert_eq!(exit_code(&["is-leap", "2024"]), Some(0));
+    assert_eq!(exit_code(&["is-leap", "2023"]), Some(1));
+    assert_eq!(
+        exit_code(&[
+            "-f",
+            "rfc3339",
+            "is-dst",
+            "2024-07-01T12:00:00+00:00",
+            "Europe/Berlin"
+        ]),
+        Some(0)
+    );
+    assert_eq!(
+        exit_code(&[
+            "-f",
+            "rfc3339",
+            "is-dst",
+            "2024-01-01T12:00:00+00:00",
+            "Europe/Berlin"
+        ]),
+        Some(1)
+    );
+    assert_eq!(
+        exit_code(&[
+            "-f",
+            "rfc3339",
+            "is-dst",
+            "2024-01-01T12:00:00+00:00",
+            "Mars/Base"
+        ]),
+        Some(2)
+    );
+}